@@ -28,51 +28,770 @@ pub struct TableInfo {
 pub struct JsonPathInfo {
     pub path: String,
     pub data_type: String,
+    /// Type déduit du contenu des chaînes (integer, number, boolean, date ou string)
+    pub inferred_type: Option<String>,
     pub sample: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Présence d'un chemin sur les lignes analysées d'un fichier NDJSON
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NdjsonPathStats {
+    pub path: String,
+    /// Lignes où le chemin a une valeur non nulle, une valeur null, ou est absent
+    pub present_lines: u32,
+    pub null_lines: u32,
+    pub missing_lines: u32,
+    /// Chemin avec une valeur non nulle sur toutes les lignes
+    pub in_all_lines: bool,
+    /// Part des lignes (0 à 1) où la valeur est null ou absente
+    pub null_ratio: f64,
+    pub missing_ratio: f64,
+}
+
+/// Étape de navigation d'un chemin JSON (segment, présence et type de la valeur atteinte)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PathProbeStep {
+    pub segment: String,
+    pub found: bool,
+    pub value_type: String,
+}
+
+/// Résultat de la résolution d'un chemin sur le premier objet de la racine
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PathProbe {
+    pub value: Option<JsonValue>,
+    pub trail: Vec<PathProbeStep>,
+    /// Raison de l'échec (segment introuvable ou valeur non navigable)
+    pub reason: Option<String>,
+}
+
+/// Valeur distincte d'un chemin JSON et son nombre d'occurrences
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ValueCount {
+    pub value: JsonValue,
+    pub count: u32,
+}
+
+/// Distribution des valeurs d'un chemin JSON sur l'ensemble des objets de la racine
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ValueHistogram {
+    pub total: u32,
+    /// Objets où le chemin est absent ou vaut null
+    pub null_count: u32,
+    pub distinct_count: u32,
+    /// Valeurs les plus fréquentes, par nombre d'occurrences décroissant
+    pub top_values: Vec<ValueCount>,
+}
+
+/// Champ présent dans les objets source mais absent du mapping
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnmappedField {
+    pub path: String,
+    /// Première valeur non nulle rencontrée (null si le champ l'est partout)
+    pub sample_value: JsonValue,
+    /// Nombre d'objets de l'échantillon contenant le champ
+    pub occurrences: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ImportConfig {
+    /// Chemin du fichier JSON, ou URL `http(s)://` d'une source distante
     pub json_path: String,
+    /// Délai d'attente (en secondes) et en-têtes de la requête pour une source distante
+    pub http_timeout_secs: Option<u64>,
+    pub http_headers: Option<HashMap<String, String>>,
+    /// Remplace les séquences UTF-8 invalides (U+FFFD) au lieu d'interrompre la lecture
+    pub lossy_utf8: Option<bool>,
+    /// Accepte les nombres non standard `NaN`, `Infinity` et `-Infinity` (importés comme null)
+    /// au lieu de rejeter le fichier; sans effet sur une source distante
+    pub lenient_numbers: Option<bool>,
+    /// Au-delà de cette taille (256 Mo par défaut), un fichier dont la racine est un tableau,
+    /// ou dont `json_root` désigne un tableau par des clés simples (`response.data.records[]`),
+    /// est lu en streaming plutôt qu'entièrement en mémoire
+    pub streaming_threshold_bytes: Option<u64>,
     pub db_path: String,
     pub json_root: String,
+    pub secondary_roots: Option<HashMap<String, String>>,
+    /// Nombre d'éléments de métadonnées retirés au début et à la fin de la racine
+    /// (en-tête/pied d'export), avant `offset` et `limit`
+    pub root_skip_first: Option<u32>,
+    pub root_skip_last: Option<u32>,
+    /// La racine est un dictionnaire (`{"sku123": {...}}`): chaque valeur devient une ligne,
+    /// et le chemin `@key` du mapping désigne la clé de l'entrée
+    pub map_root: Option<bool>,
+    /// Ignore (et compte comme `skipped`) les éléments de la racine qui ne sont pas des objets
+    pub skip_non_objects: Option<bool>,
+    /// Importe chaque entrée de l'objet racine comme une ligne (clé, valeur)
+    pub as_key_value: Option<bool>,
+    /// Colonnes cibles du mode clé/valeur (`key` et `value` par défaut)
+    pub key_column: Option<String>,
+    pub value_column: Option<String>,
+    /// Mode document: chaque élément de la racine est stocké en texte JSON dans cette colonne,
+    /// sans tenir compte du mapping. Le texte est toujours canonique (clés triées, sans espaces):
+    /// deux objets de même contenu donnent le même texte, quel que soit l'ordre des clés source
+    pub whole_document_column: Option<String>,
+    /// Complète le mapping avec les feuilles des objets, les chemins imbriqués étant aplatis
+    /// en noms de colonnes (`a.b.c` → `a_b_c`); le mapping explicite reste prioritaire.
+    /// Deux chemins donnant le même nom de colonne font échouer l'importation.
+    pub auto_flatten: Option<bool>,
+    /// Séparateur des segments aplatis (`_` par défaut)
+    pub flatten_separator: Option<String>,
+    /// Table large d'exploration: chaque chemin feuille devient une colonne TEXT nommée
+    /// d'après le chemin complet (`user.address.city`), créée à la volée avec la table.
+    /// Prioritaire sur `auto_flatten`; au plus 500 colonnes.
+    pub flatten_all: Option<bool>,
+    pub table_name: String,
+    /// Préfixe/suffixe ajoutés au nom de la table cible (ex: tables par client `t_acme_users`)
+    pub table_prefix: Option<String>,
+    pub table_suffix: Option<String>,
+    #[serde(default)]
+    pub mapping: HashMap<String, String>,
+    pub mapping_file: Option<String>,
+    /// Chemins de repli par colonne (colonne → chemins essayés dans l'ordre): la première
+    /// valeur non nulle est retenue, si le mapping principal n'en fournit pas pour la colonne
+    pub mapping_coalesce: Option<HashMap<String, Vec<String>>>,
+    /// Valeurs utilisées quand le chemin source est absent. Trois cas pour une colonne:
+    /// - absente du mapping et des défauts: non insérée, le DEFAULT de la table s'applique
+    /// - défaut `null`: NULL est inséré explicitement, le DEFAULT de la table est ignoré
+    /// - valeur mappée (ou défaut non null): cette valeur est insérée
+    pub defaults: Option<HashMap<String, JsonValue>>,
+    pub defaults_file: Option<String>,
+    pub forced: Option<HashMap<String, JsonValue>>,
+    pub forced_file: Option<String>,
+    pub dynamic: Option<HashMap<String, String>>,
+    pub dynamic_file: Option<String>,
+    pub expressions: Option<HashMap<String, String>>,
+    /// Étapes de transformation par colonne, appliquées de gauche à droite
+    pub transforms: Option<HashMap<String, Vec<Transform>>>,
+    pub epoch_columns: Option<HashMap<String, EpochResolution>>,
+    /// Politique par colonne en cas d'échec de conversion (`error` par défaut)
+    pub conversion_error_policy: Option<HashMap<String, ConversionErrorPolicy>>,
+    /// Refuse les entiers au-delà de la plage i64 (erreur de conversion, traitée selon
+    /// `conversion_error_policy`) au lieu de les stocker en réel avec perte de précision
+    pub strict_integers: Option<bool>,
+    /// Détecte les nombres qui perdent de la précision en réel (ex: 12345678901234567.89)
+    pub detect_precision_loss: Option<bool>,
+    /// Avec `detect_precision_loss`: avertissement (`warn`, par défaut) ou stockage en texte
+    pub precision_loss_policy: Option<PrecisionLossPolicy>,
+    /// Colonne → table de correspondance (ex: nom de pays remplacé par son identifiant)
+    pub lookups: Option<HashMap<String, Lookup>>,
+    /// Table plein texte (FTS5) alimentée avec chaque ligne insérée, dans la même transaction
+    pub fts_sync: Option<FtsSync>,
+    /// Champs texte délimités éclatés en lignes de tables enfants (ex: `"tags": "a,b,c"`)
+    pub split_into_child: Option<Vec<SplitIntoChild>>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+    pub trigger_policy: Option<TriggerPolicy>,
+    pub profile_after: Option<bool>,
+    pub replace_contents: Option<bool>,
+    pub reset_autoincrement: Option<bool>,
+    /// Refuse l'importation si la table contient déjà des lignes (premier chargement)
+    pub require_empty_table: Option<bool>,
+    pub duplicate_target_policy: Option<DuplicateTargetPolicy>,
+    pub normalize_keys: Option<KeyNormalization>,
+    pub return_inserted_keys: Option<bool>,
+    /// Colonne clé: les objets dont la clé existe déjà (en base ou plus tôt dans le fichier) sont ignorés
+    pub skip_existing_key: Option<String>,
+    /// Colonne numérotée dans l'ordre de la source (compteur croissant, indépendant du rowid);
+    /// la numérotation reprend après le maximum actuel de la colonne, ou à `sequence_start`
+    pub sequence_column: Option<String>,
+    pub sequence_start: Option<i64>,
+    /// Colonne alimentée par un compteur persistant (table `_json_counters`): la numérotation
+    /// reprend d'une importation à l'autre à la valeur suivant la dernière attribuée
+    pub persistent_counter_column: Option<String>,
+    /// Nom du compteur persistant (nom de la table par défaut)
+    pub counter_name: Option<String>,
+    /// Ajout seul avec historique: chaque objet est inséré avec la version suivante de sa clé
+    pub versioned_append: Option<VersionedAppend>,
+    /// Colonne alimentée par une clé déterministe dérivée de champs source, à la place d'une
+    /// valeur générée; avec `skip_existing_key` sur cette colonne, une réimportation
+    /// n'insère aucun doublon
+    pub derived_key: Option<DerivedKey>,
+    /// Valide la transaction tous les N objets. Un objet en échec est compté sans interrompre
+    /// le lot; un échec de la base (disque plein, verrou, commit refusé) annule le lot en cours,
+    /// les lots précédents restent en base et l'importation s'arrête
+    pub commit_every: Option<u32>,
+    /// Valide la transaction tous les N objets (si `commit_every` est absent) en enregistrant
+    /// avec chaque lot l'index du prochain élément dans la table `_json_checkpoints`
+    pub checkpoint_every: Option<u32>,
+    /// Nom du point de reprise (`table:fichier` par défaut)
+    pub checkpoint_name: Option<String>,
+    /// Reprend à l'élément suivant le dernier point de reprise validé (remplace `offset`)
+    pub resume: Option<bool>,
+    /// Début de la transaction d'écriture (`immediate` par défaut)
+    pub begin_mode: Option<BeginMode>,
+    /// Retente une fois, en fin d'importation, les objets dont l'insertion a échoué
+    pub retry_failed: Option<bool>,
+    /// Retire la clé primaire INTEGER (alias du rowid) des colonnes insérées: SQLite l'attribue
+    pub ignore_pk_mapping: Option<bool>,
+    /// Colonnes insérées choisies pour chaque objet: les champs absents sont omis de l'INSERT
+    /// et reçoivent leur valeur par défaut en base au lieu de NULL
+    pub per_row_columns: Option<bool>,
+    /// Taille de page et encodage d'une base nouvellement créée (sans effet sur une base existante)
+    pub page_size: Option<u32>,
+    pub encoding: Option<String>,
+    /// Cache de pages de la connexion d'importation (`PRAGMA cache_size`): nombre de pages si
+    /// positif, taille en Kio si négatif. Mémoire prise au processus pendant l'importation.
+    pub cache_size: Option<i64>,
+    /// Lecture par mémoire partagée (`PRAGMA mmap_size`, en octets): moins d'appels système sur
+    /// une grosse base, au prix d'un espace d'adressage réservé de cette taille
+    pub mmap_size: Option<u64>,
+    /// Signale dans `warnings` les chemins du mapping absents des objets, avec la raison
+    /// (`missing_path`, ou `array_crossed` si le chemin traverse un tableau sans `[]`)
+    pub warn_on_missing_path: Option<bool>,
+    /// Fichier NDJSON auquel chaque objet source en échec est ajouté dès son échec, tel que
+    /// lu dans la source et avec son erreur dans un champ `_error`, pour être corrigé puis
+    /// réimporté (rien n'est écrit en simulation)
+    pub reject_file: Option<String>,
+    pub audit: Option<bool>,
+    /// Enregistre le mapping chemin JSON → colonne dans la table `_json_column_provenance`
+    pub record_provenance: Option<bool>,
+    /// Extensions SQLite chargées avant l'importation (ex: fonctions utilisées par des colonnes
+    /// générées). Ce sont des bibliothèques natives exécutées avec les droits de l'application:
+    /// n'indiquer que des fichiers de confiance
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Fichier auquel chaque instantané de progression est ajouté sur une ligne JSON (NDJSON),
+    /// en plus des événements envoyés à l'interface
+    pub progress_log: Option<String>,
+    /// Script SQL exécuté dans sa propre transaction après le commit de l'importation
+    /// (ex: normalisation des données importées); son échec n'annule pas l'importation
+    pub post_sql: Option<String>,
+    pub dry_run: bool,
+    /// Type de simulation lorsque `dry_run` est actif (`count` par défaut)
+    pub dry_run_mode: Option<DryRunMode>,
+}
+
+/// Type de simulation d'une importation (`dry_run`)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DryRunMode {
+    /// Compte les objets sans accéder à la base
+    #[default]
+    Count,
+    /// Exécute toutes les insertions dans une transaction annulée à la fin: les contraintes
+    /// sont vérifiées et les erreurs par objet rapportées, sans rien conserver
+    ExecuteRollback,
+}
+
+/// Isolation des fichiers d'une importation multi-fichiers
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportIsolation {
+    /// Une seule transaction: l'échec de l'importation d'un fichier annule tous les autres
+    #[default]
+    AllOrNothing,
+    /// Une transaction par fichier: un fichier en échec est signalé dans `file_outcomes`
+    /// sans annuler les fichiers déjà importés, et l'importation continue (`per_table`
+    /// accepté comme synonyme, l'importation multiple portant sur des fichiers)
+    #[serde(alias = "per_table")]
+    PerFile,
+}
+
+/// Comportement lorsque plusieurs chemins JSON du mapping ciblent la même colonne
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateTargetPolicy {
+    Error,
+    /// Première valeur non nulle, dans l'ordre alphabétique des chemins
+    #[default]
+    Coalesce,
+}
+
+/// Normalisation de la casse des clés JSON avant l'extraction
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyNormalization {
+    #[default]
+    None,
+    Lower,
+    Snake,
+}
+
+/// Étape de transformation d'une valeur texte (`"trim"`, `"upper"`, `{"truncate": 10}`...)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    Trim,
+    Upper,
+    Lower,
+    /// Conserve au plus N caractères
+    Truncate(usize),
+    /// Remplace une chaîne vide par null
+    NullIfEmpty,
+}
+
+/// Résolution d'un timestamp Unix stocké dans une colonne INTEGER
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum EpochResolution {
+    #[serde(rename = "s")]
+    Seconds,
+    #[serde(rename = "ms")]
+    Milliseconds,
+}
+
+/// Correspondance d'une colonne via une table de référence: la valeur mappée est remplacée
+/// par `SELECT return_column FROM table WHERE match_column = ?`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Lookup {
+    pub table: String,
+    pub match_column: String,
+    pub return_column: String,
+    /// Valeur introuvable: objet en échec (`error`, par défaut) ou NULL (`null`)
+    #[serde(default)]
+    pub on_missing: LookupMissingPolicy,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LookupMissingPolicy {
+    #[default]
+    Error,
+    Null,
+}
+
+/// Table plein texte compagnon: `content_columns` (colonnes de la table cible, du même nom
+/// dans la table FTS) y sont copiées avec le rowid de la ligne insérée. Les lignes supprimées
+/// par `replace_contents` ne sont pas retirées de la table FTS.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FtsSync {
+    pub fts_table: String,
+    pub content_columns: Vec<String>,
+}
+
+/// Éclatement d'un champ texte délimité en lignes d'une table enfant: chaque élément (sans
+/// espaces autour, les éléments vides sont ignorés) est inséré dans `value_column`, avec le
+/// rowid de la ligne parente dans `parent_fk_column`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SplitIntoChild {
+    pub source_path: String,
+    pub delimiter: String,
+    pub child_table: String,
+    pub value_column: String,
+    pub parent_fk_column: String,
+}
+
+/// Import en ajout seul versionné: `version_column` reçoit la plus grande version existante
+/// pour la valeur de `key_column` (clé métier), plus un; les lignes précédentes sont conservées
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VersionedAppend {
+    pub key_column: String,
+    pub version_column: String,
+}
+
+/// Clé déterministe calculée pour chaque objet à partir de champs source (clé naturelle
+/// composite), pour que les réimportations produisent les mêmes clés
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DerivedKey {
+    pub column: String,
+    pub source_paths: Vec<String>,
+    #[serde(default)]
+    pub strategy: DerivedKeyStrategy,
+}
+
+/// Calcul d'une clé dérivée à partir des valeurs source
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DerivedKeyStrategy {
+    /// Valeurs jointes par `|` (ex: `FR|2024|42`)
+    #[default]
+    Concat,
+    /// Empreinte FNV-1a 64 bits en hexadécimal (16 caractères)
+    Hash,
+}
+
+/// Mode de début des transactions d'écriture (`BEGIN DEFERRED`, `IMMEDIATE` ou `EXCLUSIVE`)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BeginMode {
+    /// Verrou d'écriture pris à la première écriture: peut échouer en cours d'importation
+    /// (SQLITE_BUSY) si une autre connexion écrit entre-temps
+    Deferred,
+    /// Verrou d'écriture pris dès le début: échoue immédiatement en cas de concurrence
+    #[default]
+    Immediate,
+    /// Comme `immediate`, en bloquant aussi les lectures des autres connexions
+    Exclusive,
+}
+
+/// Comportement lorsqu'une valeur ne peut pas être convertie pour sa colonne
+/// (transformation, date epoch ou type SQLite en échec)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversionErrorPolicy {
+    /// L'objet est en échec
+    #[default]
+    Error,
+    /// La colonne reçoit NULL
+    Null,
+    /// La colonne reçoit sa valeur de `defaults` (obligatoire avec cette politique)
+    Default,
+}
+
+/// Traitement des nombres décimaux dont la forme écrite ne survit pas au stockage en REAL (f64)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PrecisionLossPolicy {
+    /// Stockage en réel, avec un avertissement par colonne dans le résumé
+    #[default]
+    Warn,
+    /// Stockage de la forme écrite exacte en texte
+    Text,
+}
+
+/// Comportement à adopter lorsque la table cible possède des triggers
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerPolicy {
+    Allow,
+    #[default]
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CsvImportConfig {
+    pub csv_path: String,
+    pub db_path: String,
     pub table_name: String,
+    pub table_prefix: Option<String>,
+    pub table_suffix: Option<String>,
+    pub delimiter: Option<String>,
+    pub has_headers: Option<bool>,
     pub mapping: HashMap<String, String>,
+    /// Comme pour `ImportConfig`: un défaut `null` insère NULL au lieu du DEFAULT de la table
     pub defaults: Option<HashMap<String, JsonValue>>,
     pub forced: Option<HashMap<String, JsonValue>>,
     pub dynamic: Option<HashMap<String, String>>,
+    pub expressions: Option<HashMap<String, String>>,
+    /// Étapes de transformation par colonne, appliquées de gauche à droite
+    pub transforms: Option<HashMap<String, Vec<Transform>>>,
+    pub epoch_columns: Option<HashMap<String, EpochResolution>>,
+    /// Politique par colonne en cas d'échec de conversion (`error` par défaut)
+    pub conversion_error_policy: Option<HashMap<String, ConversionErrorPolicy>>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
+    pub trigger_policy: Option<TriggerPolicy>,
+    pub profile_after: Option<bool>,
+    pub replace_contents: Option<bool>,
+    pub reset_autoincrement: Option<bool>,
+    /// Refuse l'importation si la table contient déjà des lignes (premier chargement)
+    pub require_empty_table: Option<bool>,
+    pub duplicate_target_policy: Option<DuplicateTargetPolicy>,
+    pub normalize_keys: Option<KeyNormalization>,
+    pub return_inserted_keys: Option<bool>,
+    /// Colonne clé: les objets dont la clé existe déjà (en base ou plus tôt dans le fichier) sont ignorés
+    pub skip_existing_key: Option<String>,
+    /// Valide la transaction tous les N objets. Un objet en échec est compté sans interrompre
+    /// le lot; un échec de la base (disque plein, verrou, commit refusé) annule le lot en cours,
+    /// les lots précédents restent en base et l'importation s'arrête
+    pub commit_every: Option<u32>,
+    /// Début de la transaction d'écriture (`immediate` par défaut)
+    pub begin_mode: Option<BeginMode>,
+    /// Retente une fois, en fin d'importation, les objets dont l'insertion a échoué
+    pub retry_failed: Option<bool>,
+    /// Retire la clé primaire INTEGER (alias du rowid) des colonnes insérées: SQLite l'attribue
+    pub ignore_pk_mapping: Option<bool>,
+    /// Taille de page et encodage d'une base nouvellement créée (sans effet sur une base existante)
+    pub page_size: Option<u32>,
+    pub encoding: Option<String>,
+    /// Cache de pages de la connexion d'importation (`PRAGMA cache_size`): nombre de pages si
+    /// positif, taille en Kio si négatif. Mémoire prise au processus pendant l'importation.
+    pub cache_size: Option<i64>,
+    /// Lecture par mémoire partagée (`PRAGMA mmap_size`, en octets): moins d'appels système sur
+    /// une grosse base, au prix d'un espace d'adressage réservé de cette taille
+    pub mmap_size: Option<u64>,
+    /// Signale dans `warnings` les chemins du mapping absents des objets, avec la raison
+    /// (`missing_path`, ou `array_crossed` si le chemin traverse un tableau sans `[]`)
+    pub warn_on_missing_path: Option<bool>,
+    pub audit: Option<bool>,
+    /// Enregistre le mapping chemin JSON → colonne dans la table `_json_column_provenance`
+    pub record_provenance: Option<bool>,
+    pub dry_run: bool,
+    pub dry_run_mode: Option<DryRunMode>,
+}
+
+/// Création d'une table à partir de la structure des objets JSON
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTableConfig {
+    pub json_path: String,
+    pub db_path: String,
+    pub json_root: String,
+    pub table_name: String,
+    /// Chemin JSON → colonne; vide, les clés de premier niveau deviennent les colonnes
+    #[serde(default)]
+    pub mapping: HashMap<String, String>,
+    /// Types imposés pour certaines colonnes (ex: `{"code": "TEXT"}`), prioritaires sur l'inférence
+    pub column_types: Option<HashMap<String, String>>,
+    /// Retourne le DDL sans créer la table
     pub dry_run: bool,
 }
 
+/// Importation proposée pour un fichier JSON, à relire avant de créer la table et d'importer
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportProposal {
+    pub json_root: String,
+    /// Objets trouvés à la racine, et nombre d'objets utilisés pour l'inférence
+    pub object_count: u32,
+    pub sample_size: u32,
+    pub table_name: String,
+    pub ddl: String,
+    /// Chemin JSON → colonne, utilisable tel quel dans `ImportConfig`
+    pub mapping: HashMap<String, String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateConfig {
     pub json_path: String,
     pub db_path: String,
     pub json_root: String,
     pub table_name: String,
+    /// Colonne de correspondance; `rowid` (ou `_rowid_`) fait correspondre les lignes
+    /// directement sur leur rowid SQLite, sans passer par un index secondaire
     pub key_column: String,
     pub update_columns: Vec<String>,
     pub mapping: HashMap<String, String>,
+    pub skip_missing_source: Option<bool>,
+    pub null_sets_column: Option<bool>,
+    /// Ne met à jour que les colonnes dont la valeur diffère de celle en base
+    pub minimal_update: Option<bool>,
+    /// Concurrence optimiste: la ligne n'est mise à jour que si cette colonne (ex: `version`,
+    /// `updated_at`) vaut encore la valeur lue au chemin `expected_version_path`
+    pub expected_version_column: Option<String>,
+    pub expected_version_path: Option<String>,
+    /// Début de la transaction d'écriture (`immediate` par défaut)
+    pub begin_mode: Option<BeginMode>,
     pub dry_run: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Étape d'une opération longue, indépendante de la langue (le détail reste dans `status`)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportPhase {
+    #[default]
+    Preparing,
+    AnalyzingSchema,
+    Inserting,
+    Exporting,
+    Committing,
+    Done,
+    Cancelled,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ImportProgress {
     pub total: u32,
     pub processed: u32,
     pub succeeded: u32,
     pub failed: u32,
+    #[serde(default)]
+    pub no_change: u32,
+    /// Mises à jour refusées: la ligne a changé depuis la lecture de la source
+    #[serde(default)]
+    pub conflicts: u32,
+    #[serde(default)]
+    pub phase: ImportPhase,
     pub status: String,
 }
 
+/// Avancement de la lecture d'un fichier source, avant le début de l'importation: octets lus
+/// pour une lecture complète, éléments parsés pour une lecture en streaming
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ParseProgress {
+    pub path: String,
+    pub bytes_read: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub elements_parsed: Option<u64>,
+}
+
+/// Erreur de syntaxe JSON avec sa position dans le fichier (ligne/colonne à partir de 1)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JsonParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Erreur rencontrée sur un objet source lors d'une importation ou d'une mise à jour
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RowError {
+    pub index: usize,
+    /// Code de résultat SQLite principal (ex: 19 pour SQLITE_CONSTRAINT)
+    pub sqlite_code: Option<i32>,
+    /// Code de résultat étendu (ex: 2067 pour UNIQUE, 1299 pour NOT NULL)
+    pub extended_code: Option<i32>,
+    pub message: String,
+}
+
+impl RowError {
+    /// Erreur applicative, sans code SQLite associé
+    pub fn new(index: usize, message: String) -> Self {
+        RowError {
+            index,
+            sqlite_code: None,
+            extended_code: None,
+            message,
+        }
+    }
+
+    /// Erreur SQLite, avec extraction des codes de résultat principal et étendu
+    pub fn from_sqlite(index: usize, error: &rusqlite::Error) -> Self {
+        match error {
+            rusqlite::Error::SqliteFailure(err, _) => RowError {
+                index,
+                sqlite_code: Some(err.extended_code & 0xff),
+                extended_code: Some(err.extended_code),
+                message: error.to_string(),
+            },
+            _ => RowError::new(index, error.to_string()),
+        }
+    }
+
+    /// Colonne NOT NULL sans valeur, détectée avant l'insertion (mêmes codes que SQLite)
+    pub fn not_null(index: usize, column: &str) -> Self {
+        RowError {
+            index,
+            sqlite_code: Some(rusqlite::ffi::SQLITE_CONSTRAINT),
+            extended_code: Some(rusqlite::ffi::SQLITE_CONSTRAINT_NOTNULL),
+            message: format!("Colonne NOT NULL {} nulle dans l'objet {}", column, index),
+        }
+    }
+}
+
+/// Résultat final d'une importation ou d'une mise à jour, exploitable par programme
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportSummary {
+    pub total: u32,
+    pub inserted: u32,
+    pub updated: u32,
+    pub ignored: u32,
+    pub failed: u32,
+    pub not_found: u32,
+    pub skipped: u32,
+    pub duration_ms: u64,
+    pub errors: Vec<RowError>,
+    pub column_profiles: Option<Vec<ColumnProfile>>,
+    /// Rowid de chaque ligne insérée, ou tableau des valeurs de clé primaire (WITHOUT ROWID)
+    pub inserted_keys: Option<Vec<JsonValue>>,
+    /// Avec `commit_every`: nombre de lots validés en base
+    pub committed_batches: Option<u32>,
+    /// Avec `commit_every`: offset à utiliser pour reprendre après le dernier lot validé
+    pub resume_offset: Option<u32>,
+    /// Avec `retry_failed`: objets insérés lors de la seconde passe (inclus dans `inserted`)
+    pub retry_inserted: Option<u32>,
+    /// Avec `lossy_utf8`: nombre de séquences UTF-8 invalides remplacées dans le fichier source
+    pub replaced_utf8_sequences: Option<u32>,
+    /// Avec `lenient_numbers`: nombre de valeurs `NaN`/`Infinity` remplacées par null
+    pub replaced_non_finite_numbers: Option<u32>,
+    /// Pour un fichier local: lecture en streaming (true) ou entièrement en mémoire (false)
+    pub streamed: Option<bool>,
+    /// Avec `post_sql`: succès du script de finalisation, et message d'erreur en cas d'échec
+    pub post_sql_succeeded: Option<bool>,
+    pub post_sql_error: Option<String>,
+    /// Avec `lookups`: correspondances servies par le cache, sans requête
+    pub lookup_cache_hits: Option<u32>,
+    /// Avec `split_into_child`: lignes insérées dans les tables enfants
+    pub child_rows_inserted: Option<u32>,
+    /// Avec `expected_version_column`: lignes non mises à jour car modifiées entre-temps
+    pub conflicts: Option<u32>,
+    /// Importation multi-fichiers avec `isolation: per_file`: résultat de chaque fichier
+    pub file_outcomes: Option<Vec<FileOutcome>>,
+    /// Avertissements sans échec (ex: aucun objet extrait de la racine)
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Résultat d'un fichier d'une importation multi-fichiers
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileOutcome {
+    pub path: String,
+    /// Fichier lu et importé; en cas d'échec, rien n'est conservé de ce fichier
+    pub succeeded: bool,
+    pub inserted: u32,
+    pub failed: u32,
+    pub error: Option<String>,
+}
+
+/// Entrée de l'historique des importations (table `_json_import_audit`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportAuditEntry {
+    pub id: i64,
+    pub imported_at: String,
+    pub source_file: String,
+    pub table_name: String,
+    pub total: u32,
+    pub inserted: u32,
+    pub failed: u32,
+    pub duration_ms: i64,
+}
+
+/// Estimation de la durée d'une importation, extrapolée depuis un échantillon
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportEstimate {
+    pub total_objects: u32,
+    pub sample_size: u32,
+    pub sample_duration_ms: u64,
+    pub rows_per_second: f64,
+    pub parse_duration_ms: u64,
+    pub estimated_duration_ms: u64,
+    /// Ordre de grandeur de la mémoire nécessaire pour charger le fichier parsé
+    pub estimated_memory_bytes: u64,
+}
+
+/// Collision prévue entre un objet importé et une contrainte d'unicité
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UniqueConflict {
+    pub index: usize,
+    pub column: String,
+    pub value: JsonValue,
+    /// `true` si la valeur existe déjà en base, `false` si elle est répétée dans le fichier
+    pub in_database: bool,
+}
+
+/// Résultat de la prévisualisation des collisions d'unicité
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConflictReport {
+    pub total: u32,
+    pub conflicting_rows: u32,
+    pub unique_columns: Vec<String>,
+    pub conflicts: Vec<UniqueConflict>,
+}
+
+/// Provenance d'une colonne: chemin JSON utilisé lors d'une importation
+/// (table `_json_column_provenance`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnProvenance {
+    pub id: i64,
+    pub recorded_at: String,
+    pub source_file: String,
+    pub table_name: String,
+    pub json_path: String,
+    pub column_name: String,
+}
+
+/// Profil d'une colonne après importation (valeurs extrêmes et nombre de NULL)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnProfile {
+    pub column: String,
+    pub min: Option<JsonValue>,
+    pub max: Option<JsonValue>,
+    pub non_null_count: i64,
+    pub null_count: i64,
+}
+
 /// Récupère la liste des tables d'une base de données SQLite
 #[tauri::command]
 pub async fn db_get_tables(db_path: String) -> Result<Vec<String>, String> {
     db::get_tables(&db_path).map_err(|e| e.to_string())
 }
 
+/// Nombre de lignes de toutes les tables en un seul appel (-1 pour une table illisible)
+#[tauri::command]
+pub async fn db_table_counts(db_path: String) -> Result<HashMap<String, i64>, String> {
+    db::get_table_counts(&db_path)
+}
+
 /// Analyse la structure d'une table SQLite
 #[tauri::command]
 pub async fn db_analyze_table(db_path: String, table_name: String) -> Result<TableInfo, String> {
@@ -85,11 +804,25 @@ pub async fn json_analyze_structure(json_path: String) -> Result<Vec<JsonPathInf
     json::analyze_structure(&json_path).map_err(|e| e.to_string())
 }
 
+/// Lance l'analyse progressive d'un fichier JSON et retourne son identifiant
 #[tauri::command]
-pub fn json_analyze_structure_progressive(json_path: String, window: tauri::Window) -> Result<(), String> {
+pub fn json_analyze_structure_progressive(json_path: String, window: tauri::Window) -> Result<String, String> {
     json::analyze_structure_progressive(&json_path, window)
 }
 
+/// Arrête une analyse progressive (événement `json-path-analysis-cancelled`);
+/// false si elle est déjà terminée
+#[tauri::command]
+pub fn cancel_analysis(analysis_id: String) -> bool {
+    json::cancel_analysis(&analysis_id)
+}
+
+/// Vérifie qu'un fichier JSON est bien formé et indique la position de l'erreur sinon
+#[tauri::command]
+pub async fn json_validate(json_path: String) -> Result<(), JsonParseError> {
+    json::validate_json_file(&json_path)
+}
+
 /// Récupère un échantillon d'objets depuis un chemin JSON
 #[tauri::command]
 pub async fn json_get_sample(
@@ -100,12 +833,69 @@ pub async fn json_get_sample(
     json::get_sample(&json_path, &json_root, limit).map_err(|e| e.to_string())
 }
 
+/// Résout un chemin JSON sur le premier objet de la racine, comme lors de l'importation
+#[tauri::command]
+pub async fn json_probe_path(
+    json_path: String,
+    json_root: String,
+    path: String,
+) -> Result<PathProbe, String> {
+    json::probe_path(&json_path, &json_root, &path)
+}
+
+/// Calcule la distribution des valeurs d'un chemin JSON (les `top_n` plus fréquentes)
+#[tauri::command]
+pub async fn json_value_histogram(
+    json_path: String,
+    json_root: String,
+    path: String,
+    top_n: Option<u32>,
+) -> Result<ValueHistogram, String> {
+    json::value_histogram(&json_path, &json_root, &path, top_n)
+}
+
+/// Champs des objets source (échantillon de `sample_size` objets) qu'aucun chemin du mapping
+/// ne référence, avec une valeur d'exemple
+#[tauri::command]
+pub async fn report_unmapped_fields(
+    json_path: String,
+    json_root: String,
+    mapping: HashMap<String, String>,
+    sample_size: Option<u32>,
+) -> Result<Vec<UnmappedField>, String> {
+    json::unmapped_fields(&json_path, &json_root, &mapping, sample_size)
+}
+
+/// Libère les documents JSON gardés en mémoire pour l'analyse et l'échantillonnage
+#[tauri::command]
+pub async fn json_clear_cache() -> Result<(), String> {
+    json::cache::clear_json_cache();
+    Ok(())
+}
+
+/// Récupère un échantillon d'objets en streaming, envoyés via l'événement `json-sample-object`
+#[tauri::command]
+pub fn json_get_sample_progressive(
+    json_path: String,
+    json_root: String,
+    limit: Option<u32>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    json::get_sample_progressive(&json_path, &json_root, limit, window)
+}
+
 /// Importe des données JSON dans une table SQLite
 #[tauri::command]
 pub async fn import_json_to_sqlite(
     config: ImportConfig,
     window: tauri::Window,
-) -> Result<ImportProgress, String> {
+) -> Result<ImportSummary, String> {
+    // Avancement de la lecture du fichier source (octets lus ou éléments parsés)
+    let parse_window = window.clone();
+    let parse_progress = move |progress: ParseProgress| {
+        let _ = parse_window.emit("parse-progress", &progress);
+    };
+
     // Création d'une fonction de callback pour rapporter la progression
     let progress_callback = move |progress: ImportProgress| {
         // Envoie un événement de progression au frontend
@@ -113,7 +903,57 @@ pub async fn import_json_to_sqlite(
     };
 
     // Appel de la fonction d'importation du module db
-    db::import_json_to_sqlite(config, progress_callback).map_err(|e| e.to_string())
+    db::import_json_to_sqlite(config, progress_callback, parse_progress).map_err(|e| e.to_string())
+}
+
+/// Importe plusieurs fichiers JSON dans la même table avec la même configuration
+/// (le parsing des fichiers est parallélisé, les insertions restent séquentielles)
+#[tauri::command]
+pub async fn import_json_files_to_sqlite(
+    config: ImportConfig,
+    json_paths: Vec<String>,
+    parse_threads: Option<u32>,
+    isolation: Option<ImportIsolation>,
+    window: tauri::Window,
+) -> Result<ImportSummary, String> {
+    let progress_callback = move |progress: ImportProgress| {
+        let _ = window.emit("import-progress", &progress);
+    };
+
+    db::import_json_files_to_sqlite(
+        config,
+        json_paths,
+        parse_threads,
+        isolation.unwrap_or_default(),
+        progress_callback,
+    )
+}
+
+/// Prévisualise les collisions sur les contraintes d'unicité, sans rien insérer
+#[tauri::command]
+pub async fn check_conflicts(config: ImportConfig) -> Result<ConflictReport, String> {
+    db::check_conflicts(config)
+}
+
+/// Estime la durée d'une importation à partir d'un échantillon, sans modifier la base
+#[tauri::command]
+pub async fn estimate_import(config: ImportConfig) -> Result<ImportEstimate, String> {
+    db::estimate_import(config)
+}
+
+/// Importe des données CSV dans une table SQLite
+#[tauri::command]
+pub async fn import_csv_to_sqlite(
+    config: CsvImportConfig,
+    window: tauri::Window,
+) -> Result<ImportSummary, String> {
+    // Création d'une fonction de callback pour rapporter la progression
+    let progress_callback = move |progress: ImportProgress| {
+        // Envoie un événement de progression au frontend
+        let _ = window.emit("import-progress", &progress);
+    };
+
+    db::import_csv_to_sqlite(config, progress_callback)
 }
 
 /// Met à jour une table SQLite à partir de données JSON
@@ -121,7 +961,7 @@ pub async fn import_json_to_sqlite(
 pub async fn update_sqlite_from_json(
     config: UpdateConfig,
     window: tauri::Window,
-) -> Result<ImportProgress, String> {
+) -> Result<ImportSummary, String> {
     // Création d'une fonction de callback pour rapporter la progression
     let progress_callback = move |progress: ImportProgress| {
         // Envoie un événement de progression au frontend
@@ -130,4 +970,53 @@ pub async fn update_sqlite_from_json(
 
     // Appel de la fonction de mise à jour du module db
     db::update_sqlite_from_json(config, progress_callback).map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+/// Récupère l'historique des importations enregistrées dans une base
+#[tauri::command]
+pub async fn get_import_history(db_path: String) -> Result<Vec<ImportAuditEntry>, String> {
+    db::get_import_history(&db_path)
+}
+
+/// Récupère la provenance (chemin JSON → colonne) enregistrée pour une table
+#[tauri::command]
+pub async fn get_provenance(
+    db_path: String,
+    table_name: String,
+) -> Result<Vec<ColumnProvenance>, String> {
+    db::get_provenance(&db_path, &table_name)
+}
+
+/// Exporte une table SQLite au format NDJSON (un objet JSON par ligne)
+#[tauri::command]
+pub async fn export_table_to_ndjson(
+    db_path: String,
+    table_name: String,
+    output_path: String,
+    window: tauri::Window,
+) -> Result<ImportProgress, String> {
+    // Création d'une fonction de callback pour rapporter la progression
+    let progress_callback = move |progress: ImportProgress| {
+        // Envoie un événement de progression au frontend
+        let _ = window.emit("export-progress", &progress);
+    };
+
+    db::export_table_to_ndjson(&db_path, &table_name, &output_path, progress_callback)
+}
+/// Crée une table dont les colonnes et les types sont déduits des objets JSON; retourne le DDL
+#[tauri::command]
+pub async fn create_table_from_json(config: CreateTableConfig) -> Result<String, String> {
+    db::create_table_from_json(config)
+}
+
+/// Propose une racine, un mapping et un CREATE TABLE pour un fichier JSON (sans rien exécuter)
+#[tauri::command]
+pub async fn propose_import(json_path: String) -> Result<ImportProposal, String> {
+    db::propose_import(&json_path)
+}
+
+/// Exporte le schéma d'une base sous forme d'instructions CREATE (optionnellement pour une seule table)
+#[tauri::command]
+pub async fn export_schema(db_path: String, table_name: Option<String>) -> Result<String, String> {
+    db::export_schema(&db_path, table_name.as_deref())
+}
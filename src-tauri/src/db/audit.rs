@@ -0,0 +1,99 @@
+use crate::commands::{ImportAuditEntry, ImportSummary};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+/// Nom de la table d'historique des importations
+pub const AUDIT_TABLE: &str = "_json_import_audit";
+
+/// Crée la table d'historique si elle n'existe pas encore
+fn ensure_audit_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {} (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            imported_at TEXT NOT NULL,
+            source_file TEXT NOT NULL,
+            table_name TEXT NOT NULL,
+            total INTEGER NOT NULL,
+            inserted INTEGER NOT NULL,
+            failed INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL
+        )",
+        AUDIT_TABLE
+    ))
+    .map_err(|e| format!("Erreur lors de la création de la table d'audit: {}", e))
+}
+
+/// Enregistre une importation dans la table d'historique
+pub fn record_import(
+    conn: &Connection,
+    source_file: &str,
+    table_name: &str,
+    summary: &ImportSummary,
+) -> Result<(), String> {
+    ensure_audit_table(conn)?;
+
+    conn.execute(
+        &format!(
+            "INSERT INTO {} (imported_at, source_file, table_name, total, inserted, failed, duration_ms)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            AUDIT_TABLE
+        ),
+        params![
+            Utc::now().to_rfc3339(),
+            source_file,
+            table_name,
+            summary.total,
+            summary.inserted,
+            summary.failed,
+            summary.duration_ms as i64,
+        ],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Erreur lors de l'enregistrement de l'audit: {}", e))
+}
+
+/// Récupère l'historique des importations (le plus récent en premier)
+pub fn get_import_history(db_path: &str) -> Result<Vec<ImportAuditEntry>, String> {
+    let conn = match Connection::open(db_path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
+    };
+
+    let exists: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name = ?",
+            [AUDIT_TABLE],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Erreur lors de la lecture de l'historique: {}", e))?;
+
+    if exists == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, imported_at, source_file, table_name, total, inserted, failed, duration_ms
+             FROM {} ORDER BY id DESC",
+            AUDIT_TABLE
+        ))
+        .map_err(|e| format!("Erreur lors de la préparation de la requête: {}", e))?;
+
+    let entries = stmt
+        .query_map([], |row| {
+            Ok(ImportAuditEntry {
+                id: row.get(0)?,
+                imported_at: row.get(1)?,
+                source_file: row.get(2)?,
+                table_name: row.get(3)?,
+                total: row.get(4)?,
+                inserted: row.get(5)?,
+                failed: row.get(6)?,
+                duration_ms: row.get(7)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Erreur lors de la lecture de l'historique: {}", e))?;
+
+    Ok(entries)
+}
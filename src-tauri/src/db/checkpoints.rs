@@ -0,0 +1,46 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Nom de la table des points de reprise (index du prochain élément à importer par source)
+pub const CHECKPOINTS_TABLE: &str = "_json_checkpoints";
+
+/// Crée la table des points de reprise si elle n'existe pas encore
+fn ensure_checkpoints_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {} (
+            name TEXT PRIMARY KEY,
+            next_index INTEGER NOT NULL
+        )",
+        CHECKPOINTS_TABLE
+    ))
+    .map_err(|e| format!("Erreur lors de la création de la table des points de reprise: {}", e))
+}
+
+/// Index du prochain élément à importer (None si aucun point de reprise n'est enregistré)
+pub fn read_checkpoint(conn: &Connection, name: &str) -> Result<Option<u32>, String> {
+    ensure_checkpoints_table(conn)?;
+
+    conn.query_row(
+        &format!("SELECT next_index FROM {} WHERE name = ?", CHECKPOINTS_TABLE),
+        [name],
+        |row| row.get::<_, u32>(0),
+    )
+    .optional()
+    .map_err(|e| format!("Erreur lors de la lecture du point de reprise {}: {}", name, e))
+}
+
+/// Enregistre l'index du prochain élément à importer; à appeler dans la transaction du lot,
+/// pour que le point de reprise ne soit validé qu'avec les données qu'il couvre
+pub fn store_checkpoint(conn: &Connection, name: &str, next_index: u32) -> Result<(), String> {
+    ensure_checkpoints_table(conn)?;
+
+    conn.execute(
+        &format!(
+            "INSERT INTO {} (name, next_index) VALUES (?, ?)
+             ON CONFLICT(name) DO UPDATE SET next_index = excluded.next_index",
+            CHECKPOINTS_TABLE
+        ),
+        params![name, next_index],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Erreur lors de l'enregistrement du point de reprise {}: {}", name, e))
+}
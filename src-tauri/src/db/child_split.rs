@@ -0,0 +1,142 @@
+use crate::commands::SplitIntoChild;
+use crate::db::{quote_identifier, validate_identifier};
+use crate::json::extract::get_value_by_path;
+use rusqlite::{params, Connection};
+use serde_json::Value as JsonValue;
+
+/// Éclatement d'un champ texte délimité (ex: `"tags": "a,b,c"`) en lignes d'une table enfant,
+/// chacune liée au rowid de la ligne parente
+pub struct ChildSplitter {
+    splits: Vec<(SplitIntoChild, String)>,
+    pub rows_inserted: u32,
+}
+
+impl ChildSplitter {
+    /// Vérifie les identifiants et que chaque table enfant accepte ses colonnes
+    pub fn new(conn: &Connection, splits: Vec<SplitIntoChild>) -> Result<Self, String> {
+        let mut prepared = Vec::with_capacity(splits.len());
+
+        for split in splits {
+            validate_identifier(&split.child_table)?;
+            validate_identifier(&split.value_column)?;
+            validate_identifier(&split.parent_fk_column)?;
+            if split.delimiter.is_empty() {
+                return Err(format!("Délimiteur vide pour le chemin {}", split.source_path));
+            }
+
+            let sql = format!(
+                "INSERT INTO {} ({}, {}) VALUES (?, ?)",
+                quote_identifier(&split.child_table),
+                quote_identifier(&split.parent_fk_column),
+                quote_identifier(&split.value_column)
+            );
+            conn.prepare_cached(&sql).map_err(|e| {
+                format!("Table enfant {} invalide: {}", split.child_table, e)
+            })?;
+
+            prepared.push((split, sql));
+        }
+
+        Ok(ChildSplitter {
+            splits: prepared,
+            rows_inserted: 0,
+        })
+    }
+
+    /// Éléments de chaque éclatement pour un objet source, sans espaces autour et sans
+    /// éléments vides. Un champ absent ou null ne donne aucune ligne enfant.
+    pub fn tokens(&self, obj: &JsonValue) -> Result<Vec<Vec<String>>, String> {
+        self.splits
+            .iter()
+            .map(|(split, _)| match get_value_by_path(obj, &split.source_path) {
+                None | Some(JsonValue::Null) => Ok(Vec::new()),
+                Some(JsonValue::String(text)) => Ok(text
+                    .split(split.delimiter.as_str())
+                    .map(str::trim)
+                    .filter(|token| !token.is_empty())
+                    .map(str::to_string)
+                    .collect()),
+                Some(other) => Err(format!(
+                    "Chemin {}: texte délimité attendu pour la table {}, valeur {} trouvée",
+                    split.source_path, split.child_table, other
+                )),
+            })
+            .collect()
+    }
+
+    /// Insère les lignes enfants d'une ligne parente qui vient d'être insérée
+    pub fn insert(
+        &mut self,
+        conn: &Connection,
+        parent_rowid: i64,
+        tokens: &[Vec<String>],
+    ) -> Result<(), String> {
+        for ((split, sql), split_tokens) in self.splits.iter().zip(tokens) {
+            let mut stmt = conn
+                .prepare_cached(sql)
+                .map_err(|e| format!("Erreur de préparation pour {}: {}", split.child_table, e))?;
+            for token in split_tokens {
+                stmt.execute(params![parent_rowid, token]).map_err(|e| {
+                    format!("Erreur lors de l'insertion dans {}: {}", split.child_table, e)
+                })?;
+                self.rows_inserted += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tags_split() -> SplitIntoChild {
+        SplitIntoChild {
+            source_path: "meta.tags".to_string(),
+            delimiter: ";".to_string(),
+            child_table: "tags".to_string(),
+            value_column: "tag".to_string(),
+            parent_fk_column: "item_id".to_string(),
+        }
+    }
+
+    fn splitter() -> ChildSplitter {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE tags (item_id INTEGER, tag TEXT);").unwrap();
+        ChildSplitter::new(&conn, vec![tags_split()]).unwrap()
+    }
+
+    #[test]
+    fn tokens_are_trimmed_and_empty_ones_skipped() {
+        let splitter = splitter();
+        let tokens = |obj: JsonValue| splitter.tokens(&obj);
+
+        let expected = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(tokens(json!({"meta": {"tags": " a ;b;; ;c "}})), Ok(vec![expected]));
+        assert_eq!(tokens(json!({"meta": {"tags": null}})), Ok(vec![vec![]]));
+        assert_eq!(tokens(json!({"meta": {}})), Ok(vec![vec![]]));
+        assert!(tokens(json!({"meta": {"tags": ["a"]}})).unwrap_err().contains("meta.tags"));
+    }
+
+    #[test]
+    fn invalid_splits_are_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE tags (item_id INTEGER, tag TEXT);").unwrap();
+
+        let empty_delimiter = SplitIntoChild {
+            delimiter: String::new(),
+            ..tags_split()
+        };
+        let error = ChildSplitter::new(&conn, vec![empty_delimiter]).err().unwrap();
+        assert_eq!(error, "Délimiteur vide pour le chemin meta.tags");
+
+        let unknown_column = SplitIntoChild {
+            value_column: "label".to_string(),
+            ..tags_split()
+        };
+        let error = ChildSplitter::new(&conn, vec![unknown_column]).err().unwrap();
+        assert!(error.starts_with("Table enfant tags invalide"));
+    }
+}
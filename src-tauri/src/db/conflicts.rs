@@ -0,0 +1,120 @@
+use crate::commands::{ConflictReport, ImportConfig, UniqueConflict};
+use crate::db::insert::{find_existing_keys, get_unique_columns, key_to_string};
+use crate::json::extract::{apply_mapping, extract_root_objects};
+use crate::json::format_parse_error;
+use rusqlite::Connection;
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+
+use super::{merge_with_file, resolve_table_name};
+
+/// Prévisualise, sans rien insérer, les objets qui entreraient en collision avec une
+/// contrainte d'unicité: valeur déjà présente en base ou répétée dans le fichier
+pub fn check_conflicts(config: ImportConfig) -> Result<ConflictReport, String> {
+    let mapping = merge_with_file(Some(config.mapping), config.mapping_file.as_deref())?
+        .unwrap_or_default();
+    let forced = merge_with_file(config.forced, config.forced_file.as_deref())?;
+
+    let table_name = resolve_table_name(
+        &config.table_name,
+        config.table_prefix.as_deref(),
+        config.table_suffix.as_deref(),
+    );
+
+    // Lecture du fichier JSON
+    let json_content = std::fs::read_to_string(&config.json_path)
+        .map_err(|e| format!("Erreur de lecture du fichier JSON: {}", e))?;
+
+    let json_data: JsonValue =
+        serde_json::from_str(&json_content).map_err(|e| format_parse_error(&e))?;
+
+    let mut root_objects = extract_root_objects(&json_data, &config.json_root)?;
+
+    // Application de offset et limit, comme pour l'importation
+    let offset_val = config.offset.unwrap_or(0) as usize;
+    if offset_val > 0 && offset_val < root_objects.len() {
+        root_objects = root_objects[offset_val..].to_vec();
+    }
+
+    if let Some(limit_val) = config.limit {
+        let limit_usize = limit_val as usize;
+        if limit_usize > 0 && limit_usize < root_objects.len() {
+            root_objects.truncate(limit_usize);
+        }
+    }
+
+    // Lignes telles qu'elles seraient insérées (mapping puis valeurs forcées statiques)
+    let rows: Vec<HashMap<String, Option<JsonValue>>> = root_objects
+        .iter()
+        .map(|obj| {
+            let mut mapped_data = apply_mapping(obj, &mapping);
+            if let Some(ref force) = forced {
+                for (col_name, forced_value) in force {
+                    if forced_value.as_str() != Some("{{DYNAMIC}}") {
+                        mapped_data.insert(col_name.clone(), Some(forced_value.clone()));
+                    }
+                }
+            }
+            mapped_data
+        })
+        .collect();
+
+    let conn = match Connection::open(&config.db_path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
+    };
+
+    let unique_columns = get_unique_columns(&conn, &table_name)?;
+
+    let mut conflicts = Vec::new();
+    let mut conflicting_rows = HashSet::new();
+
+    for column in &unique_columns {
+        let candidate_keys: HashSet<String> = rows
+            .iter()
+            .filter_map(|row| row.get(column).and_then(|value| value.as_ref()))
+            .filter_map(key_to_string)
+            .collect();
+
+        if candidate_keys.is_empty() {
+            continue;
+        }
+
+        let candidate_keys: Vec<String> = candidate_keys.into_iter().collect();
+        let existing_keys = find_existing_keys(&conn, &table_name, column, &candidate_keys)?;
+
+        let mut seen_keys = HashSet::new();
+        for (index, row) in rows.iter().enumerate() {
+            let value = match row.get(column).and_then(|value| value.as_ref()) {
+                Some(v) => v,
+                None => continue,
+            };
+            let key = match key_to_string(value) {
+                Some(k) => k,
+                None => continue,
+            };
+
+            let in_database = existing_keys.contains(&key);
+            let repeated = !seen_keys.insert(key);
+
+            if in_database || repeated {
+                conflicting_rows.insert(index);
+                conflicts.push(UniqueConflict {
+                    index,
+                    column: column.clone(),
+                    value: value.clone(),
+                    in_database,
+                });
+            }
+        }
+    }
+
+    conflicts.sort_by_key(|c| c.index);
+
+    Ok(ConflictReport {
+        total: rows.len() as u32,
+        conflicting_rows: conflicting_rows.len() as u32,
+        unique_columns,
+        conflicts,
+    })
+}
@@ -0,0 +1,397 @@
+use crate::commands::{ConversionErrorPolicy, PrecisionLossPolicy};
+use crate::json::canonical_json;
+use rusqlite::types::Value as SqlValue;
+use serde_json::{Number, Value as JsonValue};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+/// Structure pour stocker les informations sur une colonne
+#[derive(Debug, Clone)]
+pub struct ColumnMetadata {
+    pub name: String,
+    pub data_type: String,
+    pub not_null: bool,
+    pub primary_key: bool,
+    /// Position dans la clé primaire (1 pour la première colonne, 0 hors clé primaire)
+    pub pk_position: i32,
+    pub default_value: Option<String>,
+}
+
+/// Options de conversion d'une valeur JSON en valeur SQLite
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConvertOpts<'a> {
+    /// Refuse les entiers hors de la plage i64 au lieu de les stocker en réel (avec perte de précision)
+    pub strict_integers: bool,
+    /// Détection des décimaux qui perdent de la précision en réel; avec `text`, leur forme
+    /// écrite est stockée en texte (la politique `warn` est traitée par l'appelant)
+    pub precision_loss: Option<PrecisionLossPolicy>,
+    /// Nombres du texte source qui perdent en précision, relevés pour la détection
+    pub lossy_numbers: Option<&'a LossyNumbers>,
+}
+
+impl<'a> ConvertOpts<'a> {
+    /// Forme écrite d'un nombre qui perd en précision une fois stocké en réel, si la
+    /// détection est active
+    pub fn lossy_lexical(&self, n: &Number) -> Option<&'a str> {
+        self.precision_loss?;
+        self.lossy_numbers?.lexical(n)
+    }
+}
+
+/// Forme écrite des nombres du texte source dont la valeur réelle (f64) ne restitue pas les
+/// chiffres significatifs (ex: 12345678901234567.89), indexée par cette valeur: un nombre
+/// parsé ne conserve pas son écriture. Une valeur atteinte aussi par une autre écriture est
+/// ambiguë et n'est pas retenue.
+#[derive(Debug, Default)]
+pub struct LossyNumbers {
+    lexical: HashMap<u64, Option<String>>,
+}
+
+impl LossyNumbers {
+    /// Relève les nombres du fichier JSON (ou NDJSON) `path`: une première lecture trouve les
+    /// écritures avec perte, une seconde (seulement s'il y en a) les écritures exactes de
+    /// même valeur, qui rendent la forme écrite ambiguë
+    pub fn from_file(path: &str) -> io::Result<Self> {
+        let mut numbers = LossyNumbers::default();
+        for_each_number(File::open(path)?, |token, value| {
+            if loses_precision(token, value) {
+                match numbers.lexical.entry(value.to_bits()) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(Some(token.to_string()));
+                    }
+                    Entry::Occupied(mut entry) => {
+                        if entry.get().as_deref() != Some(token) {
+                            entry.insert(None);
+                        }
+                    }
+                }
+            }
+        })?;
+
+        if !numbers.lexical.is_empty() {
+            for_each_number(File::open(path)?, |token, value| {
+                if !loses_precision(token, value) {
+                    if let Some(lexical) = numbers.lexical.get_mut(&value.to_bits()) {
+                        *lexical = None;
+                    }
+                }
+            })?;
+        }
+
+        numbers.lexical.retain(|_, lexical| lexical.is_some());
+        Ok(numbers)
+    }
+
+    /// Forme écrite d'un nombre parsé, s'il perd en précision
+    pub fn lexical(&self, n: &Number) -> Option<&str> {
+        if !n.is_f64() {
+            return None;
+        }
+        self.lexical.get(&n.as_f64()?.to_bits())?.as_deref()
+    }
+}
+
+/// Appelle `on_number` pour chaque nombre non entier (ou hors de la plage des entiers 64 bits)
+/// du texte JSON, hors des chaînes, avec son écriture et sa valeur réelle
+fn for_each_number<R: Read>(reader: R, mut on_number: impl FnMut(&str, f64)) -> io::Result<()> {
+    let mut token = String::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    let mut flush = |token: &mut String| {
+        if token.starts_with(|c: char| c == '-' || c.is_ascii_digit())
+            && token.parse::<i64>().is_err()
+            && token.parse::<u64>().is_err()
+        {
+            if let Ok(value) = token.parse::<f64>() {
+                if value.is_finite() {
+                    on_number(token, value);
+                }
+            }
+        }
+        token.clear();
+    };
+
+    for byte in BufReader::new(reader).bytes() {
+        let byte = byte?;
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+        } else if matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+            token.push(byte as char);
+        } else {
+            flush(&mut token);
+            in_string = byte == b'"';
+        }
+    }
+    flush(&mut token);
+
+    Ok(())
+}
+
+/// Valeur JSON qui ne peut pas être convertie pour la colonne cible
+#[derive(Debug, Clone)]
+pub struct ConvertError {
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(ref column) => write!(f, "Colonne {}: {}", column, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Affinité TEXT d'un type déclaré, selon les règles de SQLite: le type contient `CHAR`,
+/// `CLOB` ou `TEXT`, et pas `INT` (prioritaire)
+fn has_text_affinity(data_type: &str) -> bool {
+    let data_type = data_type.to_uppercase();
+    !data_type.contains("INT")
+        && ["CHAR", "CLOB", "TEXT"].iter().any(|name| data_type.contains(name))
+}
+
+/// Conversion d'une valeur JsonValue en rusqlite::types::Value, commune à l'insertion et
+/// à la mise à jour. Les objets et tableaux sont stockés en texte JSON canonique
+/// (voir `canonical_json`), ce qui rend les comparaisons et hachages de ce texte stables.
+/// Dans une colonne d'affinité TEXT, un booléen devient `"true"`/`"false"` et un nombre
+/// sa forme écrite, plutôt que 1/0 ou un nombre converti par SQLite.
+pub fn json_to_sql_value(
+    value: &JsonValue,
+    column: Option<&ColumnMetadata>,
+    opts: &ConvertOpts,
+) -> Result<SqlValue, ConvertError> {
+    let text_column = column.is_some_and(|c| has_text_affinity(&c.data_type));
+
+    let converted = match value {
+        JsonValue::Null => SqlValue::Null,
+        JsonValue::Bool(b) if text_column => SqlValue::Text(b.to_string()),
+        JsonValue::Number(n) if text_column => match opts.lossy_lexical(n) {
+            Some(lexical) => SqlValue::Text(lexical.to_string()),
+            None => SqlValue::Text(n.to_string()),
+        },
+        JsonValue::Bool(b) => SqlValue::Integer(*b as i64),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                SqlValue::Integer(i)
+            } else if n.is_u64() && opts.strict_integers {
+                return Err(ConvertError {
+                    column: column.map(|c| c.name.clone()),
+                    message: format!("entier {} hors de la plage des entiers SQLite (i64)", n),
+                });
+            } else if let Some(lexical) = opts
+                .lossy_lexical(n)
+                .filter(|_| opts.precision_loss == Some(PrecisionLossPolicy::Text))
+            {
+                SqlValue::Text(lexical.to_string())
+            } else {
+                SqlValue::Real(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        JsonValue::String(s) => SqlValue::Text(s.clone()),
+        JsonValue::Array(_) | JsonValue::Object(_) => SqlValue::Text(canonical_json(value)),
+    };
+
+    Ok(converted)
+}
+
+/// Vrai si la valeur réelle (f64) ne restitue pas les chiffres significatifs du nombre tel
+/// qu'écrit dans le JSON (ex: 12345678901234567.89). Les écritures équivalentes (`1e2`,
+/// `100.0`) ne sont pas considérées comme une perte.
+fn loses_precision(lexical: &str, value: f64) -> bool {
+    decimal_digits(lexical) != decimal_digits(&format!("{:e}", value))
+}
+
+/// Forme décimale normalisée d'un nombre écrit: signe, chiffres significatifs (sans zéros
+/// de tête ni de fin) et exposant du dernier chiffre
+fn decimal_digits(lexical: &str) -> (bool, String, i64) {
+    let (negative, unsigned) = match lexical.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, lexical),
+    };
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().unwrap_or(0)),
+        None => (unsigned, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let digits = format!("{}{}", integer, fraction);
+    let digits = digits.trim_start_matches('0');
+    let trimmed = digits.trim_end_matches('0');
+    if trimmed.is_empty() {
+        return (false, String::new(), 0);
+    }
+
+    let exponent = exponent - fraction.len() as i64 + (digits.len() - trimmed.len()) as i64;
+    (negative, trimmed.to_string(), exponent)
+}
+
+/// Valeur de repli d'une colonne dont la conversion a échoué, selon sa politique:
+/// `None` si l'échec doit être conservé (politique `error`, par défaut)
+pub fn conversion_fallback(
+    column: &str,
+    policies: Option<&HashMap<String, ConversionErrorPolicy>>,
+    defaults: Option<&HashMap<String, JsonValue>>,
+) -> Option<JsonValue> {
+    match policies.and_then(|p| p.get(column)).copied().unwrap_or_default() {
+        ConversionErrorPolicy::Error => None,
+        ConversionErrorPolicy::Null => Some(JsonValue::Null),
+        ConversionErrorPolicy::Default => defaults.and_then(|d| d.get(column)).cloned(),
+    }
+}
+
+/// Vérifie que chaque colonne en politique `default` a une valeur dans `defaults`
+pub fn validate_conversion_policies(
+    policies: Option<&HashMap<String, ConversionErrorPolicy>>,
+    defaults: Option<&HashMap<String, JsonValue>>,
+) -> Result<(), String> {
+    let mut missing: Vec<&str> = policies
+        .into_iter()
+        .flatten()
+        .filter(|(column, policy)| {
+            **policy == ConversionErrorPolicy::Default
+                && !defaults.is_some_and(|d| d.contains_key(*column))
+        })
+        .map(|(column, _)| column.as_str())
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    missing.sort();
+    Err(format!(
+        "Politique de conversion 'default' sans valeur dans defaults pour: {}",
+        missing.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::temp_json;
+    use serde_json::json;
+
+    fn column(data_type: &str) -> ColumnMetadata {
+        ColumnMetadata {
+            name: "c".to_string(),
+            data_type: data_type.to_string(),
+            not_null: false,
+            primary_key: false,
+            pk_position: 0,
+            default_value: None,
+        }
+    }
+
+    fn convert(value: JsonValue, data_type: Option<&str>) -> SqlValue {
+        let column = data_type.map(column);
+        json_to_sql_value(&value, column.as_ref(), &ConvertOpts::default()).unwrap()
+    }
+
+    #[test]
+    fn scalars_keep_their_sqlite_type() {
+        assert_eq!(convert(json!(null), None), SqlValue::Null);
+        assert_eq!(convert(json!(true), None), SqlValue::Integer(1));
+        assert_eq!(convert(json!(false), Some("INTEGER")), SqlValue::Integer(0));
+        assert_eq!(convert(json!(-42), None), SqlValue::Integer(-42));
+        assert_eq!(convert(json!(i64::MAX), None), SqlValue::Integer(i64::MAX));
+        assert_eq!(convert(json!(2.5), Some("REAL")), SqlValue::Real(2.5));
+        assert_eq!(convert(json!("abc"), None), SqlValue::Text("abc".to_string()));
+    }
+
+    #[test]
+    fn integer_beyond_i64_is_real_unless_strict() {
+        let value = json!(u64::MAX);
+        assert_eq!(convert(value.clone(), None), SqlValue::Real(u64::MAX as f64));
+
+        let opts = ConvertOpts {
+            strict_integers: true,
+            ..ConvertOpts::default()
+        };
+        let error = json_to_sql_value(&value, Some(&column("INTEGER")), &opts).unwrap_err();
+        assert_eq!(error.column.as_deref(), Some("c"));
+        assert!(error.message.contains("hors de la plage"));
+    }
+
+    #[test]
+    fn arrays_and_objects_are_canonical_json_text() {
+        assert_eq!(convert(json!([1, "a", null]), None), SqlValue::Text(r#"[1,"a",null]"#.into()));
+        assert_eq!(
+            convert(json!({"b": 1, "a": {"d": 2, "c": 3}}), Some("TEXT")),
+            SqlValue::Text(r#"{"a":{"c":3,"d":2},"b":1}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn text_affinity_stores_booleans_and_numbers_as_written() {
+        for data_type in ["TEXT", "VARCHAR(10)", "clob", "NATIVE CHARACTER(70)"] {
+            assert_eq!(convert(json!(true), Some(data_type)), SqlValue::Text("true".into()));
+            assert_eq!(convert(json!(7), Some(data_type)), SqlValue::Text("7".into()));
+            assert_eq!(convert(json!(1.5), Some(data_type)), SqlValue::Text("1.5".into()));
+        }
+        // INT est prioritaire sur CHAR dans les règles d'affinité de SQLite
+        assert_eq!(convert(json!(true), Some("CHARINT")), SqlValue::Integer(1));
+        assert_eq!(convert(json!(7), Some("NUMERIC")), SqlValue::Integer(7));
+    }
+
+    #[test]
+    fn high_precision_numbers_keep_their_lexical_form() {
+        let path = temp_json(r#"[{"amount": 12345678901234567.89, "rate": 0.5, "n": 100.0}]"#);
+        let numbers = LossyNumbers::from_file(&path).unwrap();
+
+        let amount = serde_json::from_str::<Number>("12345678901234567.89").unwrap();
+        assert_eq!(numbers.lexical(&amount), Some("12345678901234567.89"));
+        for exact in ["0.5", "100.0", "42"] {
+            let n = serde_json::from_str::<Number>(exact).unwrap();
+            assert_eq!(numbers.lexical(&n), None, "{}", exact);
+        }
+    }
+
+    #[test]
+    fn numbers_inside_strings_are_ignored() {
+        let path = temp_json(r#"[{"label": "12345678901234567.89 \" 0.10000000000000000001"}]"#);
+        assert!(LossyNumbers::from_file(&path).unwrap().lexical.is_empty());
+    }
+
+    #[test]
+    fn value_also_written_exactly_is_ambiguous() {
+        let path = temp_json(r#"[12345678901234568.0, 12345678901234567.89]"#);
+        assert!(LossyNumbers::from_file(&path).unwrap().lexical.is_empty());
+    }
+
+    #[test]
+    fn text_policy_stores_the_lexical_form() {
+        let path = temp_json("[12345678901234567.89]");
+        let numbers = LossyNumbers::from_file(&path).unwrap();
+        let value: JsonValue = serde_json::from_str("12345678901234567.89").unwrap();
+
+        let opts = ConvertOpts {
+            precision_loss: Some(PrecisionLossPolicy::Text),
+            lossy_numbers: Some(&numbers),
+            ..ConvertOpts::default()
+        };
+        assert_eq!(
+            json_to_sql_value(&value, None, &opts).unwrap(),
+            SqlValue::Text("12345678901234567.89".to_string())
+        );
+
+        let opts = ConvertOpts {
+            precision_loss: Some(PrecisionLossPolicy::Warn),
+            ..opts
+        };
+        assert_eq!(
+            json_to_sql_value(&value, None, &opts).unwrap(),
+            SqlValue::Real(12345678901234567.89)
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Nom de la table des compteurs persistants (dernière valeur attribuée par compteur)
+pub const COUNTERS_TABLE: &str = "_json_counters";
+
+/// Crée la table des compteurs si elle n'existe pas encore
+fn ensure_counters_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {} (
+            name TEXT PRIMARY KEY,
+            value INTEGER NOT NULL
+        )",
+        COUNTERS_TABLE
+    ))
+    .map_err(|e| format!("Erreur lors de la création de la table des compteurs: {}", e))
+}
+
+/// Dernière valeur attribuée par un compteur (0 pour un compteur encore jamais utilisé)
+pub fn read_counter(conn: &Connection, name: &str) -> Result<i64, String> {
+    ensure_counters_table(conn)?;
+
+    conn.query_row(
+        &format!("SELECT value FROM {} WHERE name = ?", COUNTERS_TABLE),
+        [name],
+        |row| row.get::<_, i64>(0),
+    )
+    .optional()
+    .map(|value| value.unwrap_or(0))
+    .map_err(|e| format!("Erreur lors de la lecture du compteur {}: {}", name, e))
+}
+
+/// Enregistre la dernière valeur attribuée par un compteur; à appeler dans la transaction
+/// des insertions, pour que le compteur soit annulé avec elles
+pub fn store_counter(conn: &Connection, name: &str, value: i64) -> Result<(), String> {
+    conn.execute(
+        &format!(
+            "INSERT INTO {} (name, value) VALUES (?, ?)
+             ON CONFLICT(name) DO UPDATE SET value = excluded.value",
+            COUNTERS_TABLE
+        ),
+        params![name, value],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Erreur lors de l'enregistrement du compteur {}: {}", name, e))
+}
@@ -0,0 +1,304 @@
+use crate::commands::CreateTableConfig;
+use crate::db::{quote_identifier, validate_identifier};
+use crate::json::extract::{apply_mapping, extract_root_objects};
+use crate::json::read_json_file;
+use rusqlite::Connection;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+
+/// Noms de types reconnus par SQLite (chacun se résout vers une affinité INTEGER, REAL,
+/// NUMERIC, TEXT ou BLOB selon les règles de détermination d'affinité)
+const RECOGNIZED_TYPES: [&str; 22] = [
+    "INTEGER",
+    "INT",
+    "TINYINT",
+    "SMALLINT",
+    "MEDIUMINT",
+    "BIGINT",
+    "INT2",
+    "INT8",
+    "REAL",
+    "DOUBLE",
+    "FLOAT",
+    "NUMERIC",
+    "DECIMAL",
+    "BOOLEAN",
+    "DATE",
+    "DATETIME",
+    "TEXT",
+    "CHARACTER",
+    "VARCHAR",
+    "NCHAR",
+    "CLOB",
+    "BLOB",
+];
+
+/// Vérifie un type de colonne fourni explicitement (ex: `TEXT`, `VARCHAR(20)`,
+/// `DECIMAL(10,2)`) et le retourne normalisé en majuscules
+fn validate_column_type(column: &str, column_type: &str) -> Result<String, String> {
+    let normalized = column_type.trim().to_uppercase();
+    let (name, size) = match normalized.find('(') {
+        Some(pos) => (normalized[..pos].trim(), Some(&normalized[pos..])),
+        None => (normalized.as_str(), None),
+    };
+
+    // Taille optionnelle: un ou deux entiers entre parenthèses
+    let valid_size = size.map_or(true, |size| {
+        size.strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .is_some_and(|inner| {
+                let parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+                parts.len() <= 2
+                    && parts
+                        .iter()
+                        .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+            })
+    });
+
+    if !RECOGNIZED_TYPES.contains(&name) || !valid_size {
+        return Err(format!(
+            "Type '{}' non reconnu pour la colonne {} (types acceptés: {})",
+            column_type,
+            column,
+            RECOGNIZED_TYPES.join(", ")
+        ));
+    }
+
+    Ok(normalized)
+}
+
+/// Type SQLite déduit d'une valeur JSON (None pour null, qui n'apporte aucune information)
+fn infer_value_type(value: &JsonValue) -> Option<&'static str> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(_) => Some("INTEGER"),
+        JsonValue::Number(n) if n.is_i64() => Some("INTEGER"),
+        JsonValue::Number(_) => Some("REAL"),
+        JsonValue::String(_) | JsonValue::Array(_) | JsonValue::Object(_) => Some("TEXT"),
+    }
+}
+
+/// Combine deux types déduits: INTEGER et REAL donnent REAL, tout autre mélange donne TEXT
+fn merge_types(current: &'static str, other: &'static str) -> &'static str {
+    match (current, other) {
+        (a, b) if a == b => a,
+        ("INTEGER", "REAL") | ("REAL", "INTEGER") => "REAL",
+        _ => "TEXT",
+    }
+}
+
+/// Génère (et exécute, hors dry run) le CREATE TABLE correspondant aux objets JSON.
+/// Les types sont déduits des valeurs, sauf pour les colonnes de `column_types`.
+/// Retourne le DDL final.
+pub fn create_table_from_json(config: CreateTableConfig) -> Result<String, String> {
+    validate_identifier(&config.table_name)?;
+
+    let json_data = read_json_file(&config.json_path)?;
+    let root_objects = extract_root_objects(&json_data, &config.json_root)?;
+
+    // Sans mapping, chaque clé de premier niveau devient une colonne du même nom
+    let mapping: HashMap<String, String> = if config.mapping.is_empty() {
+        root_objects
+            .iter()
+            .filter_map(|obj| obj.as_object())
+            .flat_map(|map| map.keys())
+            .map(|key| (key.clone(), key.clone()))
+            .collect()
+    } else {
+        config.mapping
+    };
+
+    if mapping.is_empty() {
+        return Err("Aucune colonne à créer: mapping vide et aucun objet trouvé".to_string());
+    }
+
+    let column_types = config.column_types.unwrap_or_default();
+    for column in mapping.values().chain(column_types.keys()) {
+        validate_identifier(column)?;
+    }
+
+    let unknown: Vec<&String> = column_types
+        .keys()
+        .filter(|column| !mapping.values().any(|c| c == *column))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Types fournis pour des colonnes absentes du mapping: {}",
+            unknown.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    let ddl = build_create_table_ddl(&config.table_name, &root_objects, &mapping, &column_types)?;
+
+    if config.dry_run {
+        return Ok(ddl);
+    }
+
+    let conn = match Connection::open(&config.db_path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
+    };
+
+    conn.execute_batch(&ddl)
+        .map_err(|e| format!("Erreur lors de la création de la table: {}", e))?;
+
+    Ok(ddl)
+}
+
+/// Construit le CREATE TABLE des colonnes du mapping, avec les types déduits des objets
+/// (ou imposés par `column_types`)
+pub fn build_create_table_ddl(
+    table_name: &str,
+    root_objects: &[JsonValue],
+    mapping: &HashMap<String, String>,
+    column_types: &HashMap<String, String>,
+) -> Result<String, String> {
+    // Inférence sur l'ensemble des objets (colonnes triées par nom)
+    let mut inferred: BTreeMap<&str, Option<&'static str>> =
+        mapping.values().map(|column| (column.as_str(), None)).collect();
+
+    for obj in root_objects {
+        for (column, value) in apply_mapping(obj, mapping) {
+            let Some(value_type) = value.as_ref().and_then(infer_value_type) else {
+                continue;
+            };
+            if let Some(entry) = inferred.get_mut(column.as_str()) {
+                *entry = Some(match *entry {
+                    Some(current) => merge_types(current, value_type),
+                    None => value_type,
+                });
+            }
+        }
+    }
+
+    let mut definitions = Vec::new();
+    for (column, inferred_type) in inferred {
+        let column_type = match column_types.get(column) {
+            Some(explicit) => validate_column_type(column, explicit)?,
+            // Colonne toujours absente ou null: TEXT par défaut
+            None => inferred_type.unwrap_or("TEXT").to_string(),
+        };
+        definitions.push(format!("    {} {}", quote_identifier(column), column_type));
+    }
+
+    Ok(format!(
+        "CREATE TABLE {} (\n{}\n);",
+        quote_identifier(table_name),
+        definitions.join(",\n")
+    ))
+}
+
+/// Colonnes TEXT absentes de la table et instructions qui les créent (la table elle-même si
+/// elle n'existe pas), à exécuter dans la transaction d'importation.
+pub fn missing_text_columns(
+    conn: &Connection,
+    table_name: &str,
+    columns: &[String],
+) -> Result<(Vec<String>, Vec<String>), String> {
+    // Colonnes existantes, comparées sans tenir compte de la casse comme le fait SQLite
+    let existing: Vec<String> = conn
+        .prepare(&format!("PRAGMA table_info({})", quote_identifier(table_name)))
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(1))?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| format!("Erreur lors de la lecture des colonnes de {}: {}", table_name, e))?
+        .into_iter()
+        .map(|column| column.to_lowercase())
+        .collect();
+    let missing: Vec<String> = columns
+        .iter()
+        .filter(|column| !existing.contains(&column.to_lowercase()))
+        .cloned()
+        .collect();
+    if missing.is_empty() {
+        return Ok((missing, Vec::new()));
+    }
+
+    let statements: Vec<String> = if existing.is_empty() {
+        let definitions: Vec<String> = missing
+            .iter()
+            .map(|column| format!("{} TEXT", quote_identifier(column)))
+            .collect();
+        vec![format!(
+            "CREATE TABLE {} ({})",
+            quote_identifier(table_name),
+            definitions.join(", ")
+        )]
+    } else {
+        missing
+            .iter()
+            .map(|column| {
+                format!(
+                    "ALTER TABLE {} ADD COLUMN {} TEXT",
+                    quote_identifier(table_name),
+                    quote_identifier(column)
+                )
+            })
+            .collect()
+    };
+    Ok((missing, statements))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{query_column, temp_db, temp_json};
+    use serde_json::json;
+
+    fn config(json_path: &str, db_path: &str, column_types: &[(&str, &str)]) -> CreateTableConfig {
+        CreateTableConfig {
+            json_path: json_path.to_string(),
+            db_path: db_path.to_string(),
+            json_root: String::new(),
+            table_name: "items".to_string(),
+            mapping: HashMap::new(),
+            column_types: Some(
+                column_types
+                    .iter()
+                    .map(|(column, column_type)| (column.to_string(), column_type.to_string()))
+                    .collect(),
+            ),
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn explicit_type_overrides_inference() {
+        let json_path = temp_json(r#"[{"code": 1234, "qty": 2, "price": 1.5}, {"code": 42}]"#);
+        let db_path = temp_db("");
+
+        let ddl = create_table_from_json(config(&json_path, &db_path, &[("code", "varchar(8)")]))
+            .unwrap();
+
+        assert!(ddl.contains(r#""code" VARCHAR(8)"#), "{}", ddl);
+        let types: Vec<String> = query_column(
+            &db_path,
+            "SELECT name || ' ' || type FROM pragma_table_info('items') ORDER BY name",
+        );
+        assert_eq!(types, ["code VARCHAR(8)", "price REAL", "qty INTEGER"]);
+    }
+
+    #[test]
+    fn unrecognized_or_unmapped_types_are_rejected() {
+        let json_path = temp_json(r#"[{"code": 1}]"#);
+        let db_path = temp_db("");
+
+        let error = create_table_from_json(config(&json_path, &db_path, &[("code", "STRING")]));
+        assert!(error.unwrap_err().starts_with("Type 'STRING' non reconnu"));
+        let error = create_table_from_json(config(&json_path, &db_path, &[("other", "TEXT")]));
+        assert!(error.unwrap_err().contains("absentes du mapping: other"));
+        let tables: Vec<String> = query_column(&db_path, "SELECT name FROM sqlite_master");
+        assert!(tables.is_empty());
+    }
+
+    #[test]
+    fn mixed_numbers_are_inferred_as_real() {
+        let objects = [json!({"n": 1}), json!({"n": 2.5}), json!({"n": null})];
+        let mapping = HashMap::from([("n".to_string(), "n".to_string())]);
+
+        let ddl = build_create_table_ddl("items", &objects, &mapping, &HashMap::new()).unwrap();
+
+        assert_eq!(ddl, "CREATE TABLE \"items\" (\n    \"n\" REAL\n);");
+    }
+}
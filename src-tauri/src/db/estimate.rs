@@ -0,0 +1,181 @@
+use crate::commands::{DryRunMode, ImportConfig, ImportEstimate, TriggerPolicy};
+use crate::db::convert::LossyNumbers;
+use crate::db::insert::{insert_objects, InsertOptions};
+use crate::json::extract::extract_root_objects;
+use crate::json::format_parse_error;
+use serde_json::Value as JsonValue;
+use std::time::Instant;
+
+use super::{database_lock, merge_with_file, resolve_table_name};
+
+/// Nombre d'objets importés pour mesurer le débit
+const ESTIMATE_SAMPLE_SIZE: usize = 1000;
+
+/// Rapport approximatif entre la taille du fichier et la mémoire occupée une fois parsé
+const MEMORY_FACTOR: u64 = 6;
+
+/// Estime la durée d'une importation en important un échantillon dans la base cible, au sein
+/// d'une transaction annulée (la base réelle n'est jamais modifiée, mais ses données comptent:
+/// correspondances, clés existantes, index), puis en extrapolant le débit mesuré au nombre
+/// total d'objets
+pub fn estimate_import(config: ImportConfig) -> Result<ImportEstimate, String> {
+    let mapping = merge_with_file(Some(config.mapping), config.mapping_file.as_deref())?
+        .unwrap_or_default();
+    let defaults = merge_with_file(config.defaults, config.defaults_file.as_deref())?;
+    let forced = merge_with_file(config.forced, config.forced_file.as_deref())?;
+    let dynamic = merge_with_file(config.dynamic, config.dynamic_file.as_deref())?;
+
+    let table_name = resolve_table_name(
+        &config.table_name,
+        config.table_prefix.as_deref(),
+        config.table_suffix.as_deref(),
+    );
+
+    // Lecture et parsing du fichier, mesurés séparément de l'insertion
+    let parse_start = Instant::now();
+    let json_content = std::fs::read_to_string(&config.json_path)
+        .map_err(|e| format!("Erreur de lecture du fichier JSON: {}", e))?;
+    let file_size = json_content.len() as u64;
+
+    let json_data: JsonValue =
+        serde_json::from_str(&json_content).map_err(|e| format_parse_error(&e))?;
+    let root_objects = extract_root_objects(&json_data, &config.json_root)?;
+    let parse_duration_ms = parse_start.elapsed().as_millis() as u64;
+
+    let total_objects = root_objects.len();
+    let sample: Vec<JsonValue> = root_objects
+        .into_iter()
+        .take(ESTIMATE_SAMPLE_SIZE)
+        .collect();
+    let sample_size = sample.len();
+
+    // Écritures des nombres avec perte de précision, relevées dans le fichier source
+    let precision_loss = config
+        .detect_precision_loss
+        .unwrap_or(false)
+        .then(|| config.precision_loss_policy.unwrap_or_default());
+    let lossy_numbers = match precision_loss {
+        Some(_) => Some(
+            LossyNumbers::from_file(&config.json_path)
+                .map_err(|e| format!("Erreur de lecture du fichier JSON: {}", e))?,
+        ),
+        None => None,
+    };
+
+    // Une seule opération d'écriture à la fois sur une même base, comme pour l'importation
+    let lock = database_lock(&config.db_path);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+    // Échantillon importé dans la base cible, transaction annulée à la fin; les effets hors
+    // transaction (fichier de rejets, script de finalisation, journal) ne sont pas transmis
+    let options = InsertOptions {
+        mapping_coalesce: config.mapping_coalesce.as_ref(),
+        defaults,
+        forced,
+        dynamic,
+        expressions: config.expressions,
+        transforms: config.transforms,
+        epoch_columns: config.epoch_columns,
+        conversion_error_policy: config.conversion_error_policy,
+        strict_integers: config.strict_integers.unwrap_or(false),
+        precision_loss,
+        lossy_numbers: lossy_numbers.as_ref(),
+        lookups: config.lookups,
+        fts_sync: config.fts_sync,
+        split_into_child: config.split_into_child,
+        trigger_policy: TriggerPolicy::Allow,
+        require_empty_table: config.require_empty_table.unwrap_or(false),
+        duplicate_target_policy: config.duplicate_target_policy.unwrap_or_default(),
+        normalize_keys: config.normalize_keys.unwrap_or_default(),
+        skip_existing_key: config.skip_existing_key,
+        versioned_append: config.versioned_append.as_ref(),
+        derived_key: config.derived_key.as_ref(),
+        begin_mode: config.begin_mode.unwrap_or_default(),
+        ignore_pk_mapping: config.ignore_pk_mapping.unwrap_or(false),
+        per_row_columns: config.per_row_columns.unwrap_or(false),
+        cache_size: config.cache_size,
+        mmap_size: config.mmap_size,
+        extensions: &config.extensions,
+        dry_run: true,
+        dry_run_mode: DryRunMode::ExecuteRollback,
+        ..Default::default()
+    };
+    let summary =
+        insert_objects(sample.into(), &config.db_path, &table_name, &mapping, options, |_| {})?;
+
+    let rows_per_second = if summary.duration_ms > 0 {
+        sample_size as f64 * 1000.0 / summary.duration_ms as f64
+    } else {
+        sample_size as f64 * 1000.0
+    };
+
+    let insert_duration_ms = if rows_per_second > 0.0 {
+        (total_objects as f64 / rows_per_second * 1000.0) as u64
+    } else {
+        0
+    };
+
+    Ok(ImportEstimate {
+        total_objects: total_objects as u32,
+        sample_size: sample_size as u32,
+        sample_duration_ms: summary.duration_ms,
+        rows_per_second,
+        parse_duration_ms,
+        estimated_duration_ms: parse_duration_ms + insert_duration_ms,
+        estimated_memory_bytes: file_size * MEMORY_FACTOR,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{query_column, temp_db, temp_json};
+    use serde_json::json;
+
+    #[test]
+    fn sample_runs_against_the_real_database_and_is_rolled_back() {
+        let db_path = temp_db(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, title TEXT);
+             CREATE VIRTUAL TABLE items_fts USING fts5(title);
+             INSERT INTO items VALUES (1, 'existant');",
+        );
+        let json_path = temp_json(r#"[{"id": 2, "title": "a"}, {"id": 3, "title": "b"}]"#);
+        let config: ImportConfig = serde_json::from_value(json!({
+            "json_path": json_path,
+            "db_path": db_path,
+            "json_root": "",
+            "table_name": "items",
+            "dry_run": false,
+            "mapping": {"id": "id", "title": "title"},
+            "fts_sync": {"fts_table": "items_fts", "content_columns": ["title"]},
+        }))
+        .unwrap();
+
+        let estimate = estimate_import(config).unwrap();
+
+        assert_eq!((estimate.total_objects, estimate.sample_size), (2, 2));
+        let counts: Vec<String> = query_column(
+            &db_path,
+            "SELECT (SELECT COUNT(*) FROM items) || ':' || (SELECT COUNT(*) FROM items_fts)",
+        );
+        assert_eq!(counts, ["1:0"]);
+    }
+
+    #[test]
+    fn sample_sees_the_existing_rows() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER); INSERT INTO items VALUES (1);");
+        let json_path = temp_json(r#"[{"id": 2}]"#);
+        let config: ImportConfig = serde_json::from_value(json!({
+            "json_path": json_path,
+            "db_path": db_path,
+            "json_root": "",
+            "table_name": "items",
+            "dry_run": false,
+            "mapping": {"id": "id"},
+            "require_empty_table": true,
+        }))
+        .unwrap();
+
+        assert!(estimate_import(config).unwrap_err().contains("items"));
+    }
+}
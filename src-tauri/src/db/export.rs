@@ -0,0 +1,196 @@
+use crate::commands::{ImportPhase, ImportProgress};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use serde_json::{Map, Value as JsonValue};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use super::{analyze_table, quote_identifier};
+
+/// Nombre de lignes écrites entre deux vidages du tampon d'écriture
+const FLUSH_INTERVAL: u32 = 1000;
+
+/// Convertit une valeur SQLite en valeur JSON (BLOB encodé en base64, NULL en null)
+pub fn sqlite_value_to_json(value: ValueRef) -> JsonValue {
+    match value {
+        ValueRef::Null => JsonValue::Null,
+        ValueRef::Integer(i) => JsonValue::Number(i.into()),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f)
+            .map(JsonValue::Number)
+            .unwrap_or(JsonValue::Null),
+        ValueRef::Text(t) => JsonValue::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => JsonValue::String(BASE64.encode(b)),
+    }
+}
+
+/// Exporte le contenu d'une table SQLite au format NDJSON (un objet JSON par ligne)
+pub fn export_table_to_ndjson<F>(
+    db_path: &str,
+    table_name: &str,
+    output_path: &str,
+    progress_callback: F,
+) -> Result<ImportProgress, String>
+where
+    F: Fn(ImportProgress) + Send + 'static,
+{
+    // Récupération des colonnes de la table
+    let table_info = analyze_table(db_path, table_name)?;
+    let column_names: Vec<String> = table_info.columns.into_iter().map(|c| c.name).collect();
+
+    let conn = match Connection::open(db_path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
+    };
+
+    let quoted_table = quote_identifier(table_name);
+
+    let total: i64 = conn
+        .query_row(&format!("SELECT COUNT(*) FROM {}", quoted_table), [], |row| {
+            row.get(0)
+        })
+        .map_err(|e| format!("Erreur lors du comptage des lignes: {}", e))?;
+
+    let mut current_progress = ImportProgress {
+        total: total as u32,
+        processed: 0,
+        succeeded: 0,
+        failed: 0,
+        no_change: 0,
+        conflicts: 0,
+        phase: ImportPhase::Preparing,
+        status: "Préparation de l'export...".to_string(),
+    };
+    progress_callback(current_progress.clone());
+
+    let file = File::create(output_path).map_err(|e| {
+        format!(
+            "Erreur lors de la création du fichier {}: {}",
+            output_path, e
+        )
+    })?;
+    let mut writer = BufWriter::new(file);
+
+    let select_query = format!(
+        "SELECT {} FROM {}",
+        column_names
+            .iter()
+            .map(|c| quote_identifier(c))
+            .collect::<Vec<_>>()
+            .join(", "),
+        quoted_table
+    );
+
+    let mut stmt = match conn.prepare(&select_query) {
+        Ok(s) => s,
+        Err(e) => return Err(format!("Erreur lors de la préparation de la requête: {}", e)),
+    };
+
+    let mut rows = match stmt.query([]) {
+        Ok(rows) => rows,
+        Err(e) => return Err(format!("Erreur lors de l'exécution de la requête: {}", e)),
+    };
+
+    current_progress.phase = ImportPhase::Exporting;
+    current_progress.status = "Export des données...".to_string();
+    progress_callback(current_progress.clone());
+
+    loop {
+        let row = match rows.next() {
+            Ok(Some(row)) => row,
+            Ok(None) => break,
+            Err(e) => return Err(format!("Erreur lors de la lecture des lignes: {}", e)),
+        };
+
+        current_progress.processed += 1;
+
+        // Construction de l'objet JSON de la ligne
+        let mut object = Map::new();
+        for (idx, name) in column_names.iter().enumerate() {
+            let value = match row.get_ref(idx) {
+                Ok(v) => sqlite_value_to_json(v),
+                Err(_) => JsonValue::Null,
+            };
+            object.insert(name.clone(), value);
+        }
+
+        match serde_json::to_string(&JsonValue::Object(object)) {
+            Ok(line) => {
+                writeln!(writer, "{}", line)
+                    .map_err(|e| format!("Erreur lors de l'écriture du fichier: {}", e))?;
+                current_progress.succeeded += 1;
+            }
+            Err(e) => {
+                current_progress.failed += 1;
+                eprintln!(
+                    "Erreur lors de la sérialisation de la ligne {}: {}",
+                    current_progress.processed, e
+                );
+            }
+        }
+
+        // Vidage périodique du tampon et mise à jour du progrès
+        if current_progress.processed % FLUSH_INTERVAL == 0 {
+            writer
+                .flush()
+                .map_err(|e| format!("Erreur lors de l'écriture du fichier: {}", e))?;
+            current_progress.status = format!(
+                "Progression: {}/{} lignes exportées",
+                current_progress.processed, current_progress.total
+            );
+            progress_callback(current_progress.clone());
+        }
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Erreur lors de l'écriture du fichier: {}", e))?;
+
+    current_progress.phase = ImportPhase::Done;
+    current_progress.status = format!(
+        "Export terminé. Succès: {}, Échecs: {}",
+        current_progress.succeeded, current_progress.failed
+    );
+    progress_callback(current_progress.clone());
+
+    Ok(current_progress)
+}
+
+/// Exporte le schéma de la base (CREATE TABLE/VIEW/INDEX/TRIGGER), éventuellement limité
+/// à une table et aux objets qui lui sont rattachés (index, triggers)
+pub fn export_schema(db_path: &str, table_name: Option<&str>) -> Result<String, String> {
+    let conn = match Connection::open(db_path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
+    };
+
+    // Les tables passent avant les vues, index et triggers qui en dépendent;
+    // les tables internes de SQLite (sqlite_sequence...) ne sont pas recréables
+    let mut stmt = conn
+        .prepare(
+            "SELECT sql FROM sqlite_master
+             WHERE sql IS NOT NULL
+               AND name NOT LIKE 'sqlite_%'
+               AND (?1 IS NULL OR tbl_name = ?1)
+             ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'view' THEN 1 WHEN 'index' THEN 2 ELSE 3 END,
+                      rowid",
+        )
+        .map_err(|e| format!("Erreur lors de la lecture du schéma: {}", e))?;
+
+    let statements = stmt
+        .query_map([table_name], |row| row.get::<_, String>(0))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Erreur lors de la lecture du schéma: {}", e))?;
+
+    if let Some(name) = table_name {
+        if statements.is_empty() {
+            return Err(format!("Table introuvable: {}", name));
+        }
+    }
+
+    Ok(statements
+        .into_iter()
+        .map(|sql| format!("{};\n", sql))
+        .collect())
+}
@@ -0,0 +1,63 @@
+use crate::commands::FtsSync;
+use crate::db::{quote_identifier, validate_identifier};
+use rusqlite::Connection;
+
+/// Alimentation d'une table plein texte (FTS5) compagnon: après chaque insertion, les colonnes
+/// texte de la ligne sont copiées dans la table FTS avec le même rowid, dans la même transaction
+pub struct FtsSyncer {
+    sql: String,
+    fts_table: String,
+}
+
+impl FtsSyncer {
+    /// Vérifie les identifiants, que la table cible a un rowid et que la table FTS accepte
+    /// les colonnes demandées
+    pub fn new(conn: &Connection, table_name: &str, fts_sync: &FtsSync) -> Result<Self, String> {
+        validate_identifier(&fts_sync.fts_table)?;
+        if fts_sync.content_columns.is_empty() {
+            return Err(format!(
+                "Aucune colonne de contenu pour la table plein texte {}",
+                fts_sync.fts_table
+            ));
+        }
+        for column in &fts_sync.content_columns {
+            validate_identifier(column)?;
+        }
+
+        let columns = fts_sync
+            .content_columns
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "INSERT INTO {} (rowid, {}) SELECT rowid, {} FROM {} WHERE rowid = ?",
+            quote_identifier(&fts_sync.fts_table),
+            columns,
+            columns,
+            quote_identifier(table_name)
+        );
+
+        conn.prepare_cached(&sql).map_err(|e| {
+            format!("Table plein texte {} invalide: {}", fts_sync.fts_table, e)
+        })?;
+
+        Ok(FtsSyncer {
+            sql,
+            fts_table: fts_sync.fts_table.clone(),
+        })
+    }
+
+    /// Copie dans la table FTS la ligne qui vient d'être insérée
+    pub fn sync(&self, conn: &Connection, rowid: i64) -> Result<(), String> {
+        conn.prepare_cached(&self.sql)
+            .and_then(|mut stmt| stmt.execute([rowid]))
+            .map(|_| ())
+            .map_err(|e| {
+                format!(
+                    "Erreur lors de l'alimentation de la table plein texte {}: {}",
+                    self.fts_table, e
+                )
+            })
+    }
+}
@@ -1,15 +1,61 @@
-use crate::commands::ImportProgress;
-use crate::json::extract::{apply_mapping, extract_root_objects, get_value_by_path};
+use crate::commands::{
+    BeginMode, ColumnProfile, ConversionErrorPolicy, DerivedKey, DryRunMode, DuplicateTargetPolicy,
+    EpochResolution, FtsSync, ImportPhase, ImportProgress, ImportSummary, KeyNormalization,
+    Lookup, ParseProgress, PrecisionLossPolicy, RowError, SplitIntoChild, Transform,
+    TriggerPolicy, VersionedAppend,
+};
+use crate::db::convert::{
+    conversion_fallback, json_to_sql_value, validate_conversion_policies, ColumnMetadata,
+    ConvertOpts, LossyNumbers,
+};
+use crate::db::audit::record_import;
+use crate::db::child_split::ChildSplitter;
+use crate::db::checkpoints::store_checkpoint;
+use crate::db::counters::{read_counter, store_counter};
+use crate::db::create::missing_text_columns;
+use crate::db::provenance::record_provenance;
+use crate::db::versions::VersionTracker;
+use crate::db::export::sqlite_value_to_json;
+use crate::db::fts::FtsSyncer;
+use crate::db::lookup::LookupResolver;
+use crate::db::{
+    apply_new_database_pragmas, apply_performance_pragmas, check_database_not_locked,
+    load_extensions, quote_identifier, validate_column_path, validate_identifier,
+    ImportTransaction,
+};
+use crate::json::expression::Expression;
+use crate::json::remote::{fetch_json, is_url, HttpOptions};
+use crate::json::stream::{
+    starts_with_array, stream_nested_array, stream_top_level_array, streamable_root_keys,
+};
+use crate::json::transform::apply_transforms;
+use crate::json::{
+    decode_utf8_lossy, format_parse_error, parse_datetime, read_file_with_progress,
+    replace_non_finite_numbers,
+};
+use crate::json::extract::{
+    apply_coalesce, apply_mapping, array_crossing_hint, derive_key, expand_map_entries,
+    extract_root_objects, extract_root_objects_iter, find_array_crossing, find_duplicate_targets,
+    flatten_mapping, get_value_by_path, normalize_key, normalize_keys_in_place,
+};
 use chrono::Utc;
 use rand::Rng;
-use rusqlite::{params_from_iter, Connection, Result as SqliteResult, Row, Statement, Transaction};
+use rusqlite::types::ValueRef;
+use rusqlite::{params_from_iter, Connection, Result as SqliteResult, Row, Statement};
 use serde_json::{json, Map, Value as JsonValue};
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
+use std::fs::OpenOptions;
 use std::io::Read;
+use std::io::{BufWriter, Write};
 use std::path::Path;
+use std::slice;
+use std::sync::mpsc::{sync_channel, Receiver};
 use std::sync::{Arc, Mutex};
-use std::thread;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
 use uuid::Uuid;
 
 // Fonction utilitaire pour obtenir une colonne qui peut être NULL
@@ -64,48 +110,739 @@ fn generate_dynamic_value(data_type: &str, column_name: &str, index: usize) -> J
     }
 }
 
-/// Structure pour stocker les informations sur une colonne
-#[derive(Debug, Clone)]
-struct ColumnMetadata {
-    name: String,
-    data_type: String,
-    not_null: bool,
-    primary_key: bool,
-    default_value: Option<String>,
+/// Convertit une date (RFC 3339, `AAAA-MM-JJ HH:MM:SS` ou `AAAA-MM-JJ`, en UTC) en timestamp Unix.
+/// Les nombres et null sont conservés tels quels.
+fn to_epoch(value: &JsonValue, resolution: EpochResolution) -> Result<JsonValue, String> {
+    let text = match value {
+        JsonValue::String(s) => s.trim(),
+        JsonValue::Null | JsonValue::Number(_) => return Ok(value.clone()),
+        _ => return Err(format!("Valeur non convertible en date: {}", value)),
+    };
+
+    let datetime =
+        parse_datetime(text).ok_or_else(|| format!("Date invalide: \"{}\"", text))?;
+
+    let epoch = match resolution {
+        EpochResolution::Seconds => datetime.timestamp(),
+        EpochResolution::Milliseconds => datetime.timestamp_millis(),
+    };
+
+    Ok(JsonValue::Number(epoch.into()))
+}
+
+/// Document JSON lu depuis un fichier ou une URL, avec le décompte des corrections appliquées
+pub struct JsonSource {
+    pub json_data: JsonValue,
+    /// Fichier vide ou ne contenant que des espaces
+    pub empty_file: bool,
+    pub replaced_utf8_sequences: Option<u32>,
+    pub replaced_non_finite_numbers: Option<u32>,
+    /// Fichier local lu en streaming (élément par élément) plutôt qu'entièrement en mémoire
+    pub streamed: Option<bool>,
+    /// Éléments du tableau lu en streaming (`json_data` est alors `null`)
+    pub elements: Option<StreamedElements>,
+}
+
+/// Éléments d'un tableau lu en streaming, transmis par le thread de lecture au fil du parsing
+pub struct StreamedElements {
+    receiver: Receiver<JsonValue>,
+    reader: JoinHandle<Result<usize, String>>,
+}
+
+impl StreamedElements {
+    /// Tous les éléments, pour les options qui ont besoin du tableau complet
+    fn collect(self) -> Result<Vec<JsonValue>, String> {
+        let items = self.receiver.into_iter().collect();
+        finish_reading(self.reader)?;
+        Ok(items)
+    }
+}
+
+/// Fin de la lecture d'objets fournis au fil de l'eau: attente du thread de lecture, ou erreur
+/// rencontrée en parcourant la racine; une erreur annule l'importation en cours
+pub type FinishReading<'a> = Box<dyn FnOnce() -> Result<(), String> + 'a>;
+
+/// Objets racine à insérer: liste en mémoire, ou éléments insérés au fil de leur lecture
+/// (tableau lu en streaming, ou racine du document parcourue sans copie d'ensemble)
+pub enum RootObjects<'a> {
+    Loaded(Vec<JsonValue>),
+    Streamed {
+        items: Box<dyn Iterator<Item = JsonValue> + 'a>,
+        finish: FinishReading<'a>,
+    },
+}
+
+impl From<Vec<JsonValue>> for RootObjects<'_> {
+    fn from(objects: Vec<JsonValue>) -> Self {
+        RootObjects::Loaded(objects)
+    }
+}
+
+/// Attend la fin du thread de lecture en streaming, une fois le flux épuisé ou abandonné
+/// (un abandon interrompt la lecture sans erreur)
+fn finish_reading(reader: JoinHandle<Result<usize, String>>) -> Result<(), String> {
+    reader
+        .join()
+        .map_err(|_| "Le thread de lecture du fichier JSON s'est arrêté brutalement".to_string())?
+        .map(|_| ())
+}
+
+/// Options d'insertion d'objets déjà extraits; les champs reprennent les options de même nom
+/// de la configuration d'importation (`ImportConfig`), valeurs par défaut comprises
+#[derive(Default)]
+pub struct InsertOptions<'a> {
+    /// Chemins de repli par colonne, essayés quand le mapping principal ne donne rien
+    pub mapping_coalesce: Option<&'a HashMap<String, Vec<String>>>,
+    pub defaults: Option<HashMap<String, JsonValue>>,
+    pub forced: Option<HashMap<String, JsonValue>>,
+    pub dynamic: Option<HashMap<String, String>>,
+    pub expressions: Option<HashMap<String, String>>,
+    pub transforms: Option<HashMap<String, Vec<Transform>>>,
+    pub epoch_columns: Option<HashMap<String, EpochResolution>>,
+    pub conversion_error_policy: Option<HashMap<String, ConversionErrorPolicy>>,
+    pub strict_integers: bool,
+    /// Politique appliquée aux nombres décimaux qui perdent en précision (None: pas de détection)
+    pub precision_loss: Option<PrecisionLossPolicy>,
+    /// Nombres du texte source qui perdent en précision (voir `LossyNumbers`)
+    pub lossy_numbers: Option<&'a LossyNumbers>,
+    pub lookups: Option<HashMap<String, Lookup>>,
+    pub fts_sync: Option<FtsSync>,
+    pub split_into_child: Option<Vec<SplitIntoChild>>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+    pub trigger_policy: TriggerPolicy,
+    pub profile_after: bool,
+    pub replace_contents: bool,
+    pub reset_autoincrement: bool,
+    pub require_empty_table: bool,
+    pub duplicate_target_policy: DuplicateTargetPolicy,
+    pub normalize_keys: KeyNormalization,
+    pub return_inserted_keys: bool,
+    pub skip_existing_key: Option<String>,
+    pub sequence_column: Option<&'a str>,
+    pub sequence_start: Option<i64>,
+    pub counter_column: Option<&'a str>,
+    /// Nom du compteur persistant (nom de la table par défaut)
+    pub counter_name: Option<&'a str>,
+    pub versioned_append: Option<&'a VersionedAppend>,
+    pub derived_key: Option<&'a DerivedKey>,
+    pub commit_every: Option<u32>,
+    /// Point de reprise enregistré avec chaque lot validé
+    pub checkpoint_name: Option<&'a str>,
+    pub begin_mode: BeginMode,
+    pub retry_failed: bool,
+    pub ignore_pk_mapping: bool,
+    pub per_row_columns: bool,
+    pub page_size: Option<u32>,
+    pub encoding: Option<&'a str>,
+    pub cache_size: Option<i64>,
+    pub mmap_size: Option<u64>,
+    pub warn_on_missing_path: bool,
+    pub reject_file: Option<&'a str>,
+    /// Source enregistrée dans l'historique des importations
+    pub audit_source: Option<&'a str>,
+    /// Source enregistrée comme provenance de chaque ligne insérée
+    pub provenance_source: Option<&'a str>,
+    pub extensions: &'a [String],
+    pub post_sql: Option<&'a str>,
+    pub dry_run: bool,
+    pub dry_run_mode: DryRunMode,
+    /// Colonnes TEXT créées au besoin dans la transaction d'importation (`flatten_all`)
+    pub text_columns: Vec<String>,
+    /// Connexion de l'appelant, déjà dans une transaction qui englobe l'importation (chaque
+    /// importation y devient un point de sauvegarde); None: connexion et transaction dédiées
+    pub connection: Option<&'a mut Connection>,
+}
+
+/// Sélection et mise en forme des objets racine d'un document JSON avant leur insertion
+#[derive(Default)]
+pub struct RootOptions<'a> {
+    pub json_root: &'a str,
+    /// Racines secondaires (préfixe → chemin) dont les éléments sont rattachés par index
+    pub secondary_roots: Option<HashMap<String, String>>,
+    pub skip_first: u32,
+    pub skip_last: u32,
+    pub map_root: bool,
+    pub skip_non_objects: bool,
+    /// Mode clé/valeur: colonnes recevant la clé et la valeur de chaque entrée
+    pub key_value_columns: Option<(&'a str, &'a str)>,
+    pub whole_document_column: Option<&'a str>,
+    /// Séparateur de l'aplatissement automatique (None: pas d'aplatissement)
+    pub flatten_separator: Option<&'a str>,
+    pub flatten_all: bool,
+}
+
+/// Nombre maximal de colonnes créées par `flatten_all` (un chemin feuille par colonne)
+const FLATTEN_ALL_MAX_COLUMNS: usize = 500;
+
+/// Nombre d'éléments parsés entre deux rapports d'avancement d'une lecture en streaming
+const PARSE_PROGRESS_ELEMENTS: usize = 10_000;
+
+/// Éléments parsés d'avance par le thread de lecture en streaming, en attente d'insertion
+const STREAMED_ELEMENTS_QUEUE_DEPTH: usize = 1024;
+
+/// Taille de fichier au-delà de laquelle un tableau racine est lu en streaming (256 Mo)
+pub const DEFAULT_STREAMING_THRESHOLD_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Choisit la lecture en streaming pour un fichier plus gros que le seuil dont la racine est
+/// un tableau, ou dont `json_root` désigne un tableau par des clés simples
+/// (`response.data.records[]`): retourne les clés menant au tableau (vide pour le tableau
+/// racine). Les corrections du texte source (UTF-8, NaN) et les chemins traversant plusieurs
+/// tableaux imposent la lecture complète.
+fn streaming_root_keys(
+    json_path: &str,
+    json_root: &str,
+    threshold: u64,
+    rewrites_text: bool,
+) -> Result<Option<Vec<String>>, String> {
+    let size = std::fs::metadata(json_path)
+        .map_err(|e| format!("Erreur de lecture du fichier JSON: {}", e))?
+        .len();
+
+    let keys = if size <= threshold || rewrites_text {
+        None
+    } else if json_root.is_empty() {
+        starts_with_array(json_path)?.then(Vec::new)
+    } else {
+        streamable_root_keys(json_root)
+    };
+
+    Ok(keys)
+}
+
+/// Lit et parse la source JSON d'une importation (fichier local ou URL http(s)); un gros
+/// tableau est lu en streaming par un thread dédié, pendant l'insertion de ses éléments
+pub fn read_json_source<P>(
+    json_path: &str,
+    json_root: &str,
+    http_options: &HttpOptions,
+    lossy_utf8: bool,
+    lenient_numbers: bool,
+    streaming_threshold_bytes: u64,
+    parse_progress: P,
+) -> Result<JsonSource, String>
+where
+    P: Fn(ParseProgress) + Send + 'static,
+{
+    // Lecture du fichier JSON, ou téléchargement pour une source http(s)
+    let mut replaced_utf8_sequences = None;
+    let mut replaced_non_finite_numbers = None;
+    let mut empty_file = false;
+    let mut streamed = None;
+    let mut elements = None;
+    let json_data: JsonValue = if is_url(json_path) {
+        fetch_json(json_path, http_options)?
+    } else if let Some(keys) = streaming_root_keys(
+        json_path,
+        json_root,
+        streaming_threshold_bytes,
+        lossy_utf8 || lenient_numbers,
+    )? {
+        // Gros fichier: les éléments sont parsés un à un par un thread de lecture et transmis à
+        // l'insertion au fil du parsing, sans charger le texte ni le tableau complets
+        streamed = Some(true);
+        let (sender, receiver) = sync_channel(STREAMED_ELEMENTS_QUEUE_DEPTH);
+        let path = json_path.to_string();
+        let reader = thread::spawn(move || {
+            let mut parsed = 0;
+            let push = |value| {
+                parsed += 1;
+                if parsed % PARSE_PROGRESS_ELEMENTS == 0 {
+                    parse_progress(ParseProgress {
+                        path: path.clone(),
+                        elements_parsed: Some(parsed as u64),
+                        ..Default::default()
+                    });
+                }
+                // Récepteur fermé: l'insertion s'est arrêtée, la lecture aussi
+                sender.send(value).is_ok()
+            };
+            let count = if keys.is_empty() {
+                stream_top_level_array(&path, push)?
+            } else {
+                stream_nested_array(&path, &keys, push)?
+            };
+            parse_progress(ParseProgress {
+                path: path.clone(),
+                elements_parsed: Some(count as u64),
+                ..Default::default()
+            });
+            Ok(count)
+        });
+        elements = Some(StreamedElements { receiver, reader });
+        JsonValue::Null
+    } else {
+        streamed = Some(false);
+        let bytes = read_file_with_progress(json_path, |bytes_read, total_bytes| {
+            parse_progress(ParseProgress {
+                path: json_path.to_string(),
+                bytes_read: Some(bytes_read),
+                total_bytes: Some(total_bytes),
+                elements_parsed: None,
+            })
+        })
+        .map_err(|e| format!("Erreur de lecture du fichier JSON: {}", e))?;
+
+        // UTF-8 strict par défaut; en mode tolérant, les séquences invalides sont remplacées
+        let json_content = if lossy_utf8 {
+            let (text, replaced) = decode_utf8_lossy(&bytes);
+            if replaced > 0 {
+                eprintln!(
+                    "Avertissement: {} séquence(s) UTF-8 invalide(s) remplacée(s) dans {}",
+                    replaced, json_path
+                );
+            }
+            replaced_utf8_sequences = Some(replaced as u32);
+            text
+        } else {
+            String::from_utf8(bytes)
+                .map_err(|e| format!("Erreur de lecture du fichier JSON: UTF-8 invalide ({})", e))?
+        };
+
+        // Nombres non standard (NaN, Infinity) remplacés par null avant le parsing
+        let json_content = if lenient_numbers {
+            let (text, replaced) = replace_non_finite_numbers(&json_content);
+            if replaced > 0 {
+                eprintln!(
+                    "Avertissement: {} valeur(s) NaN/Infinity remplacée(s) par null dans {}",
+                    replaced, json_path
+                );
+            }
+            replaced_non_finite_numbers = Some(replaced as u32);
+            text
+        } else {
+            json_content
+        };
+
+        // Fichier vide ou ne contenant que des espaces: aucun objet, sans erreur de syntaxe
+        if json_content.trim().is_empty() {
+            empty_file = true;
+            JsonValue::Array(Vec::new())
+        } else {
+            serde_json::from_str(&json_content).map_err(|e| format_parse_error(&e))?
+        }
+    };
+
+    Ok(JsonSource {
+        json_data,
+        empty_file,
+        replaced_utf8_sequences,
+        replaced_non_finite_numbers,
+        streamed,
+        elements,
+    })
 }
 
 /// Fonction principale pour importer des données JSON dans SQLite
 pub fn insert_json_data<F>(
     json_path: &str,
+    source: JsonSource,
     db_path: &str,
-    json_root: &str,
     table_name: &str,
     mapping: &HashMap<String, String>,
-    defaults: Option<HashMap<String, JsonValue>>,
-    forced: Option<HashMap<String, JsonValue>>,
-    dynamic: Option<HashMap<String, String>>,
-    limit: Option<u32>,
-    offset: Option<u32>,
-    dry_run: bool,
+    root: RootOptions<'_>,
+    mut options: InsertOptions<'_>,
     progress_callback: F,
-) -> Result<ImportProgress, String>
+) -> Result<ImportSummary, String>
 where
     F: Fn(ImportProgress) + Send + 'static,
 {
-    // Lecture du fichier JSON
-    let json_content = std::fs::read_to_string(json_path)
-        .map_err(|e| format!("Erreur de lecture du fichier JSON: {}", e))?;
+    let JsonSource {
+        mut json_data,
+        empty_file,
+        replaced_utf8_sequences,
+        replaced_non_finite_numbers,
+        streamed,
+        elements,
+    } = source;
+    let RootOptions {
+        json_root,
+        secondary_roots,
+        skip_first: root_skip_first,
+        skip_last: root_skip_last,
+        map_root,
+        skip_non_objects,
+        key_value_columns,
+        whole_document_column,
+        flatten_separator,
+        flatten_all,
+    } = root;
+
+    // Tableau lu en streaming: ses éléments sont insérés au fil du parsing, sauf pour les
+    // options qui ont besoin de tous les objets (ils sont alors rassemblés en mémoire)
+    let needs_all_objects = secondary_roots.is_some()
+        || map_root
+        || root_skip_last > 0
+        || flatten_separator.is_some()
+        || flatten_all;
+    let mut stream = None;
+
+    // Extraction des objets à la racine spécifiée. Pour un tableau à la racine, les éléments
+    // sont déplacés hors du document plutôt que copiés, ce qui évite de doubler la mémoire;
+    // une racine désignée par un chemin est parcourue paresseusement, objet par objet
+    let mut lazy_root = None;
+    let mut root_objects = if empty_file {
+        Vec::new()
+    } else if let Some(elements) = elements {
+        if needs_all_objects {
+            elements.collect()?
+        } else {
+            stream = Some(elements);
+            Vec::new()
+        }
+    } else if json_root.is_empty() && secondary_roots.is_none() {
+        match &mut json_data {
+            JsonValue::Array(items) => std::mem::take(items),
+            _ => extract_root_objects(&json_data, json_root)?,
+        }
+    } else if !needs_all_objects {
+        lazy_root = Some(extract_root_objects_iter(&json_data, json_root)?);
+        Vec::new()
+    } else {
+        extract_root_objects(&json_data, json_root)?
+    };
+
+    // Racine dictionnaire: une ligne par entrée, la clé restant accessible via `@key`
+    if map_root {
+        root_objects = expand_map_entries(root_objects);
+    }
+
+    // Tableaux parallèles: chaque élément secondaire est rattaché à l'objet de même index
+    if let Some(ref secondaries) = secondary_roots {
+        zip_secondary_roots(&json_data, &mut root_objects, secondaries)?;
+    }
+
+    // Lignes d'en-tête et de pied retirées de la racine: elles ne sont pas des données
+    let keep_until = root_objects.len().saturating_sub(root_skip_last as usize);
+    root_objects.truncate(keep_until);
+
+    // Objets parcourus un par un, depuis la liste en mémoire, le thread de lecture ou la
+    // racine du document (chaque objet n'est copié qu'au moment de son insertion; le parcours
+    // s'arrête à la première erreur de navigation, relevée en fin de lecture)
+    let navigation_error = Cell::new(None);
+    let (items, finish): (Box<dyn Iterator<Item = JsonValue>>, Option<FinishReading>) =
+        match (stream, lazy_root) {
+            (Some(StreamedElements { receiver, reader }), _) => (
+                Box::new(receiver.into_iter()),
+                Some(Box::new(move || finish_reading(reader))),
+            ),
+            (None, Some(objects)) => {
+                let error = &navigation_error;
+                let items = objects.map_while(move |object| match object {
+                    Ok(object) => Some(object.clone()),
+                    Err(e) => {
+                        error.set(Some(e));
+                        None
+                    }
+                });
+                let finish = move || error.take().map_or(Ok(()), Err);
+                (Box::new(items), Some(Box::new(finish)))
+            }
+            (None, None) => (Box::new(root_objects.into_iter()), None),
+        };
+
+    // Éléments scalaires ou tableaux mêlés aux objets: ils produiraient des lignes vides
+    let non_objects = Cell::new(0u32);
+    let non_objects_seen = &non_objects;
+    let mut items: Box<dyn Iterator<Item = JsonValue> + '_> = Box::new(
+        items
+            .skip(root_skip_first as usize)
+            .filter(move |obj| {
+                if !obj.is_object() {
+                    non_objects_seen.set(non_objects_seen.get() + 1);
+                }
+                obj.is_object() || !skip_non_objects
+            }),
+    );
+
+    if key_value_columns.is_some() && whole_document_column.is_some() {
+        return Err("Les modes clé/valeur et document ne peuvent pas être combinés".to_string());
+    }
+
+    // Mode clé/valeur: chaque entrée des objets racine devient une ligne `{key, value}`
+    // (les valeurs complexes sont stockées sous forme de texte JSON lors de l'insertion)
+    let mode_mapping: HashMap<String, String>;
+    let mapping = match key_value_columns {
+        Some((key_column, value_column)) => {
+            items = Box::new(
+                items
+                    .filter_map(|obj| match obj {
+                        JsonValue::Object(map) => Some(map),
+                        _ => None,
+                    })
+                    .flat_map(|map| {
+                        map.into_iter()
+                            .map(|(key, value)| json!({ "key": key, "value": value }))
+                    }),
+            );
+
+            mode_mapping = if mapping.is_empty() {
+                HashMap::from([
+                    ("key".to_string(), key_column.to_string()),
+                    ("value".to_string(), value_column.to_string()),
+                ])
+            } else {
+                mapping.clone()
+            };
+            &mode_mapping
+        }
+        // Mode document: l'élément entier est la valeur de l'unique colonne cible
+        None => match whole_document_column {
+            Some(column) => {
+                items = Box::new(items.map(|obj| json!({ "document": obj })));
+
+                mode_mapping = HashMap::from([("document".to_string(), column.to_string())]);
+                &mode_mapping
+            }
+            // Table large: une colonne TEXT par chemin feuille, nommée d'après le chemin
+            // complet (`user.address.city`) et créée si besoin lors de l'importation
+            None if flatten_all => {
+                let root_objects: Vec<JsonValue> = items.collect();
+                let explicit_columns: HashSet<&String> = mapping.values().collect();
+                mode_mapping = flatten_mapping(&root_objects, ".", mapping)?
+                    .into_iter()
+                    .filter(|(_, column)| !explicit_columns.contains(column))
+                    .chain(mapping.iter().map(|(path, column)| (path.clone(), column.clone())))
+                    .collect();
+
+                let mut columns: Vec<String> = mode_mapping.values().cloned().collect();
+                if columns.len() > FLATTEN_ALL_MAX_COLUMNS {
+                    return Err(format!(
+                        "flatten_all: {} chemins feuilles trouvés, au-delà de la limite de {} \
+                         colonnes; mappez les champs utiles explicitement",
+                        columns.len(),
+                        FLATTEN_ALL_MAX_COLUMNS
+                    ));
+                }
+                columns.sort();
+                options.text_columns = columns;
+                items = Box::new(root_objects.into_iter());
+                &mode_mapping
+            }
+            // Aplatissement automatique: les chemins et colonnes du mapping explicite
+            // sont prioritaires sur ceux déduits des objets
+            None => match flatten_separator {
+                Some(separator) => {
+                    let root_objects: Vec<JsonValue> = items.collect();
+                    let explicit_columns: HashSet<&String> = mapping.values().collect();
+                    mode_mapping = flatten_mapping(&root_objects, separator, mapping)?
+                        .into_iter()
+                        .filter(|(_, column)| !explicit_columns.contains(column))
+                        .chain(mapping.iter().map(|(path, column)| (path.clone(), column.clone())))
+                        .collect();
+                    items = Box::new(root_objects.into_iter());
+                    &mode_mapping
+                }
+                None => mapping,
+            },
+        },
+    };
+
+    let extracted = Cell::new(0usize);
+    let extracted_seen = &extracted;
+    let items = items.inspect(move |_| extracted_seen.set(extracted_seen.get() + 1));
+    let root_objects = match finish {
+        Some(finish) => RootObjects::Streamed {
+            items: Box::new(items),
+            finish,
+        },
+        None => RootObjects::Loaded(items.collect()),
+    };
+
+    let mut summary = insert_objects(
+        root_objects,
+        db_path,
+        table_name,
+        mapping,
+        options,
+        progress_callback,
+    )?;
+
+    let non_object_count = non_objects.get();
+    if skip_non_objects {
+        summary.total += non_object_count;
+        summary.skipped += non_object_count;
+    } else if non_object_count > 0 {
+        eprintln!(
+            "Avertissement: {} élément(s) de la racine ne sont pas des objets et ont été insérés sans valeurs mappées",
+            non_object_count
+        );
+    }
+    summary.replaced_utf8_sequences = replaced_utf8_sequences;
+    summary.replaced_non_finite_numbers = replaced_non_finite_numbers;
+    summary.streamed = streamed;
+    if let Some(replaced) = replaced_non_finite_numbers.filter(|replaced| *replaced > 0) {
+        summary.warnings.push(format!(
+            "{} valeur(s) NaN/Infinity remplacée(s) par null",
+            replaced
+        ));
+    }
+
+    // Aucun objet extrait: importation sans effet, probablement une racine mal choisie
+    // (une sélection vide par offset/limit est signalée par insert_objects)
+    if extracted.get() == 0 {
+        let warning = if empty_file {
+            format!("Le fichier {} est vide: aucun objet à importer", json_path)
+        } else {
+            format!(
+                "Aucun objet extrait à la racine '{}': vérifiez le chemin json_root",
+                json_root
+            )
+        };
+        eprintln!("Avertissement: {}", warning);
+        summary.warnings.push(warning);
+    }
+
+    Ok(summary)
+}
+
+/// Rattache à chaque objet principal l'élément de même index de chaque racine secondaire,
+/// accessible dans le mapping par le chemin `<préfixe>[@index]` (ex: `ages[@index]`)
+fn zip_secondary_roots(
+    json_data: &JsonValue,
+    root_objects: &mut [JsonValue],
+    secondary_roots: &HashMap<String, String>,
+) -> Result<(), String> {
+    for (prefix, root_path) in secondary_roots {
+        let secondary = extract_root_objects(json_data, root_path)?;
+
+        if secondary.len() != root_objects.len() {
+            return Err(format!(
+                "La racine secondaire '{}' ({}) contient {} éléments au lieu de {}",
+                prefix,
+                root_path,
+                secondary.len(),
+                root_objects.len()
+            ));
+        }
+
+        let key = format!("{}[@index]", prefix);
+        for (index, (obj, value)) in root_objects.iter_mut().zip(secondary).enumerate() {
+            match obj {
+                JsonValue::Object(map) => {
+                    map.insert(key.clone(), value);
+                }
+                _ => {
+                    return Err(format!(
+                        "L'élément {} de la racine principale n'est pas un objet: impossible d'y associer '{}'",
+                        index, prefix
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Insère une liste d'objets JSON déjà extraits (quelle que soit leur source) dans une table SQLite
+pub fn insert_objects<F>(
+    root_objects: RootObjects<'_>,
+    db_path: &str,
+    table_name: &str,
+    mapping: &HashMap<String, String>,
+    options: InsertOptions<'_>,
+    progress_callback: F,
+) -> Result<ImportSummary, String>
+where
+    F: Fn(ImportProgress) + Send + 'static,
+{
+    let InsertOptions {
+        mapping_coalesce,
+        defaults,
+        forced,
+        dynamic,
+        expressions,
+        transforms,
+        epoch_columns,
+        conversion_error_policy,
+        strict_integers,
+        precision_loss,
+        lossy_numbers,
+        lookups,
+        fts_sync,
+        split_into_child,
+        limit,
+        offset,
+        trigger_policy,
+        profile_after,
+        replace_contents,
+        reset_autoincrement,
+        require_empty_table,
+        duplicate_target_policy,
+        normalize_keys,
+        return_inserted_keys,
+        skip_existing_key,
+        sequence_column,
+        sequence_start,
+        counter_column,
+        counter_name,
+        versioned_append,
+        derived_key,
+        commit_every,
+        checkpoint_name,
+        begin_mode,
+        retry_failed,
+        ignore_pk_mapping,
+        per_row_columns,
+        page_size,
+        encoding,
+        cache_size,
+        mmap_size,
+        warn_on_missing_path,
+        reject_file,
+        audit_source,
+        provenance_source,
+        extensions,
+        post_sql,
+        dry_run,
+        dry_run_mode,
+        text_columns,
+        connection,
+    } = options;
+    let start_time = Instant::now();
+
+    // Validation des noms de table et de colonnes avant toute construction de SQL
+    validate_identifier(table_name)?;
+    for column in mapping.values().chain(mapping_coalesce.into_iter().flat_map(|c| c.keys())) {
+        validate_column_path(column)?;
+    }
+
+    // Validation du mapping: plusieurs chemins JSON vers une même colonne
+    let duplicate_targets = find_duplicate_targets(mapping);
+    if !duplicate_targets.is_empty() {
+        let message = format!(
+            "Colonnes ciblées par plusieurs chemins JSON: {}",
+            duplicate_targets
+                .iter()
+                .map(|(column, paths)| format!("{} ({})", column, paths.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
 
-    let json_data: JsonValue = serde_json::from_str(&json_content)
-        .map_err(|e| format!("Erreur de parsing JSON: {}", e))?;
+        if duplicate_target_policy == DuplicateTargetPolicy::Error {
+            return Err(message);
+        }
+
+        eprintln!("Avertissement: {}", message);
+    }
+
+    // Fichier de rejets (sauf simulation): les objets en échec y sont écrits tels que lus
+    // dans la source, une copie étant gardée avant la normalisation des clés
+    let reject_file = reject_file.filter(|_| !dry_run);
+    let keep_originals = reject_file.is_some() && normalize_keys != KeyNormalization::None;
 
-    // Extraction des objets à la racine spécifiée
-    let mut root_objects = extract_root_objects(&json_data, json_root)?;
+    // Objets lus en streaming: insérés au fil du parsing
+    let (mut root_objects, stream) = match root_objects {
+        RootObjects::Loaded(objects) => (objects, None),
+        RootObjects::Streamed { items, finish } => (Vec::new(), Some((items, finish))),
+    };
 
-    // Application de offset et limit
+    // Application de offset et limit (un offset au-delà de la fin ne laisse aucun objet)
+    let source_objects = root_objects.len();
     let offset_val = offset.unwrap_or(0) as usize;
-    if offset_val > 0 && offset_val < root_objects.len() {
-        root_objects = root_objects[offset_val..].to_vec();
+    if offset_val > 0 {
+        root_objects.drain(..offset_val.min(source_objects));
     }
 
     if let Some(limit_val) = limit {
@@ -115,6 +852,64 @@ where
         }
     }
 
+    let original_objects = keep_originals.then(|| root_objects.clone());
+
+    // Normalisation de la casse des clés (objets et chemins du mapping)
+    let normalized_mapping: HashMap<String, String>;
+    let mapping = if normalize_keys != KeyNormalization::None {
+        for obj in root_objects.iter_mut() {
+            normalize_keys_in_place(obj, normalize_keys, 0);
+        }
+
+        normalized_mapping = mapping
+            .iter()
+            .map(|(path, column)| {
+                let normalized_path = path
+                    .split('.')
+                    .map(|segment| normalize_key(segment, normalize_keys))
+                    .collect::<Vec<_>>()
+                    .join(".");
+                (normalized_path, column.clone())
+            })
+            .collect();
+        &normalized_mapping
+    } else {
+        mapping
+    };
+
+    // En streaming, offset, limit et normalisation s'appliquent au fil de la lecture
+    let streamed_objects = Cell::new(0usize);
+    let (stream_items, stream_reader) = match stream {
+        Some((items, finish)) => {
+            let streamed_seen = &streamed_objects;
+            let items = items
+                .inspect(move |_| streamed_seen.set(streamed_seen.get() + 1))
+                .skip(offset_val)
+                .take(limit.filter(|l| *l > 0).map_or(usize::MAX, |l| l as usize))
+                .map(move |mut obj| {
+                    let original = keep_originals.then(|| obj.clone());
+                    normalize_keys_in_place(&mut obj, normalize_keys, 0);
+                    (obj, original)
+                });
+            (Some(items), Some(finish))
+        }
+        None => (None, None),
+    };
+
+    // Politiques de conversion: une politique `default` exige une valeur par défaut
+    validate_conversion_policies(conversion_error_policy.as_ref(), defaults.as_ref())?;
+
+    // Analyse des expressions calculées (une erreur de syntaxe interrompt l'importation)
+    let mut parsed_expressions: Vec<(String, Expression)> = Vec::new();
+    if let Some(ref exprs) = expressions {
+        for (col_name, expr) in exprs {
+            let parsed = Expression::parse(expr).map_err(|e| {
+                format!("Expression invalide pour la colonne {}: {}", col_name, e)
+            })?;
+            parsed_expressions.push((col_name.clone(), parsed));
+        }
+    }
+
     let total_objects = root_objects.len();
 
     // Création du progress initial
@@ -123,49 +918,204 @@ where
         processed: 0,
         succeeded: 0,
         failed: 0,
+        no_change: 0,
+        conflicts: 0,
+        phase: ImportPhase::Preparing,
         status: "Préparation...".to_string(),
     };
 
+    let mut summary = ImportSummary {
+        total: total_objects as u32,
+        ..Default::default()
+    };
+
+    if source_objects > 0 && total_objects == 0 {
+        let warning = format!(
+            "Aucun des {} objet(s) sélectionné (offset {}{})",
+            source_objects,
+            offset_val,
+            limit.map_or(String::new(), |l| format!(", limit {}", l))
+        );
+        eprintln!("Avertissement: {}", warning);
+        summary.warnings.push(warning);
+    }
+
     // Appel du callback pour notifier le début du processus
     progress_callback(current_progress.clone());
 
-    // En mode dry run, on ne fait rien de plus
-    if dry_run {
+    // En mode dry run (comptage), on ne fait rien de plus
+    if dry_run && dry_run_mode == DryRunMode::Count {
+        if let (Some(items), Some(finish)) = (stream_items, stream_reader) {
+            summary.total = items.count() as u32;
+            finish()?;
+        }
+        current_progress.phase = ImportPhase::Done;
         current_progress.status = "Simulation terminée (dry run)".to_string();
         progress_callback(current_progress.clone());
-        return Ok(current_progress);
+        summary.duration_ms = start_time.elapsed().as_millis() as u64;
+        return Ok(summary);
     }
 
-    // Connexion à la base de données
-    let mut conn = match Connection::open(db_path) {
-        Ok(c) => c,
-        Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
+    // Connexion à la base de données, ou celle de l'appelant dont la transaction englobe
+    // l'importation (importation multi-fichiers tout-ou-rien)
+    let mut own_conn;
+    let conn: &mut Connection = match connection {
+        Some(conn) => conn,
+        None => {
+            own_conn = match Connection::open(db_path) {
+                Ok(c) => c,
+                Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
+            };
+            &mut own_conn
+        }
     };
 
+    // Extensions nécessaires au schéma (ex: fonctions appelées par des colonnes générées)
+    load_extensions(&conn, extensions)?;
+
+    // Taille de page et encodage, à définir avant toute création de table
+    if let Some(warning) = apply_new_database_pragmas(&conn, page_size, encoding)? {
+        eprintln!("Avertissement: {}", warning);
+        summary.warnings.push(warning);
+    }
+
+    // Cache de pages et mémoire partagée de la connexion, avant la transaction d'importation
+    apply_performance_pragmas(&conn, cache_size, mmap_size)?;
+
     // Récupération des métadonnées de la table
+    current_progress.phase = ImportPhase::AnalyzingSchema;
     current_progress.status = "Analyse de la structure de la table...".to_string();
     progress_callback(current_progress.clone());
 
-    let table_columns = match get_table_columns(&conn, table_name) {
+    // Colonnes TEXT à créer au début de la transaction d'importation (annulées avec elle en
+    // simulation), décrites dès maintenant pour préparer l'insertion
+    let (added_columns, add_column_statements) =
+        missing_text_columns(&conn, table_name, &text_columns)?;
+    let table_created = !add_column_statements.is_empty()
+        && add_column_statements[0].starts_with("CREATE TABLE");
+
+    let mut table_columns = match get_table_columns(&conn, table_name) {
         Ok(c) => c,
+        Err(_) if table_created => Vec::new(),
         Err(e) => return Err(format!("Erreur lors de l'analyse de la table: {}", e)),
     };
+    table_columns.extend(added_columns.iter().map(|name| ColumnMetadata {
+        name: name.clone(),
+        data_type: "TEXT".to_string(),
+        not_null: false,
+        primary_key: false,
+        pk_position: 0,
+        default_value: None,
+    }));
+    if !added_columns.is_empty() {
+        summary.warnings.push(format!(
+            "[flatten_all] {} colonne(s) TEXT ajoutée(s) à la table {}",
+            added_columns.len(),
+            table_name
+        ));
+    }
 
-    // Récupération des contraintes d'unicité
-    let unique_columns = match get_unique_columns(&conn, table_name) {
-        Ok(c) => c,
-        Err(e) => {
+    // Tables de correspondance, vérifiées avant toute insertion
+    let mut lookup_resolver = match lookups {
+        Some(lookups) => Some(LookupResolver::new(&conn, lookups)?),
+        None => None,
+    };
+
+    // Table plein texte compagnon, vérifiée avant toute insertion
+    let fts_syncer = match fts_sync {
+        Some(fts_sync) => {
+            if is_without_rowid(&conn, table_name)? {
+                return Err(format!(
+                    "La table {} est WITHOUT ROWID: impossible d'alimenter la table plein texte {}",
+                    table_name, fts_sync.fts_table
+                ));
+            }
+            Some(FtsSyncer::new(&conn, table_name, &fts_sync)?)
+        }
+        None => None,
+    };
+
+    // Tables enfants des champs délimités, liées au rowid de la ligne parente
+    let mut child_splitter = match split_into_child {
+        Some(splits) => {
+            if is_without_rowid(&conn, table_name)? {
+                return Err(format!(
+                    "La table {} est WITHOUT ROWID: impossible de lier des lignes enfants",
+                    table_name
+                ));
+            }
+            Some(ChildSplitter::new(&conn, splits)?)
+        }
+        None => None,
+    };
+
+    // Premier chargement: la table doit être vide
+    if require_empty_table && !table_created {
+        let has_rows = conn
+            .query_row(
+                &format!(
+                    "SELECT EXISTS(SELECT 1 FROM {} LIMIT 1)",
+                    quote_identifier(table_name)
+                ),
+                [],
+                |row| row.get::<_, bool>(0),
+            )
+            .map_err(|e| format!("Erreur lors de la vérification du contenu de la table: {}", e))?;
+
+        if has_rows {
             return Err(format!(
-                "Erreur lors de l'analyse des contraintes d'unicité: {}",
-                e
-            ))
+                "La table {} contient déjà des données (require_empty_table)",
+                table_name
+            ));
+        }
+    }
+
+    // Une vue (alimentée par des triggers INSTEAD OF) n'a ni contraintes NOT NULL ni index
+    // d'unicité propres: ceux-ci appartiennent aux tables sous-jacentes
+    let target_is_view = is_view(&conn, table_name)?;
+
+    // Récupération des contraintes d'unicité
+    let unique_columns = if target_is_view {
+        Vec::new()
+    } else {
+        match get_unique_columns(&conn, table_name) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(format!(
+                    "Erreur lors de l'analyse des contraintes d'unicité: {}",
+                    e
+                ))
+            }
         }
     };
 
+    // Détection des triggers susceptibles de modifier ou rejeter les insertions
+    // (les triggers INSTEAD OF d'une vue sont attendus et ne sont pas signalés)
+    if trigger_policy != TriggerPolicy::Allow && !target_is_view {
+        let triggers = get_table_triggers(&conn, table_name)?;
+
+        if !triggers.is_empty() {
+            let message = format!(
+                "La table '{}' possède des triggers: {}",
+                table_name,
+                triggers.join(", ")
+            );
+
+            if trigger_policy == TriggerPolicy::Error {
+                return Err(message);
+            }
+
+            eprintln!("Avertissement: {}", message);
+            current_progress.status = format!("Avertissement: {}", message);
+            progress_callback(current_progress.clone());
+            summary.warnings.push(message);
+        }
+    }
+
     // Vérification des colonnes NOT NULL
     let not_null_columns: Vec<&ColumnMetadata> = table_columns
         .iter()
-        .filter(|c| c.not_null && !c.primary_key && c.default_value.is_none())
+        .filter(|c| !target_is_view && c.not_null && !c.primary_key && c.default_value.is_none())
         .collect();
 
     // Récupération de toutes les colonnes existantes dans la table
@@ -174,10 +1124,15 @@ where
     // Identification des colonnes à inclure dans l'insertion
     let mut all_mapped_columns = HashSet::new();
 
-    // Ajout des colonnes du mapping
+    // Ajout des colonnes du mapping (et des colonnes à chemins de repli)
     for col in mapping.values() {
         all_mapped_columns.insert(col.clone());
     }
+    if let Some(coalesce) = mapping_coalesce {
+        for col in coalesce.keys() {
+            all_mapped_columns.insert(col.clone());
+        }
+    }
 
     // Ajout des colonnes par défaut
     if let Some(ref def) = defaults {
@@ -200,55 +1155,376 @@ where
         }
     }
 
-    // Filtrer pour ne garder que les colonnes qui existent dans la table
-    let columns_to_include: Vec<String> = all_mapped_columns
-        .into_iter()
-        .filter(|col| all_column_names.contains(col))
-        .collect();
+    // Ajout des colonnes calculées
+    for (col, _) in &parsed_expressions {
+        all_mapped_columns.insert(col.clone());
+    }
 
-    // Vérification si toutes les colonnes NOT NULL sont couvertes
-    let missing_required_columns: Vec<String> = not_null_columns
-        .iter()
-        .filter(|col| !columns_to_include.contains(&col.name))
-        .map(|col| col.name.clone())
-        .collect();
+    // Colonnes générées ciblées par le mapping: absentes de `table_info`, elles seraient
+    // ignorées silencieusement; on indique plutôt les colonnes dont elles dépendent
+    if !target_is_view {
+        let mapped: HashSet<&String> = mapping.values().collect();
+        for (column, dependencies) in get_generated_columns(&conn, table_name, &table_columns)? {
+            if !mapped.contains(&column) {
+                continue;
+            }
 
-    if !missing_required_columns.is_empty() {
-        return Err(format!(
-            "Colonnes avec contrainte NOT NULL sans valeur par défaut ni mapping: {}",
-            missing_required_columns.join(", ")
-        ));
-    }
+            let message = if dependencies.is_empty() {
+                format!(
+                    "La colonne {} est générée et ne peut pas être alimentée: elle est ignorée",
+                    column
+                )
+            } else {
+                format!(
+                    "La colonne {} est générée à partir de {}: mappez plutôt ces colonnes",
+                    column,
+                    dependencies.join(" + ")
+                )
+            };
 
-    // Préparation pour l'insertion
-    current_progress.status = "Préparation de l'insertion...".to_string();
-    progress_callback(current_progress.clone());
+            eprintln!("Avertissement: {}", message);
+            current_progress.status = format!("Avertissement: {}", message);
+            progress_callback(current_progress.clone());
+            summary.warnings.push(message);
+        }
+    }
 
-    // Construction de la requête d'insertion
-    let placeholders = columns_to_include
+    // Filtrer pour ne garder que les colonnes qui existent dans la table
+    // (sans tenir compte de la casse, comme SQLite: `UserName` désigne la colonne `username`)
+    let lowercase_column_names: HashMap<String, &String> = table_columns
         .iter()
-        .map(|_| "?")
-        .collect::<Vec<_>>()
-        .join(", ");
-
-    let insert_query = format!(
-        "INSERT INTO {} ({}) VALUES ({})",
-        table_name,
-        columns_to_include.join(", "),
-        placeholders
-    );
+        .map(|c| (c.name.to_lowercase(), &c.name))
+        .collect();
+    let mut column_aliases: Vec<(String, String)> = Vec::new();
+    let mut columns_to_include: Vec<String> = Vec::new();
+    for col in all_mapped_columns {
+        if all_column_names.contains(&col) {
+            if !columns_to_include.contains(&col) {
+                columns_to_include.push(col);
+            }
+        } else if let Some(canonical) = lowercase_column_names.get(&col.to_lowercase()) {
+            // Casse de la table conservée pour le SQL généré
+            if !columns_to_include.contains(*canonical) {
+                columns_to_include.push((*canonical).clone());
+            }
+            column_aliases.push((col, (*canonical).clone()));
+        }
+    }
 
-    // Démarrage de la transaction
-    let tx = match conn.transaction() {
-        Ok(t) => t,
-        Err(e) => {
+    // Colonne de séquence: alimentée par l'importation, qu'elle soit mappée ou non
+    if let Some(column) = sequence_column {
+        validate_identifier(column)?;
+        if !all_column_names.contains(column) {
             return Err(format!(
-                "Erreur lors de la création de la transaction: {}",
-                e
-            ))
+                "Colonne de séquence '{}' introuvable dans la table {}",
+                column, table_name
+            ));
         }
+        if !columns_to_include.iter().any(|col| col == column) {
+            columns_to_include.push(column.to_string());
+        }
+    }
+
+    // Import versionné: clé métier et colonne de version doivent exister dans la table
+    let mut version_tracker = match versioned_append {
+        Some(versioned) => {
+            for column in [&versioned.key_column, &versioned.version_column] {
+                if !all_column_names.contains(column) {
+                    return Err(format!(
+                        "Colonne '{}' de l'import versionné introuvable dans la table {}",
+                        column, table_name
+                    ));
+                }
+            }
+            if !columns_to_include.contains(&versioned.version_column) {
+                columns_to_include.push(versioned.version_column.clone());
+            }
+            Some((versioned.version_column.as_str(), VersionTracker::new(table_name, versioned)?))
+        }
+        None => None,
+    };
+
+    // Clé dérivée: colonne alimentée pour chaque objet à partir de ses champs source
+    if let Some(derived) = derived_key {
+        validate_identifier(&derived.column)?;
+        if derived.source_paths.is_empty() {
+            return Err(format!("Clé dérivée {}: aucun chemin source", derived.column));
+        }
+        if !all_column_names.contains(&derived.column) {
+            return Err(format!(
+                "Colonne de clé dérivée '{}' introuvable dans la table {}",
+                derived.column, table_name
+            ));
+        }
+        if !columns_to_include.contains(&derived.column) {
+            columns_to_include.push(derived.column.clone());
+        }
+    }
+
+    // Colonne du compteur persistant, alimentée de la même façon
+    if let Some(column) = counter_column {
+        validate_identifier(column)?;
+        if !all_column_names.contains(column) {
+            return Err(format!(
+                "Colonne de compteur '{}' introuvable dans la table {}",
+                column, table_name
+            ));
+        }
+        if !columns_to_include.iter().any(|col| col == column) {
+            columns_to_include.push(column.to_string());
+        }
+    }
+
+    // Clé primaire INTEGER (alias du rowid) fournie par le mapping: elle contourne
+    // l'attribution automatique et peut entrer en collision avec les lignes existantes
+    let table_pk_columns: Vec<&ColumnMetadata> =
+        table_columns.iter().filter(|c| c.pk_position > 0).collect();
+    let rowid_alias = match table_pk_columns.as_slice() {
+        [pk] if !target_is_view && pk.data_type.eq_ignore_ascii_case("INTEGER") => {
+            if is_without_rowid(&conn, table_name)? {
+                None
+            } else {
+                Some(pk.name.clone())
+            }
+        }
+        _ => None,
+    };
+
+    if let Some(pk_name) = rowid_alias.clone().filter(|pk| columns_to_include.contains(pk)) {
+        if ignore_pk_mapping {
+            columns_to_include.retain(|col| *col != pk_name);
+            eprintln!(
+                "Clé primaire {} retirée de l'insertion: valeurs attribuées par SQLite",
+                pk_name
+            );
+        } else {
+            let incoming_keys: HashSet<String> = root_objects
+                .iter()
+                .filter_map(|obj| apply_mapping(obj, mapping).remove(&pk_name).flatten())
+                .filter_map(|value| key_to_string(&value))
+                .collect();
+            let incoming_keys: Vec<String> = incoming_keys.into_iter().collect();
+            let collisions = find_existing_keys(&conn, table_name, &pk_name, &incoming_keys)?.len();
+
+            let message = if collisions > 0 {
+                format!(
+                    "La clé primaire {} est fournie par le mapping: {} valeur(s) existent déjà dans la table",
+                    pk_name, collisions
+                )
+            } else {
+                format!(
+                    "La clé primaire {} est fournie par le mapping: l'attribution automatique est contournée",
+                    pk_name
+                )
+            };
+
+            eprintln!("Avertissement: {}", message);
+            current_progress.status = format!("Avertissement: {}", message);
+            progress_callback(current_progress.clone());
+            summary.warnings.push(message);
+        }
+    }
+
+    // Vérification si toutes les colonnes NOT NULL sont couvertes
+    let missing_required_columns: Vec<String> = not_null_columns
+        .iter()
+        .filter(|col| !columns_to_include.contains(&col.name))
+        .map(|col| col.name.clone())
+        .collect();
+
+    if !missing_required_columns.is_empty() {
+        return Err(format!(
+            "Colonnes avec contrainte NOT NULL sans valeur par défaut ni mapping: {}",
+            missing_required_columns.join(", ")
+        ));
+    }
+
+    // Positions des colonnes NOT NULL, vérifiées pour chaque objet avant l'insertion: un
+    // NULL lié viole la contrainte même si la colonne a une valeur par défaut (qui ne
+    // s'applique qu'aux colonnes omises). L'alias du rowid reçoit une valeur de SQLite.
+    let not_null_positions: Vec<usize> = columns_to_include
+        .iter()
+        .enumerate()
+        .filter(|(_, col)| {
+            table_columns.iter().any(|c| {
+                &&c.name == col
+                    && !target_is_view
+                    && c.not_null
+                    && rowid_alias.as_ref() != Some(&c.name)
+            })
+        })
+        .map(|(position, _)| position)
+        .collect();
+
+    // Préparation pour l'insertion
+    current_progress.phase = ImportPhase::Preparing;
+    current_progress.status = "Préparation de l'insertion...".to_string();
+    progress_callback(current_progress.clone());
+
+    // Construction de la requête d'insertion
+    let placeholders = columns_to_include
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let insert_query = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote_identifier(table_name),
+        columns_to_include
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", "),
+        placeholders
+    );
+
+    // Simulation avec exécution: tout se fait dans une seule transaction, annulée à la fin
+    let rollback_only = dry_run && dry_run_mode == DryRunMode::ExecuteRollback;
+
+    // Validation par lots: le remplacement complet doit rester atomique
+    let batch_size = if rollback_only {
+        0
+    } else {
+        commit_every.unwrap_or(0) as usize
+    };
+    if batch_size > 0 && replace_contents {
+        return Err("commit_every est incompatible avec replace_contents".to_string());
+    }
+
+    // Vérification qu'aucune autre connexion ne verrouille la base (déjà faite par l'appelant
+    // dont la transaction est en cours)
+    if conn.is_autocommit() {
+        check_database_not_locked(conn, db_path)?;
+    }
+
+    // Démarrage de la transaction
+    let mut tx = match ImportTransaction::begin(conn, begin_mode) {
+        Ok(t) => t,
+        Err(e) => {
+            return Err(format!(
+                "Erreur lors de la création de la transaction: {}",
+                e
+            ))
+        }
+    };
+
+    for statement in &add_column_statements {
+        tx.execute_batch(statement)
+            .map_err(|e| format!("Erreur lors de l'ajout des colonnes à {}: {}", table_name, e))?;
+    }
+
+    // Préparation du retour des clés insérées (les tables WITHOUT ROWID n'ont pas de rowid:
+    // on retourne alors les valeurs de la clé primaire, dans l'ordre de ses colonnes)
+    let without_rowid = return_inserted_keys && is_without_rowid(&tx, table_name)?;
+    let mut pk_columns: Vec<&ColumnMetadata> =
+        table_columns.iter().filter(|c| c.pk_position > 0).collect();
+    pk_columns.sort_by_key(|c| c.pk_position);
+    let pk_columns: Vec<String> = pk_columns.into_iter().map(|c| c.name.clone()).collect();
+    if return_inserted_keys {
+        summary.inserted_keys = Some(Vec::new());
+    }
+
+    // Remplacement complet: suppression des anciennes lignes dans la même transaction,
+    // annulée avec le reste en cas d'échec de l'importation
+    if replace_contents {
+        current_progress.phase = ImportPhase::Inserting;
+        current_progress.status = "Suppression des données existantes...".to_string();
+        progress_callback(current_progress.clone());
+
+        if let Err(e) = tx.execute(&format!("DELETE FROM {}", quote_identifier(table_name)), []) {
+            return Err(format!(
+                "Erreur lors de la suppression des données existantes: {}",
+                e
+            ));
+        }
+
+        // Remise à zéro du compteur AUTOINCREMENT (table sqlite_sequence présente seulement
+        // si au moins une table de la base utilise AUTOINCREMENT)
+        if reset_autoincrement {
+            let has_sequence = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='sqlite_sequence'",
+                    [],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map(|count| count > 0)
+                .unwrap_or(false);
+
+            if has_sequence {
+                if let Err(e) = tx.execute("DELETE FROM sqlite_sequence WHERE name = ?", [table_name]) {
+                    return Err(format!(
+                        "Erreur lors de la réinitialisation du compteur AUTOINCREMENT: {}",
+                        e
+                    ));
+                }
+            }
+        }
+    } else if reset_autoincrement {
+        eprintln!("Avertissement: reset_autoincrement ignoré sans replace_contents");
+    }
+
+    // Premier numéro de séquence: valeur fournie, ou suite du maximum actuel de la colonne
+    // (lu après un éventuel remplacement des données)
+    let mut next_sequence = match (sequence_column, sequence_start) {
+        (Some(_), Some(start)) => Some(start),
+        (Some(column), None) => {
+            let query = format!(
+                "SELECT COALESCE(MAX({}), 0) + 1 FROM {}",
+                quote_identifier(column),
+                quote_identifier(table_name)
+            );
+            match tx.query_row(&query, [], |row| row.get::<_, i64>(0)) {
+                Ok(next) => Some(next),
+                Err(e) => {
+                    return Err(format!(
+                        "Erreur lors de la lecture de la séquence {}: {}",
+                        column, e
+                    ))
+                }
+            }
+        }
+        (None, _) => None,
+    };
+
+    // Compteur persistant: reprend après la dernière valeur enregistrée
+    let counter_name = counter_column.map(|_| counter_name.unwrap_or(table_name));
+    let mut next_counter = match counter_name {
+        Some(name) => Some(read_counter(&tx, name)? + 1),
+        None => None,
     };
 
+    // Insertion si absente: les clés déjà présentes en base sont recherchées par lots
+    // (une requête IN par lot) plutôt qu'avec une requête par objet; en streaming, les clés
+    // ne sont pas connues d'avance et chacune est recherchée à sa première occurrence
+    let mut seen_keys: HashSet<String> = HashSet::new();
+    if let Some(ref key_column) = skip_existing_key {
+        if !all_column_names.contains(key_column) {
+            return Err(format!(
+                "Colonne clé '{}' introuvable dans la table {}",
+                key_column, table_name
+            ));
+        }
+
+        current_progress.phase = ImportPhase::Preparing;
+        current_progress.status = "Recherche des clés déjà présentes...".to_string();
+        progress_callback(current_progress.clone());
+
+        let candidate_keys: HashSet<String> = root_objects
+            .iter()
+            .filter_map(|obj| match derived_key {
+                Some(derived) if &derived.column == key_column => {
+                    derive_key(obj, derived).ok().map(JsonValue::String)
+                }
+                _ => apply_mapping(obj, mapping).remove(key_column).flatten(),
+            })
+            .filter_map(|value| key_to_string(&value))
+            .collect();
+        let candidate_keys: Vec<String> = candidate_keys.into_iter().collect();
+
+        seen_keys = find_existing_keys(&tx, table_name, key_column, &candidate_keys)?;
+    }
+
     // Préparation du statement
     let mut stmt = match tx.prepare(&insert_query) {
         Ok(s) => s,
@@ -261,22 +1537,174 @@ where
     };
 
     // Traitement des objets
+    current_progress.phase = ImportPhase::Inserting;
     current_progress.status = "Insertion des données...".to_string();
     progress_callback(current_progress.clone());
 
-    let mut success_count = 0;
-    let mut error_count = 0;
+    let mut success_count: u32 = 0;
+    let mut error_count: u32 = 0;
+    let mut ignored_count: u32 = 0;
+
+    // État du dernier lot validé (avec commit_every)
+    let mut committed_batches: u32 = 0;
+    let mut committed_objects: usize = 0;
+    let mut committed_success: u32 = 0;
+    let mut committed_ignored: u32 = 0;
+    let mut batch_failed = false;
+
+    // Chemins du mapping absents: nombre d'objets concernés et raison (par chemin)
+    let mut missing_paths: HashMap<&str, (u32, Option<String>)> = HashMap::new();
+
+    // Objets en échec à l'insertion, conservés avec leurs valeurs pour une seconde passe
+    let mut failed_rows: Vec<FailedRow> = Vec::new();
+
+    let convert_opts = ConvertOpts {
+        strict_integers,
+        precision_loss,
+        lossy_numbers,
+    };
+    // Nombres stockés en réel avec perte de précision (politique `warn`), par colonne, comptés
+    // pour les lignes effectivement insérées
+    let mut precision_losses: HashMap<String, u32> = HashMap::new();
+    let policies = conversion_error_policy.as_ref();
+    let default_values = defaults.as_ref();
+
+    let objects: Box<dyn Iterator<Item = SourceObject>> = match stream_items {
+        Some(items) => Box::new(
+            items.map(|(obj, original)| (Cow::Owned(obj), original.map(Cow::Owned))),
+        ),
+        None => Box::new(root_objects.iter().enumerate().map(|(index, obj)| {
+            let original = original_objects.as_ref().map(|objects| &objects[index]);
+            (Cow::Borrowed(obj), original.map(Cow::Borrowed))
+        })),
+    };
+
+    // Objets en échec ajoutés au fichier de rejets dès leur échec
+    let mut rejects = reject_file.map(RejectWriter::open).transpose()?;
+
+    for (index, (object, original)) in objects.enumerate() {
+        let obj: &JsonValue = &object;
+        let source: &JsonValue = original.as_deref().unwrap_or(obj);
+
+        // Fin de lot: validation, puis nouvelle transaction et nouveau statement
+        if batch_size > 0 && index > 0 && index % batch_size == 0 {
+            drop(stmt);
+            if let (Some(name), Some(next)) = (counter_name, next_counter) {
+                store_counter(&tx, name, next - 1)?;
+            }
+            if let Some(name) = checkpoint_name {
+                store_checkpoint(&tx, name, offset.unwrap_or(0) + index as u32)?;
+            }
+            // Commit refusé: la transaction du lot est annulée en étant libérée
+            let commit_error = tx.commit().err();
+            if let Some(ref e) = commit_error {
+                let message =
+                    format!("Erreur lors du commit du lot {}: {}", committed_batches + 1, e);
+                eprintln!("{}", message);
+                summary.warnings.push(message);
+            } else {
+                committed_batches += 1;
+                committed_objects = index;
+                committed_success = success_count;
+                committed_ignored = ignored_count;
+            }
+
+            tx = match ImportTransaction::begin(conn, begin_mode) {
+                Ok(t) => t,
+                Err(e) => {
+                    return Err(format!(
+                        "Erreur lors de la création de la transaction: {}",
+                        e
+                    ))
+                }
+            };
+            stmt = match tx.prepare(&insert_query) {
+                Ok(s) => s,
+                Err(e) => {
+                    return Err(format!(
+                        "Erreur lors de la préparation de la requête: {}",
+                        e
+                    ))
+                }
+            };
+            if commit_error.is_some() {
+                batch_failed = true;
+                break;
+            }
+        }
 
-    for (index, obj) in root_objects.iter().enumerate() {
         current_progress.processed += 1;
 
-        // Application du mapping
+        // Application du mapping, puis des chemins de repli
         let mut mapped_data = apply_mapping(obj, mapping);
+        if let Some(coalesce) = mapping_coalesce {
+            apply_coalesce(obj, &mut mapped_data, coalesce);
+        }
+
+        // Clé dérivée calculée avant le filtrage des clés existantes, qui peut la viser
+        if let Some(derived) = derived_key {
+            match derive_key(obj, derived) {
+                Ok(key) => {
+                    mapped_data.insert(derived.column.clone(), Some(JsonValue::String(key)));
+                }
+                Err(message) => {
+                    error_count += 1;
+                    current_progress.failed += 1;
+                    eprintln!("Erreur lors du calcul de l'objet {}: {}", index, message);
+                    if let Some(ref mut rejects) = rejects {
+                        rejects.write(source, &message)?;
+                    }
+                    summary.errors.push(RowError::new(index, message));
+                    continue;
+                }
+            }
+        }
 
-        // Application des valeurs par défaut (seulement si la valeur est null/undefined)
+        if warn_on_missing_path {
+            for path in mapping.keys() {
+                if get_value_by_path(obj, path).is_none() {
+                    let entry = missing_paths.entry(path.as_str()).or_insert((0, None));
+                    entry.0 += 1;
+                    if entry.1.is_none() {
+                        entry.1 = find_array_crossing(obj, path);
+                    }
+                }
+            }
+        }
+
+        // Clé déjà présente en base ou déjà rencontrée dans ce fichier: objet ignoré
+        if let Some(ref key_column) = skip_existing_key {
+            if let Some(key) = mapped_data
+                .get(key_column)
+                .and_then(|value| value.as_ref())
+                .and_then(key_to_string)
+            {
+                let known = seen_keys.contains(&key)
+                    || (stream_reader.is_some()
+                        && !find_existing_keys(&tx, table_name, key_column, slice::from_ref(&key))?
+                            .is_empty());
+                seen_keys.insert(key);
+                if known {
+                    ignored_count += 1;
+                    current_progress.no_change += 1;
+                    continue;
+                }
+            }
+        }
+
+        // Application des valeurs par défaut (seulement si le chemin source est absent).
+        // Un défaut `null` lie NULL explicitement: la colonne fait partie de l'insertion,
+        // le DEFAULT de la table ne s'applique donc pas
         if let Some(ref def) = defaults {
             for (col_name, default_value) in def {
                 if !mapped_data.contains_key(col_name) || mapped_data[col_name].is_none() {
+                    if default_value.is_null() {
+                        mapped_data.insert(col_name.clone(), Some(JsonValue::Null));
+                        continue;
+                    }
+
+                    // Seule une chaîne valant exactement {{DYNAMIC}} déclenche la génération:
+                    // un objet ou tableau qui la contient est inséré tel quel
                     if default_value.as_str() == Some("{{DYNAMIC}}") {
                         // Générer une valeur dynamique selon le type de la colonne
                         if let Some(col_info) = table_columns.iter().find(|c| c.name == *col_name) {
@@ -319,6 +1747,17 @@ where
             }
         }
 
+        // Numéro de séquence consommé par chaque tentative d'insertion, même en échec:
+        // une ligne réinsérée lors de la seconde passe garde sa place dans l'ordre source
+        if let (Some(column), Some(sequence)) = (sequence_column, next_sequence.as_mut()) {
+            mapped_data.insert(column.to_string(), Some(JsonValue::from(*sequence)));
+            *sequence += 1;
+        }
+        if let (Some(column), Some(counter)) = (counter_column, next_counter.as_mut()) {
+            mapped_data.insert(column.to_string(), Some(JsonValue::from(*counter)));
+            *counter += 1;
+        }
+
         // Application des templates personnalisés
         if let Some(ref dyn_templates) = dynamic {
             for (col_name, template) in dyn_templates {
@@ -342,6 +1781,115 @@ where
             }
         }
 
+        // Transformations par colonne (une étape en échec fait échouer l'objet)
+        let mut row_error = None;
+        if let Some(ref pipelines) = transforms {
+            for (col_name, steps) in pipelines {
+                if let Some(Some(value)) = mapped_data.get(col_name) {
+                    match apply_transforms(value.clone(), steps) {
+                        Ok(transformed) => {
+                            mapped_data.insert(col_name.clone(), Some(transformed));
+                        }
+                        Err(e) => match conversion_fallback(col_name, policies, default_values) {
+                            Some(fallback) => {
+                                mapped_data.insert(col_name.clone(), Some(fallback));
+                            }
+                            None => {
+                                row_error = Some(format!("Colonne {}: {}", col_name, e));
+                                break;
+                            }
+                        },
+                    }
+                }
+            }
+        }
+
+        // Évaluation des expressions calculées (une erreur fait échouer l'objet)
+        for (col_name, expression) in &parsed_expressions {
+            if row_error.is_some() {
+                break;
+            }
+            match expression.evaluate(obj) {
+                Ok(value) => {
+                    mapped_data.insert(col_name.clone(), Some(value));
+                }
+                Err(e) => {
+                    row_error = Some(format!("Colonne {}: {}", col_name, e));
+                    break;
+                }
+            }
+        }
+
+        // Conversion des dates en timestamp Unix (secondes ou millisecondes)
+        if let Some(ref epochs) = epoch_columns {
+            for (col_name, resolution) in epochs {
+                if row_error.is_some() {
+                    break;
+                }
+                if let Some(Some(value)) = mapped_data.get(col_name) {
+                    match to_epoch(value, *resolution) {
+                        Ok(epoch) => {
+                            mapped_data.insert(col_name.clone(), Some(epoch));
+                        }
+                        Err(e) => match conversion_fallback(col_name, policies, default_values) {
+                            Some(fallback) => {
+                                mapped_data.insert(col_name.clone(), Some(fallback));
+                            }
+                            None => row_error = Some(format!("Colonne {}: {}", col_name, e)),
+                        },
+                    }
+                }
+            }
+        }
+
+        // Correspondances: valeurs remplacées par celles des tables de référence
+        if let (None, Some(resolver)) = (&row_error, lookup_resolver.as_mut()) {
+            if let Err(e) = resolver.resolve(&tx, &mut mapped_data) {
+                row_error = Some(e);
+            }
+        }
+
+        // Version suivante de la clé métier (valeur finale de la colonne clé)
+        if let (None, Some((column, tracker))) = (&row_error, version_tracker.as_mut()) {
+            let key = mapped_data.get(&tracker.key_column).and_then(|value| value.as_ref());
+            match tracker.next_version(&tx, key) {
+                Ok(version) => {
+                    mapped_data.insert(column.to_string(), Some(JsonValue::from(version)));
+                }
+                Err(e) => row_error = Some(e),
+            }
+        }
+
+        // Éléments des champs délimités, insérés en lignes enfants après la ligne parente
+        let mut child_tokens = Vec::new();
+        if let (None, Some(splitter)) = (&row_error, child_splitter.as_ref()) {
+            match splitter.tokens(obj) {
+                Ok(tokens) => child_tokens = tokens,
+                Err(e) => row_error = Some(e),
+            }
+        }
+
+        if let Some(message) = row_error {
+            error_count += 1;
+            current_progress.failed += 1;
+            eprintln!("Erreur lors du calcul de l'objet {}: {}", index, message);
+            if let Some(ref mut rejects) = rejects {
+                rejects.write(source, &message)?;
+            }
+            summary.errors.push(RowError::new(index, message));
+            continue;
+        }
+
+        // Valeurs des colonnes ciblées avec une autre casse, rangées sous le nom de la table
+        for (alias, canonical) in &column_aliases {
+            if let Some(value) = mapped_data.remove(alias) {
+                let slot = mapped_data.entry(canonical.clone()).or_insert(None);
+                if slot.is_none() {
+                    *slot = value;
+                }
+            }
+        }
+
         // Traitement spécial pour les colonnes avec contrainte UNIQUE + NOT NULL sans valeur
         for unique_col in &unique_columns {
             if let Some(col_info) = table_columns.iter().find(|c| c.name == *unique_col) {
@@ -362,42 +1910,137 @@ where
         }
 
         // Préparation des valeurs à insérer
-        let mut params = Vec::new();
-
-        for col in &columns_to_include {
-            let value = if let Some(Some(val)) = mapped_data.get(col) {
-                match val {
-                    JsonValue::Null => rusqlite::types::Value::Null,
-                    JsonValue::Bool(b) => rusqlite::types::Value::Integer(*b as i64),
-                    JsonValue::Number(n) => {
-                        if n.is_i64() {
-                            rusqlite::types::Value::Integer(n.as_i64().unwrap())
-                        } else {
-                            rusqlite::types::Value::Real(n.as_f64().unwrap())
+        let params = match columns_to_include
+            .iter()
+            .map(|col| match mapped_data.get(col) {
+                Some(Some(val)) => {
+                    let column = table_columns.iter().find(|c| &c.name == col);
+                    json_to_sql_value(val, column, &convert_opts).or_else(|e| {
+                        match conversion_fallback(col, policies, default_values) {
+                            Some(fallback) => json_to_sql_value(&fallback, column, &convert_opts),
+                            None => Err(e),
                         }
-                    }
-                    JsonValue::String(s) => rusqlite::types::Value::Text(s.clone()),
-                    JsonValue::Array(_) | JsonValue::Object(_) => {
-                        rusqlite::types::Value::Text(val.to_string())
-                    }
+                    })
                 }
-            } else {
-                rusqlite::types::Value::Null
-            };
+                _ => Ok(rusqlite::types::Value::Null),
+            })
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(params) => params,
+            Err(e) => {
+                error_count += 1;
+                current_progress.failed += 1;
+                eprintln!("Erreur de conversion pour l'objet {}: {}", index, e);
+                if let Some(ref mut rejects) = rejects {
+                    rejects.write(source, &e.to_string())?;
+                }
+                summary.errors.push(RowError::new(index, e.to_string()));
+                continue;
+            }
+        };
+
+        // Colonnes par objet: seules les colonnes présentes sont insérées
+        let present_columns: Option<Vec<usize>> = if per_row_columns {
+            let present: Vec<usize> = columns_to_include
+                .iter()
+                .enumerate()
+                .filter(|(_, col)| matches!(mapped_data.get(*col), Some(Some(_))))
+                .map(|(position, _)| position)
+                .collect();
+            (present.len() < columns_to_include.len()).then_some(present)
+        } else {
+            None
+        };
 
-            params.push(value);
+        // Colonne NOT NULL liée à NULL: objet rejeté sans tenter l'insertion
+        let null_position = not_null_positions.iter().find(|&&position| {
+            params[position] == rusqlite::types::Value::Null
+                && present_columns
+                    .as_ref()
+                    .is_none_or(|present| present.contains(&position))
+        });
+        if let Some(&position) = null_position {
+            error_count += 1;
+            current_progress.failed += 1;
+            let error = RowError::not_null(index, &columns_to_include[position]);
+            eprintln!("Erreur lors de l'insertion de l'objet {}: {}", index, error.message);
+            if let Some(ref mut rejects) = rejects {
+                rejects.write(source, &error.message)?;
+            }
+            summary.errors.push(error);
+            continue;
         }
 
+        let lossy_columns: Vec<&String> = if precision_loss == Some(PrecisionLossPolicy::Warn) {
+            columns_to_include
+                .iter()
+                .filter(|col| match mapped_data.get(*col) {
+                    Some(Some(JsonValue::Number(n))) => convert_opts.lossy_lexical(n).is_some(),
+                    _ => false,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         // Exécution de la requête
-        match stmt.execute(params_from_iter(params.iter())) {
+        let result = match present_columns {
+            Some(ref present) => {
+                insert_present_columns(&tx, table_name, &columns_to_include, present, &params)
+            }
+            None => stmt.execute(params_from_iter(params.iter())),
+        };
+        match result {
             Ok(_) => {
                 success_count += 1;
                 current_progress.succeeded += 1;
+                for col in lossy_columns {
+                    *precision_losses.entry(col.clone()).or_insert(0) += 1;
+                }
+
+                if let Some(ref syncer) = fts_syncer {
+                    syncer.sync(&tx, tx.last_insert_rowid())?;
+                }
+                if let Some(ref mut splitter) = child_splitter {
+                    splitter.insert(&tx, tx.last_insert_rowid(), &child_tokens)?;
+                }
+
+                if let Some(ref mut keys) = summary.inserted_keys {
+                    keys.push(inserted_key(
+                        &tx,
+                        without_rowid,
+                        &pk_columns,
+                        &columns_to_include,
+                        &params,
+                    ));
+                }
             }
             Err(e) => {
                 error_count += 1;
                 current_progress.failed += 1;
                 eprintln!("Erreur lors de l'insertion de l'objet {}: {}", index, e);
+                let error = RowError::from_sqlite(index, &e);
+                // Échec de la base elle-même (disque plein, verrou, E/S...): le lot est perdu,
+                // l'objet n'est pas rejeté mais réimporté à la reprise
+                if batch_size > 0 && is_fatal_sqlite_error(&e) {
+                    summary.errors.push(error);
+                    batch_failed = true;
+                    break;
+                }
+                // Avec la seconde passe, l'objet n'est rejeté que s'il échoue à nouveau
+                if retry_failed {
+                    failed_rows.push(FailedRow {
+                        index,
+                        params,
+                        present_columns,
+                        child_tokens,
+                        lossy_columns: lossy_columns.into_iter().cloned().collect(),
+                        source: rejects.as_ref().map(|_| source.clone()),
+                    });
+                } else if let Some(ref mut rejects) = rejects {
+                    rejects.write(source, &error.message)?;
+                }
+                summary.errors.push(error);
             }
         }
 
@@ -412,28 +2055,484 @@ where
             progress_callback(current_progress.clone());
         }
     }
-    drop(stmt); // Ceci libère l'emprunt
 
-    // Commit de la transaction
-    match tx.commit() {
-        Ok(_) => {}
-        Err(e) => return Err(format!("Erreur lors du commit de la transaction: {}", e)),
+    // Fin de la lecture en streaming: une erreur de parsing annule la transaction en cours
+    // (les lots déjà validés avec commit_every restent en base)
+    if let Some(finish) = stream_reader {
+        if let Err(e) = finish() {
+            return Err(format!(
+                "Lecture interrompue après {} objet(s): {}",
+                current_progress.processed, e
+            ));
+        }
+
+        // Nombre d'objets connu seulement à la fin de la lecture
+        summary.total = current_progress.processed;
+        if current_progress.processed == 0 && streamed_objects.get() > 0 {
+            let warning = format!(
+                "Aucun des {} objet(s) sélectionné (offset {}{})",
+                streamed_objects.get(),
+                offset_val,
+                limit.map_or(String::new(), |l| format!(", limit {}", l))
+            );
+            eprintln!("Avertissement: {}", warning);
+            summary.warnings.push(warning);
+        }
     }
 
-    // Finalisation
-    current_progress.status = format!(
-        "Importation terminée. Succès: {}, Échecs: {}",
-        success_count, error_count
-    );
-    progress_callback(current_progress.clone());
+    // Seconde passe unique sur les objets en échec (ex: ligne dont la dépendance a été
+    // insérée plus loin dans le fichier), dans la même transaction
+    if retry_failed && !batch_failed && !failed_rows.is_empty() {
+        current_progress.status = format!("Nouvelle tentative pour {} objet(s)...", failed_rows.len());
+        progress_callback(current_progress.clone());
 
-    Ok(current_progress)
-}
+        let mut retry_count: u32 = 0;
+        for row in &failed_rows {
+            let result = match row.present_columns {
+                Some(ref present) => insert_present_columns(
+                    &tx,
+                    table_name,
+                    &columns_to_include,
+                    present,
+                    &row.params,
+                ),
+                None => stmt.execute(params_from_iter(row.params.iter())),
+            };
+            if result.is_err() {
+                // Rejet avec l'erreur de la première tentative, celle du résumé
+                let error = summary.errors.iter().find(|e| e.index == row.index);
+                if let (Some(rejects), Some(source), Some(error)) =
+                    (rejects.as_mut(), &row.source, error)
+                {
+                    rejects.write(source, &error.message)?;
+                }
+            } else {
+                retry_count += 1;
+                success_count += 1;
+                error_count -= 1;
+                current_progress.succeeded += 1;
+                current_progress.failed -= 1;
+                summary.errors.retain(|e| e.index != row.index);
+                for col in &row.lossy_columns {
+                    *precision_losses.entry(col.clone()).or_insert(0) += 1;
+                }
 
-/// Récupère les métadonnées des colonnes d'une table
-fn get_table_columns(conn: &Connection, table_name: &str) -> Result<Vec<ColumnMetadata>, String> {
-    // Utilisation d'une requête SQL directe au lieu de PRAGMA pour plus de robustesse
-    let query = format!(
+                if let Some(ref syncer) = fts_syncer {
+                    syncer.sync(&tx, tx.last_insert_rowid())?;
+                }
+                if let Some(ref mut splitter) = child_splitter {
+                    splitter.insert(&tx, tx.last_insert_rowid(), &row.child_tokens)?;
+                }
+
+                if let Some(ref mut keys) = summary.inserted_keys {
+                    keys.push(inserted_key(
+                        &tx,
+                        without_rowid,
+                        &pk_columns,
+                        &columns_to_include,
+                        &row.params,
+                    ));
+                }
+            }
+        }
+        failed_rows.clear();
+
+        summary.retry_inserted = Some(retry_count);
+    } else if retry_failed {
+        summary.retry_inserted = Some(0);
+    }
+
+    drop(stmt); // Ceci libère l'emprunt
+
+    // Objets en échec sans seconde passe (lot interrompu): rejetés avec leur première erreur
+    if let Some(ref mut rejects) = rejects {
+        for row in &failed_rows {
+            let error = summary.errors.iter().find(|e| e.index == row.index);
+            if let (Some(source), Some(error)) = (&row.source, error) {
+                rejects.write(source, &error.message)?;
+            }
+        }
+        rejects.flush()?;
+    }
+
+    // Échec d'un lot: il est annulé, les lots précédents restent validés et l'importation s'arrête
+    if batch_failed {
+        // SQLite annule parfois lui-même la transaction (ex: disque plein)
+        if !tx.is_autocommit() {
+            if let Err(e) = tx.rollback() {
+                return Err(format!("Erreur lors de l'annulation du lot: {}", e));
+            }
+        }
+
+        if let Some(ref mut keys) = summary.inserted_keys {
+            keys.truncate(committed_success as usize);
+        }
+
+        summary.inserted = committed_success;
+        summary.ignored = committed_ignored;
+        summary.failed = error_count;
+        summary.committed_batches = Some(committed_batches);
+        summary.resume_offset = Some(offset.unwrap_or(0) + committed_objects as u32);
+
+        current_progress.phase = ImportPhase::Failed;
+        current_progress.status = format!(
+            "Importation interrompue au lot {}: {} lot(s) validé(s), reprise possible à l'offset {}",
+            committed_batches + 1,
+            committed_batches,
+            offset.unwrap_or(0) + committed_objects as u32
+        );
+        progress_callback(current_progress.clone());
+
+        summary.duration_ms = start_time.elapsed().as_millis() as u64;
+        return Ok(summary);
+    }
+
+    // En mode remplacement, les anciennes données ne sont supprimées que si tout a réussi
+    if replace_contents && error_count > 0 {
+        if let Err(e) = tx.rollback() {
+            return Err(format!("Erreur lors de l'annulation de la transaction: {}", e));
+        }
+        return Err(format!(
+            "Remplacement annulé: {} objet(s) en échec, les données d'origine ont été conservées",
+            error_count
+        ));
+    }
+
+    summary.inserted = success_count;
+    summary.ignored = ignored_count;
+    summary.failed = error_count;
+
+    let mut missing_paths: Vec<_> = missing_paths.into_iter().collect();
+    missing_paths.sort();
+    for (path, (count, array_path)) in missing_paths {
+        let warning = match array_path {
+            Some(array_path) => format!(
+                "[array_crossed] {} objet(s): {}",
+                count,
+                array_crossing_hint(path, &array_path)
+            ),
+            None => format!("[missing_path] chemin `{}` absent dans {} objet(s)", path, count),
+        };
+        summary.warnings.push(warning);
+    }
+    let mut precision_losses: Vec<_> = precision_losses.into_iter().collect();
+    precision_losses.sort();
+    for (column, count) in precision_losses {
+        summary.warnings.push(format!(
+            "[precision_loss] colonne `{}`: {} nombre(s) stocké(s) en réel avec perte de précision",
+            column, count
+        ));
+    }
+    summary.lookup_cache_hits = lookup_resolver.map(|resolver| resolver.cache_hits);
+    summary.child_rows_inserted = child_splitter.map(|splitter| splitter.rows_inserted);
+    if batch_size > 0 {
+        summary.committed_batches =
+            Some(committed_batches + u32::from(current_progress.processed > 0));
+        summary.resume_offset = Some(offset.unwrap_or(0) + current_progress.processed);
+    }
+
+    // Dernière valeur du compteur persistant, validée avec les données
+    if let (Some(name), Some(next)) = (counter_name, next_counter) {
+        store_counter(&tx, name, next - 1)?;
+    }
+
+    // Point de reprise après le dernier objet, validé avec les données
+    if let Some(name) = checkpoint_name {
+        store_checkpoint(&tx, name, offset.unwrap_or(0) + current_progress.processed)?;
+    }
+
+    // Historique de l'importation, enregistré dans la même transaction que les données
+    if let Some(source_file) = audit_source {
+        summary.duration_ms = start_time.elapsed().as_millis() as u64;
+        record_import(&tx, source_file, table_name, &summary)?;
+    }
+
+    // Provenance des colonnes (chemin JSON → colonne), pour la traçabilité des données
+    if let Some(source_file) = provenance_source {
+        record_provenance(&tx, source_file, table_name, mapping)?;
+    }
+
+    // Simulation avec exécution: les insertions ont été vérifiées, rien n'est conservé
+    if rollback_only {
+        if let Err(e) = tx.rollback() {
+            return Err(format!("Erreur lors de l'annulation de la transaction: {}", e));
+        }
+
+        current_progress.phase = ImportPhase::Done;
+        current_progress.status = format!(
+            "Simulation terminée (transaction annulée). Succès: {}, Échecs: {}",
+            success_count, error_count
+        );
+        progress_callback(current_progress.clone());
+
+        summary.duration_ms = start_time.elapsed().as_millis() as u64;
+        return Ok(summary);
+    }
+
+    // Commit de la transaction
+    current_progress.phase = ImportPhase::Committing;
+    current_progress.status = "Validation de la transaction...".to_string();
+    progress_callback(current_progress.clone());
+
+    match tx.commit() {
+        Ok(_) => {}
+        Err(e) => return Err(format!("Erreur lors du commit de la transaction: {}", e)),
+    }
+
+    // Script de finalisation, dans sa propre transaction: les données importées restent
+    // validées même si le script échoue
+    if let Some(sql) = post_sql.filter(|sql| !sql.trim().is_empty()) {
+        current_progress.phase = ImportPhase::Committing;
+        current_progress.status = "Exécution du script de finalisation...".to_string();
+        progress_callback(current_progress.clone());
+
+        match run_post_sql(conn, sql) {
+            Ok(()) => summary.post_sql_succeeded = Some(true),
+            Err(e) => {
+                eprintln!("Erreur lors du script de finalisation: {}", e);
+                summary.post_sql_succeeded = Some(false);
+                summary.post_sql_error = Some(e);
+            }
+        }
+    }
+
+    // Profil des colonnes importées (min/max/nombre de NULL)
+    if profile_after {
+        summary.column_profiles = Some(profile_columns(
+            &conn,
+            table_name,
+            &table_columns,
+            &columns_to_include,
+        ));
+    }
+
+    // Finalisation
+    current_progress.phase = ImportPhase::Done;
+    current_progress.status = format!(
+        "Importation terminée. Succès: {}, Échecs: {}",
+        success_count, error_count
+    );
+    progress_callback(current_progress.clone());
+
+    summary.duration_ms = start_time.elapsed().as_millis() as u64;
+
+    Ok(summary)
+}
+
+/// Objet à insérer, accompagné de l'objet source d'origine s'il a été normalisé
+type SourceObject<'a> = (Cow<'a, JsonValue>, Option<Cow<'a, JsonValue>>);
+
+/// Objet en échec à l'insertion, conservé pour la seconde passe
+struct FailedRow {
+    index: usize,
+    params: Vec<rusqlite::types::Value>,
+    /// Positions des colonnes présentes (avec `per_row_columns`)
+    present_columns: Option<Vec<usize>>,
+    /// Éléments des lignes enfants, insérées avec la ligne parente
+    child_tokens: Vec<Vec<String>>,
+    /// Colonnes dont le nombre perd en précision (politique `warn`)
+    lossy_columns: Vec<String>,
+    /// Objet source, écrit dans le fichier de rejets s'il échoue encore
+    source: Option<JsonValue>,
+}
+
+/// Erreur qui ne tient pas à la ligne insérée mais à la base elle-même: avec `commit_every`,
+/// elle interrompt l'importation au lieu d'être comptée comme un objet en échec
+fn is_fatal_sqlite_error(error: &rusqlite::Error) -> bool {
+    use rusqlite::ErrorCode::*;
+
+    match error {
+        rusqlite::Error::SqliteFailure(err, _) => matches!(
+            err.code,
+            InternalMalfunction
+                | PermissionDenied
+                | DatabaseBusy
+                | DatabaseLocked
+                | OutOfMemory
+                | ReadOnly
+                | OperationInterrupted
+                | SystemIoFailure
+                | DatabaseCorrupt
+                | DiskFull
+                | CannotOpen
+                | FileLockingProtocolFailed
+                | NoLargeFileSupport
+                | NotADatabase
+        ),
+        _ => false,
+    }
+}
+
+/// Fichier de rejets (NDJSON) auquel chaque objet source en échec est ajouté, avec son erreur
+/// dans `_error` (un objet non JSON-objet est placé dans `_value`)
+struct RejectWriter<'a> {
+    path: &'a str,
+    writer: BufWriter<File>,
+}
+
+impl<'a> RejectWriter<'a> {
+    fn open(path: &'a str) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Impossible d'ouvrir le fichier de rejets {}: {}", path, e))?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+        })
+    }
+
+    fn write(&mut self, source: &JsonValue, error: &str) -> Result<(), String> {
+        let reject = match source {
+            JsonValue::Object(map) => {
+                let mut map = map.clone();
+                map.insert("_error".to_string(), JsonValue::String(error.to_string()));
+                JsonValue::Object(map)
+            }
+            other => json!({ "_value": other, "_error": error }),
+        };
+
+        writeln!(self.writer, "{}", reject).map_err(|e| self.write_error(e))
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        self.writer.flush().map_err(|e| self.write_error(e))
+    }
+
+    fn write_error(&self, error: std::io::Error) -> String {
+        format!("Erreur d'écriture dans le fichier de rejets {}: {}", self.path, error)
+    }
+}
+
+/// Exécute un script SQL dans une transaction dédiée (un point de sauvegarde dans la
+/// transaction de l'appelant), annulée entièrement en cas d'erreur
+pub fn run_post_sql(conn: &mut Connection, sql: &str) -> Result<(), String> {
+    let tx = conn
+        .savepoint()
+        .map_err(|e| format!("Erreur lors de la création de la transaction: {}", e))?;
+
+    tx.execute_batch(sql).map_err(|e| e.to_string())?;
+
+    tx.commit()
+        .map_err(|e| format!("Erreur lors du commit du script de finalisation: {}", e))
+}
+
+/// Insertion limitée aux colonnes présentes dans l'objet (positions dans `columns`), les autres
+/// recevant leur valeur par défaut en base. La requête est préparée une fois par ensemble de
+/// colonnes (cache des statements de la connexion).
+fn insert_present_columns(
+    conn: &Connection,
+    table_name: &str,
+    columns: &[String],
+    present: &[usize],
+    params: &[rusqlite::types::Value],
+) -> rusqlite::Result<usize> {
+    let query = if present.is_empty() {
+        format!("INSERT INTO {} DEFAULT VALUES", quote_identifier(table_name))
+    } else {
+        format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_identifier(table_name),
+            present
+                .iter()
+                .map(|&position| quote_identifier(&columns[position]))
+                .collect::<Vec<_>>()
+                .join(", "),
+            vec!["?"; present.len()].join(", ")
+        )
+    };
+
+    let mut stmt = conn.prepare_cached(&query)?;
+    stmt.execute(params_from_iter(present.iter().map(|&position| &params[position])))
+}
+
+/// Clé d'une ligne insérée: rowid, ou valeurs de la clé primaire pour une table WITHOUT ROWID
+fn inserted_key(
+    conn: &Connection,
+    without_rowid: bool,
+    pk_columns: &[String],
+    columns_to_include: &[String],
+    params: &[rusqlite::types::Value],
+) -> JsonValue {
+    if without_rowid {
+        let pk_values: Vec<JsonValue> = pk_columns
+            .iter()
+            .map(|pk| {
+                columns_to_include
+                    .iter()
+                    .position(|col| col == pk)
+                    .map_or(JsonValue::Null, |pos| {
+                        sqlite_value_to_json(ValueRef::from(&params[pos]))
+                    })
+            })
+            .collect();
+        JsonValue::Array(pk_values)
+    } else {
+        JsonValue::Number(conn.last_insert_rowid().into())
+    }
+}
+
+/// Nombre maximal de valeurs par clause IN (sous la limite de 999 paramètres des anciens SQLite)
+const EXISTING_KEYS_CHUNK_SIZE: usize = 500;
+
+/// Représentation textuelle d'une clé, commune aux valeurs JSON et aux valeurs lues en base
+pub fn key_to_string(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Retourne l'ensemble des clés déjà présentes dans la table, par lots de requêtes
+/// `SELECT key FROM table WHERE key IN (?, ?, ...)`
+pub fn find_existing_keys(
+    conn: &Connection,
+    table_name: &str,
+    key_column: &str,
+    keys: &[String],
+) -> Result<HashSet<String>, String> {
+    let mut existing = HashSet::new();
+
+    for chunk in keys.chunks(EXISTING_KEYS_CHUNK_SIZE) {
+        let query = format!(
+            "SELECT {col} FROM {table} WHERE {col} IN ({placeholders})",
+            col = quote_identifier(key_column),
+            table = quote_identifier(table_name),
+            placeholders = vec!["?"; chunk.len()].join(", ")
+        );
+
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| format!("Erreur lors de la recherche des clés existantes: {}", e))?;
+        let mut rows = stmt
+            .query(params_from_iter(chunk.iter()))
+            .map_err(|e| format!("Erreur lors de la recherche des clés existantes: {}", e))?;
+
+        while let Some(row) = rows
+            .next()
+            .map_err(|e| format!("Erreur lors de la recherche des clés existantes: {}", e))?
+        {
+            let value = row
+                .get_ref(0)
+                .map_err(|e| format!("Erreur lors de la recherche des clés existantes: {}", e))?;
+            if let Some(key) = key_to_string(&sqlite_value_to_json(value)) {
+                existing.insert(key);
+            }
+        }
+    }
+
+    Ok(existing)
+}
+
+/// Récupère les métadonnées des colonnes d'une table
+pub fn get_table_columns(
+    conn: &Connection,
+    table_name: &str,
+) -> Result<Vec<ColumnMetadata>, String> {
+    // Utilisation d'une requête SQL directe au lieu de PRAGMA pour plus de robustesse
+    let query = format!(
         "SELECT * FROM pragma_table_info('{}') ORDER BY cid",
         table_name.replace("'", "''") // Échapper les apostrophes pour éviter les injections SQL
     );
@@ -469,10 +2568,11 @@ fn get_table_columns(conn: &Connection, table_name: &str) -> Result<Vec<ColumnMe
             Err(e) => return Err(format!("Erreur lors de la lecture de la contrainte NOT NULL: {}", e)),
         };
         
-        let primary_key = match row.get::<_, i32>(5) {
-            Ok(value) => value == 1,
+        let pk_position = match row.get::<_, i32>(5) {
+            Ok(value) => value,
             Err(e) => return Err(format!("Erreur lors de la lecture de la clé primaire: {}", e)),
         };
+        let primary_key = pk_position == 1;
         
         let default_value = match row.get_ref(4) {
             Ok(cell) if cell.data_type() == rusqlite::types::Type::Null => None,
@@ -490,6 +2590,7 @@ fn get_table_columns(conn: &Connection, table_name: &str) -> Result<Vec<ColumnMe
             data_type,
             not_null,
             primary_key,
+            pk_position,
             default_value,
         });
     }
@@ -503,8 +2604,286 @@ fn get_table_columns(conn: &Connection, table_name: &str) -> Result<Vec<ColumnMe
     Ok(columns)
 }
 
+/// Calcule min/max/nombre de NULL pour chaque colonne importée
+fn profile_columns(
+    conn: &Connection,
+    table_name: &str,
+    table_columns: &[ColumnMetadata],
+    columns: &[String],
+) -> Vec<ColumnProfile> {
+    let mut profiles = Vec::new();
+
+    for column in columns {
+        let quoted_column = quote_identifier(column);
+
+        // MIN/MAX n'ont pas de sens pour les BLOB, on ne compte que les NULL
+        let is_blob = table_columns
+            .iter()
+            .find(|c| c.name == *column)
+            .map_or(false, |c| c.data_type.to_uppercase().contains("BLOB"));
+
+        let query = if is_blob {
+            format!(
+                "SELECT NULL, NULL, COUNT({col}), COUNT(*) - COUNT({col}) FROM {table}",
+                col = quoted_column,
+                table = quote_identifier(table_name)
+            )
+        } else {
+            format!(
+                "SELECT MIN({col}), MAX({col}), COUNT({col}), COUNT(*) - COUNT({col}) FROM {table}",
+                col = quoted_column,
+                table = quote_identifier(table_name)
+            )
+        };
+
+        let result = conn.query_row(&query, [], |row| {
+            let min = sqlite_value_to_json(row.get_ref(0)?);
+            let max = sqlite_value_to_json(row.get_ref(1)?);
+            Ok(ColumnProfile {
+                column: column.clone(),
+                min: if min.is_null() { None } else { Some(min) },
+                max: if max.is_null() { None } else { Some(max) },
+                non_null_count: row.get(2)?,
+                null_count: row.get(3)?,
+            })
+        });
+
+        match result {
+            Ok(profile) => profiles.push(profile),
+            Err(e) => eprintln!("Erreur lors du profilage de la colonne {}: {}", column, e),
+        }
+    }
+
+    profiles
+}
+
+/// Indique si la table est déclarée WITHOUT ROWID
+fn is_without_rowid(conn: &Connection, table_name: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type='table' AND name = ?",
+        [table_name],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .map(|sql| {
+        sql.map_or(false, |s| {
+            s.to_uppercase()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .contains("WITHOUT ROWID")
+        })
+    })
+    .or_else(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => Ok(false),
+        e => Err(format!("Erreur lors de la lecture du schéma de la table: {}", e)),
+    })
+}
+
+/// Récupère les colonnes générées (virtuelles ou stockées) d'une table, que `table_info`
+/// ne liste pas, avec les colonnes de la table référencées par leur expression
+fn get_generated_columns(
+    conn: &Connection,
+    table_name: &str,
+    table_columns: &[ColumnMetadata],
+) -> Result<Vec<(String, Vec<String>)>, String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM pragma_table_xinfo(?) WHERE hidden IN (2, 3) ORDER BY cid")
+        .map_err(|e| format!("Erreur lors de la lecture des colonnes générées: {}", e))?;
+
+    let generated = stmt
+        .query_map([table_name], |row| row.get::<_, String>(0))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Erreur lors de la lecture des colonnes générées: {}", e))?;
+
+    if generated.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql: String = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE type='table' AND name = ?",
+            [table_name],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Erreur lors de la lecture du schéma de la table: {}", e))?;
+
+    let definitions = split_column_definitions(&sql);
+
+    Ok(generated
+        .into_iter()
+        .map(|column| {
+            let dependencies = definitions
+                .iter()
+                .find(|def| definition_name(def).eq_ignore_ascii_case(&column))
+                .and_then(|def| generation_expression(def))
+                .map(|expression| {
+                    let mut dependencies: Vec<String> = Vec::new();
+                    for identifier in expression_identifiers(expression) {
+                        if let Some(col) = table_columns
+                            .iter()
+                            .find(|c| c.name.eq_ignore_ascii_case(&identifier))
+                        {
+                            if !dependencies.contains(&col.name) {
+                                dependencies.push(col.name.clone());
+                            }
+                        }
+                    }
+                    dependencies
+                })
+                .unwrap_or_default();
+            (column, dependencies)
+        })
+        .collect())
+}
+
+/// Découpe le corps d'un CREATE TABLE en définitions de colonnes et de contraintes,
+/// en ignorant les virgules entre parenthèses ou dans des chaînes et identifiants quotés
+fn split_column_definitions(sql: &str) -> Vec<&str> {
+    let Some(start) = sql.find('(') else {
+        return Vec::new();
+    };
+
+    let mut definitions = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut part_start = start + 1;
+
+    for (pos, c) in sql.char_indices().skip_while(|(pos, _)| *pos <= start) {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '[' => quote = Some(']'),
+                '(' => depth += 1,
+                ')' if depth == 0 => {
+                    definitions.push(sql[part_start..pos].trim());
+                    break;
+                }
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    definitions.push(sql[part_start..pos].trim());
+                    part_start = pos + 1;
+                }
+                _ => {}
+            },
+        }
+    }
+
+    definitions
+}
+
+/// Nom (sans guillemets) déclaré en tête d'une définition de colonne
+fn definition_name(definition: &str) -> String {
+    let mut chars = definition.chars();
+    match chars.next() {
+        Some(open @ ('"' | '`' | '[')) => {
+            let close = if open == '[' { ']' } else { open };
+            chars.take_while(|c| *c != close).collect()
+        }
+        _ => definition
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+/// Expression entre parenthèses qui suit le mot-clé AS d'une colonne générée
+fn generation_expression(definition: &str) -> Option<&str> {
+    // Majuscules ASCII uniquement, pour conserver les positions en octets de la définition
+    let upper = definition.to_ascii_uppercase();
+    let bytes = upper.as_bytes();
+
+    let mut search_from = 0;
+    while let Some(found) = upper[search_from..].find("AS") {
+        let pos = search_from + found;
+        search_from = pos + 2;
+
+        let word_start =
+            pos == 0 || !(bytes[pos - 1].is_ascii_alphanumeric() || bytes[pos - 1] == b'_');
+        let rest = upper[pos + 2..].trim_start();
+        if !word_start || !rest.starts_with('(') {
+            continue;
+        }
+
+        let open = upper.len() - rest.len();
+        let mut depth = 0;
+        for (offset, c) in definition[open..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(&definition[open + 1..open + offset]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    None
+}
+
+/// Identifiants d'une expression SQL (les chaînes littérales sont ignorées)
+fn expression_identifiers(expression: &str) -> Vec<String> {
+    let mut identifiers = Vec::new();
+    let mut current = String::new();
+    let mut in_literal = false;
+
+    for c in expression.chars() {
+        if c == '\'' {
+            in_literal = !in_literal;
+            current.clear();
+        } else if !in_literal && (c.is_alphanumeric() || c == '_') {
+            current.push(c);
+        } else if !current.is_empty() {
+            identifiers.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() && !in_literal {
+        identifiers.push(current);
+    }
+
+    identifiers
+}
+
+/// Indique si la cible est une vue plutôt qu'une table
+fn is_view(conn: &Connection, name: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='view' AND name = ?",
+        [name],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count > 0)
+    .map_err(|e| format!("Erreur lors de la détection du type de la cible: {}", e))
+}
+
+/// Récupère les noms des triggers définis sur une table
+fn get_table_triggers(conn: &Connection, table_name: &str) -> Result<Vec<String>, String> {
+    let mut stmt = match conn
+        .prepare("SELECT name FROM sqlite_master WHERE type='trigger' AND tbl_name = ?")
+    {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            return Err(format!(
+                "Erreur lors de la préparation de la requête des triggers: {}",
+                e
+            ))
+        }
+    };
+
+    let triggers = stmt
+        .query_map([table_name], |row| row.get(0))
+        .and_then(|rows| rows.collect::<Result<Vec<String>, _>>())
+        .map_err(|e| format!("Erreur lors de la récupération des triggers: {}", e))?;
+
+    Ok(triggers)
+}
+
 /// Récupère les colonnes avec contrainte d'unicité
-fn get_unique_columns(conn: &Connection, table_name: &str) -> Result<Vec<String>, String> {
+pub fn get_unique_columns(conn: &Connection, table_name: &str) -> Result<Vec<String>, String> {
     let mut unique_columns = Vec::new();
 
     // Utilisation d'une requête SQL directe pour récupérer les index
@@ -594,4 +2973,1749 @@ fn get_unique_columns(conn: &Connection, table_name: &str) -> Result<Vec<String>
     println!("Trouvé {} colonnes avec contrainte unique pour la table '{}'", unique_columns.len(), table_name);
     
     Ok(unique_columns)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::DerivedKeyStrategy;
+    use crate::db::test_support::{query_column, temp_db, temp_json, temp_path};
+
+    fn read(json_path: &str, json_root: &str, threshold: u64) -> JsonSource {
+        read_json_source(
+            json_path,
+            json_root,
+            &HttpOptions::default(),
+            false,
+            false,
+            threshold,
+            |_| {},
+        )
+        .unwrap()
+    }
+
+    fn import(json_path: &str, source: JsonSource, json_root: &str) -> (String, ImportSummary) {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER, name TEXT);");
+        let mapping = HashMap::from([
+            ("id".to_string(), "id".to_string()),
+            ("name".to_string(), "name".to_string()),
+        ]);
+        let root = RootOptions {
+            json_root,
+            ..Default::default()
+        };
+        let summary = insert_json_data(
+            json_path,
+            source,
+            &db_path,
+            "items",
+            &mapping,
+            root,
+            InsertOptions::default(),
+            |_| {},
+        )
+        .unwrap();
+        (db_path, summary)
+    }
+
+    const ITEMS: &str =
+        r#"[{"id": 1, "name": "a"}, {"id": 2, "name": "b"}, {"id": 3, "name": "c"}]"#;
+
+    #[test]
+    fn small_file_is_read_in_memory() {
+        let json_path = temp_json(ITEMS);
+        let source = read(&json_path, "", DEFAULT_STREAMING_THRESHOLD_BYTES);
+
+        assert_eq!(source.streamed, Some(false));
+        assert!(source.elements.is_none());
+        assert_eq!(source.json_data.as_array().map(Vec::len), Some(3));
+    }
+
+    #[test]
+    fn file_above_threshold_is_streamed_into_insert() {
+        let json_path = temp_json(ITEMS);
+        let source = read(&json_path, "", 0);
+
+        assert_eq!(source.streamed, Some(true));
+        assert!(source.json_data.is_null());
+
+        let (db_path, summary) = import(&json_path, source, "");
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.inserted, 3);
+        assert_eq!(summary.streamed, Some(true));
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn nested_root_path_is_streamed() {
+        let json_path = temp_json(&format!(
+            r#"{{"meta": {{"count": 3}}, "response": {{"data": {{"records": {}}}}}}}"#,
+            ITEMS
+        ));
+        let source = read(&json_path, "response.data.records[]", 0);
+        assert_eq!(source.streamed, Some(true));
+
+        let (db_path, summary) = import(&json_path, source, "response.data.records[]");
+        assert_eq!(summary.inserted, 3);
+        let names: Vec<String> = query_column(&db_path, "SELECT name FROM items ORDER BY id");
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn root_path_through_several_arrays_is_read_in_memory() {
+        let json_path = temp_json(&format!(r#"{{"pages": [{{"records": {}}}]}}"#, ITEMS));
+        let source = read(&json_path, "pages[].records[]", 0);
+        assert_eq!(source.streamed, Some(false));
+
+        let (_, summary) = import(&json_path, source, "pages[].records[]");
+        assert_eq!(summary.inserted, 3);
+    }
+
+    #[test]
+    fn precision_loss_is_counted_for_inserted_rows_only() {
+        let json_path = temp_json(
+            r#"[{"id": 1, "amount": 12345678901234567.89},
+                {"id": null, "amount": 98765432109876543.21},
+                {"id": 3, "amount": 1.5}]"#,
+        );
+        let db_path = temp_db("CREATE TABLE items (id INTEGER NOT NULL, amount REAL);");
+        let numbers = LossyNumbers::from_file(&json_path).unwrap();
+        let mapping = HashMap::from([
+            ("id".to_string(), "id".to_string()),
+            ("amount".to_string(), "amount".to_string()),
+        ]);
+        let options = InsertOptions {
+            precision_loss: Some(PrecisionLossPolicy::Warn),
+            lossy_numbers: Some(&numbers),
+            ..Default::default()
+        };
+
+        let source = read(&json_path, "", DEFAULT_STREAMING_THRESHOLD_BYTES);
+        let summary = insert_json_data(
+            &json_path,
+            source,
+            &db_path,
+            "items",
+            &mapping,
+            RootOptions::default(),
+            options,
+            |_| {},
+        )
+        .unwrap();
+
+        assert_eq!((summary.inserted, summary.failed), (2, 1));
+        assert!(summary
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("[precision_loss] colonne `amount`: 1 nombre(s)")));
+    }
+
+    fn import_not_null(per_row_columns: bool) -> (String, ImportSummary) {
+        let json_path = temp_json(
+            r#"[{"id": 1, "name": "a", "code": "c"},
+                {"id": 2, "name": null, "code": "c"},
+                {"id": 3, "name": "b", "code": null},
+                {"id": 4, "name": "c"}]"#,
+        );
+        let db_path = temp_db(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL, \
+             code TEXT NOT NULL DEFAULT 'x');",
+        );
+        let mapping = HashMap::from([
+            ("id".to_string(), "id".to_string()),
+            ("name".to_string(), "name".to_string()),
+            ("code".to_string(), "code".to_string()),
+        ]);
+        let options = InsertOptions {
+            per_row_columns,
+            ..Default::default()
+        };
+        let source = read(&json_path, "", DEFAULT_STREAMING_THRESHOLD_BYTES);
+        let summary = insert_json_data(
+            &json_path,
+            source,
+            &db_path,
+            "items",
+            &mapping,
+            RootOptions::default(),
+            options,
+            |_| {},
+        )
+        .unwrap();
+        (db_path, summary)
+    }
+
+    #[test]
+    fn null_in_not_null_column_is_rejected_before_insert() {
+        let (db_path, summary) = import_not_null(false);
+
+        assert_eq!((summary.inserted, summary.failed), (1, 3));
+        let messages: Vec<&str> = summary.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Colonne NOT NULL name nulle dans l'objet 1",
+                "Colonne NOT NULL code nulle dans l'objet 2",
+                "Colonne NOT NULL code nulle dans l'objet 3",
+            ]
+        );
+        assert!(summary
+            .errors
+            .iter()
+            .all(|e| e.extended_code == Some(rusqlite::ffi::SQLITE_CONSTRAINT_NOTNULL)));
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items");
+        assert_eq!(ids, [1]);
+    }
+
+    #[test]
+    fn omitted_null_column_takes_its_default() {
+        let (db_path, summary) = import_not_null(true);
+
+        assert_eq!((summary.inserted, summary.failed), (2, 2));
+        assert_eq!(summary.errors[1].message, "Colonne NOT NULL code nulle dans l'objet 2");
+        let codes: Vec<String> = query_column(&db_path, "SELECT code FROM items ORDER BY id");
+        assert_eq!(codes, ["c", "x"]);
+    }
+
+    fn import_flatten_all(db_path: &str, dry_run_mode: Option<DryRunMode>) -> ImportSummary {
+        let json_path = temp_json(r#"[{"id": 1, "user": {"name": "a", "city": "x"}}]"#);
+        let root = RootOptions {
+            flatten_all: true,
+            ..Default::default()
+        };
+        let options = InsertOptions {
+            dry_run: dry_run_mode.is_some(),
+            dry_run_mode: dry_run_mode.unwrap_or_default(),
+            ..Default::default()
+        };
+        let source = read(&json_path, "", DEFAULT_STREAMING_THRESHOLD_BYTES);
+        insert_json_data(
+            &json_path,
+            source,
+            db_path,
+            "items",
+            &HashMap::new(),
+            root,
+            options,
+            |_| {},
+        )
+        .unwrap()
+    }
+
+    fn item_columns(db_path: &str) -> Vec<String> {
+        query_column(db_path, "SELECT name FROM pragma_table_info('items') ORDER BY cid")
+    }
+
+    #[test]
+    fn flatten_all_columns_are_rolled_back_with_the_simulation() {
+        let db_path = temp_db("CREATE TABLE items (id TEXT);");
+
+        let summary = import_flatten_all(&db_path, Some(DryRunMode::ExecuteRollback));
+
+        assert_eq!(summary.inserted, 1);
+        assert!(summary.warnings.iter().any(|w| w.starts_with("[flatten_all] 2 colonne(s)")));
+        assert_eq!(item_columns(&db_path), ["id"]);
+        let ids: Vec<String> = query_column(&db_path, "SELECT id FROM items");
+        assert!(ids.is_empty());
+    }
+
+    #[test]
+    fn flatten_all_simulation_does_not_create_the_table() {
+        let db_path = temp_db("");
+
+        let summary = import_flatten_all(&db_path, Some(DryRunMode::ExecuteRollback));
+
+        assert_eq!(summary.inserted, 1);
+        let tables: Vec<String> = query_column(&db_path, "SELECT name FROM sqlite_master");
+        assert!(tables.is_empty());
+    }
+
+    #[test]
+    fn flatten_all_count_simulation_touches_nothing() {
+        let db_path = temp_db("CREATE TABLE items (id TEXT);");
+
+        import_flatten_all(&db_path, Some(DryRunMode::Count));
+
+        assert_eq!(item_columns(&db_path), ["id"]);
+    }
+
+    #[test]
+    fn flatten_all_adds_columns_in_the_import_transaction() {
+        let db_path = temp_db("CREATE TABLE items (id TEXT);");
+
+        let summary = import_flatten_all(&db_path, None);
+
+        assert_eq!(summary.inserted, 1);
+        assert_eq!(item_columns(&db_path), ["id", "user.city", "user.name"]);
+        let cities: Vec<String> = query_column(&db_path, "SELECT \"user.city\" FROM items");
+        assert_eq!(cities, ["x"]);
+    }
+
+    #[test]
+    fn streamed_parse_error_aborts_import() {
+        let json_path = temp_json(r#"[{"id": 1, "name": "a"}, {"id": 2, "name": }]"#);
+        let source = read(&json_path, "", 0);
+
+        let db_path = temp_db("CREATE TABLE items (id INTEGER, name TEXT);");
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        let result = insert_json_data(
+            &json_path,
+            source,
+            &db_path,
+            "items",
+            &mapping,
+            RootOptions::default(),
+            InsertOptions::default(),
+            |_| {},
+        );
+
+        assert!(result.is_err());
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items");
+        assert!(ids.is_empty());
+    }
+
+    /// Insère les objets du tableau `objects` dans la table `items` créée par `schema`, chaque
+    /// colonne de `columns` recevant le chemin de même nom
+    fn insert_items(
+        schema: &str,
+        objects: JsonValue,
+        columns: &[&str],
+        options: InsertOptions,
+    ) -> (String, Result<ImportSummary, String>) {
+        let mapping: Vec<(&str, &str)> = columns.iter().map(|column| (*column, *column)).collect();
+        insert_mapped(schema, objects, &mapping, options)
+    }
+
+    /// Comme `insert_items`, avec un mapping (chemin, colonne) explicite
+    fn insert_mapped(
+        schema: &str,
+        objects: JsonValue,
+        mapping: &[(&str, &str)],
+        options: InsertOptions,
+    ) -> (String, Result<ImportSummary, String>) {
+        let db_path = temp_db(schema);
+        let mapping: HashMap<String, String> = mapping
+            .iter()
+            .map(|(path, column)| (path.to_string(), column.to_string()))
+            .collect();
+        let objects = objects.as_array().cloned().unwrap_or_default();
+        let result = insert_objects(objects.into(), &db_path, "items", &mapping, options, |_| {});
+        (db_path, result)
+    }
+
+    #[test]
+    fn insert_trigger_follows_trigger_policy() {
+        let schema = "CREATE TABLE items (id INTEGER);
+             CREATE TABLE log (id INTEGER);
+             CREATE TRIGGER log_items AFTER INSERT ON items
+             BEGIN INSERT INTO log VALUES (new.id); END;";
+        let objects = json!([{"id": 1}]);
+
+        let options = InsertOptions {
+            trigger_policy: TriggerPolicy::Error,
+            ..Default::default()
+        };
+        let (db_path, result) = insert_items(schema, objects.clone(), &["id"], options);
+        let error = result.unwrap_err();
+        assert!(error.contains("log_items"), "{}", error);
+        let logged: Vec<i64> = query_column(&db_path, "SELECT id FROM log");
+        assert!(logged.is_empty());
+
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&statuses);
+        let db_path = temp_db(schema);
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        let options = InsertOptions {
+            trigger_policy: TriggerPolicy::Warn,
+            ..Default::default()
+        };
+        let summary = insert_objects(
+            vec![json!({"id": 1})].into(),
+            &db_path,
+            "items",
+            &mapping,
+            options,
+            move |progress| seen.lock().unwrap().push(progress.status),
+        )
+        .unwrap();
+        assert_eq!(summary.inserted, 1);
+        assert_eq!(summary.warnings, ["La table 'items' possède des triggers: log_items"]);
+        assert!(statuses
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|status| status.starts_with("Avertissement") && status.contains("log_items")));
+
+        let options = InsertOptions {
+            trigger_policy: TriggerPolicy::Allow,
+            ..Default::default()
+        };
+        let (db_path, result) = insert_items(schema, objects, &["id"], options);
+        assert_eq!(result.unwrap().inserted, 1);
+        let logged: Vec<i64> = query_column(&db_path, "SELECT id FROM log");
+        assert_eq!(logged, [1]);
+    }
+
+    #[test]
+    fn replace_contents_keeps_original_rows_when_an_object_fails() {
+        let schema = "CREATE TABLE items (id INTEGER NOT NULL, name TEXT);
+             INSERT INTO items VALUES (10, 'old'), (11, 'old');";
+        let options = InsertOptions {
+            replace_contents: true,
+            ..Default::default()
+        };
+        let objects = json!([{"id": 1, "name": "new"}, {"id": null, "name": "bad"}]);
+
+        let (db_path, result) = insert_items(schema, objects, &["id", "name"], options);
+
+        assert!(result.unwrap_err().starts_with("Remplacement annulé: 1 objet(s) en échec"));
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, [10, 11]);
+    }
+
+    #[test]
+    fn replace_contents_swaps_rows_when_everything_succeeds() {
+        let schema = "CREATE TABLE items (id INTEGER NOT NULL, name TEXT);
+             INSERT INTO items VALUES (10, 'old');";
+        let options = InsertOptions {
+            replace_contents: true,
+            ..Default::default()
+        };
+        let objects = json!([{"id": 1, "name": "new"}, {"id": 2, "name": "new"}]);
+
+        let (db_path, result) = insert_items(schema, objects, &["id", "name"], options);
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, [1, 2]);
+    }
+
+    #[test]
+    fn duplicate_targets_follow_the_policy() {
+        let schema = "CREATE TABLE items (id INTEGER, email TEXT);";
+        let objects = json!([
+            {"id": 1, "mail": "a@x", "email": null},
+            {"id": 2, "mail": "b@x", "email": "b@y"},
+        ]);
+        let mapping = [("id", "id"), ("email", "email"), ("mail", "email")];
+
+        let options = InsertOptions {
+            duplicate_target_policy: DuplicateTargetPolicy::Error,
+            ..Default::default()
+        };
+        let (_, result) = insert_mapped(schema, objects.clone(), &mapping, options);
+        assert_eq!(
+            result.unwrap_err(),
+            "Colonnes ciblées par plusieurs chemins JSON: email (email, mail)"
+        );
+
+        let options = InsertOptions {
+            duplicate_target_policy: DuplicateTargetPolicy::Coalesce,
+            ..Default::default()
+        };
+        let (db_path, result) = insert_mapped(schema, objects, &mapping, options);
+        assert_eq!(result.unwrap().inserted, 2);
+        let emails: Vec<String> = query_column(&db_path, "SELECT email FROM items ORDER BY id");
+        assert_eq!(emails, ["a@x", "b@y"]);
+    }
+
+    #[test]
+    fn reset_autoincrement_restarts_ids_after_replace() {
+        let schema = "CREATE TABLE items (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT);
+             INSERT INTO items (name) VALUES ('a'), ('b'), ('c');";
+        let objects = json!([{"name": "x"}, {"name": "y"}]);
+
+        for (reset, expected) in [(false, [4, 5]), (true, [1, 2])] {
+            let options = InsertOptions {
+                replace_contents: true,
+                reset_autoincrement: reset,
+                ..Default::default()
+            };
+            let (db_path, result) = insert_items(schema, objects.clone(), &["name"], options);
+
+            assert_eq!(result.unwrap().inserted, 2);
+            let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+            assert_eq!(ids, expected, "reset_autoincrement: {}", reset);
+        }
+    }
+
+    #[test]
+    fn rows_are_inserted_through_a_writable_view() {
+        let schema = "CREATE TABLE base (
+                 id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE, source TEXT NOT NULL);
+             CREATE VIEW items AS SELECT id, name FROM base;
+             CREATE TRIGGER items_insert INSTEAD OF INSERT ON items
+             BEGIN INSERT INTO base VALUES (new.id, upper(new.name), 'json'); END;";
+        let objects = json!([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]);
+
+        let (db_path, result) = insert_items(schema, objects, &["id", "name"], Default::default());
+
+        let summary = result.unwrap();
+        assert_eq!(summary.inserted, 2);
+        let rows: Vec<String> =
+            query_column(&db_path, "SELECT id || ':' || name || ':' || source FROM base");
+        assert_eq!(rows, ["1:A:json", "2:B:json"]);
+    }
+
+    #[test]
+    fn inserted_keys_follow_the_table_kind() {
+        let objects = json!([
+            {"region": "eu", "code": 7, "name": "a"},
+            {"region": "us", "code": 7},
+        ]);
+        let columns = ["region", "code", "name"];
+        let options = || InsertOptions {
+            return_inserted_keys: true,
+            ..Default::default()
+        };
+
+        let schema = "CREATE TABLE items (region TEXT, code INTEGER, name TEXT,
+             PRIMARY KEY (code, region)) WITHOUT ROWID;";
+        let (_, result) = insert_items(schema, objects.clone(), &columns, options());
+        let keys = result.unwrap().inserted_keys;
+        assert_eq!(keys, Some(vec![json!([7, "eu"]), json!([7, "us"])]));
+
+        let schema = "CREATE TABLE items (region TEXT, code INTEGER, name TEXT);
+             INSERT INTO items VALUES ('old', 1, NULL);";
+        let (_, result) = insert_items(schema, objects, &columns, options());
+        assert_eq!(result.unwrap().inserted_keys, Some(vec![json!(2), json!(3)]));
+    }
+
+    #[test]
+    fn dates_are_converted_to_epoch_integers() {
+        let objects = json!([
+            {"id": 1, "at": "2024-03-01T12:00:00.250+01:00", "day": "2024-03-01"},
+            {"id": 2, "at": "pas une date", "day": "2024-03-01"},
+            {"id": 3, "at": 1700000000000_i64, "day": null},
+        ]);
+        let options = InsertOptions {
+            epoch_columns: Some(HashMap::from([
+                ("at".to_string(), EpochResolution::Milliseconds),
+                ("day".to_string(), EpochResolution::Seconds),
+            ])),
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(
+            "CREATE TABLE items (id INTEGER, at INTEGER, day INTEGER);",
+            objects,
+            &["id", "at", "day"],
+            options,
+        );
+
+        let summary = result.unwrap();
+        assert_eq!(summary.inserted, 2);
+        assert_eq!(summary.errors.len(), 1);
+        assert_eq!(summary.errors[0].index, 1);
+        assert!(summary.errors[0].message.contains("Date invalide"), "{:?}", summary.errors);
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT typeof(at) || ':' || at || ':' || ifnull(day, '-') FROM items ORDER BY id",
+        );
+        assert_eq!(rows, ["integer:1709290800250:1709251200", "integer:1700000000000:-"]);
+    }
+
+    #[test]
+    fn row_errors_carry_sqlite_result_codes() {
+        let objects = json!([
+            {"id": 1, "code": "a"},
+            {"id": 2, "code": "a"},
+            {"id": null, "code": "b"},
+            {"id": 4, "code": "c", "at": "hier"},
+        ]);
+        let options = InsertOptions {
+            epoch_columns: Some(HashMap::from([("at".to_string(), EpochResolution::Seconds)])),
+            ..Default::default()
+        };
+
+        let (_, result) = insert_items(
+            "CREATE TABLE items (id INTEGER NOT NULL, code TEXT UNIQUE, at INTEGER);",
+            objects,
+            &["id", "code", "at"],
+            options,
+        );
+
+        let errors = result.unwrap().errors;
+        let codes: Vec<_> = errors
+            .iter()
+            .map(|e| (e.index, e.sqlite_code, e.extended_code))
+            .collect();
+        assert_eq!(
+            codes,
+            [(1, Some(19), Some(2067)), (2, Some(19), Some(1299)), (3, None, None)]
+        );
+        assert!(errors.iter().all(|e| !e.message.is_empty()));
+    }
+
+    #[test]
+    fn only_absent_keys_are_inserted() {
+        let schema = "CREATE TABLE items (code TEXT, label TEXT);
+             INSERT INTO items VALUES ('a', 'ancien'), ('c', 'ancien');";
+        let objects = json!([
+            {"code": "a", "label": "nouveau"},
+            {"code": "b", "label": "nouveau"},
+            {"code": "c", "label": "nouveau"},
+            {"code": "b", "label": "doublon"},
+            {"code": "d", "label": "nouveau"},
+        ]);
+        let options = InsertOptions {
+            skip_existing_key: Some("code".to_string()),
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(schema, objects, &["code", "label"], options);
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let rows: Vec<String> =
+            query_column(&db_path, "SELECT code || ':' || label FROM items ORDER BY code");
+        assert_eq!(rows, ["a:ancien", "b:nouveau", "c:ancien", "d:nouveau"]);
+    }
+
+    #[test]
+    fn existing_keys_are_looked_up_in_chunks() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+        let conn = Connection::open(&db_path).unwrap();
+        for id in (0..1500).step_by(3) {
+            conn.execute("INSERT INTO items VALUES (?)", [id]).unwrap();
+        }
+
+        let keys: Vec<String> = (0..1500).map(|id| id.to_string()).collect();
+        assert!(keys.len() > 2 * EXISTING_KEYS_CHUNK_SIZE);
+        let existing = find_existing_keys(&conn, "items", "id", &keys).unwrap();
+
+        assert_eq!(existing.len(), 500);
+        assert!(existing.contains("0") && existing.contains("1497"));
+        assert!(!existing.contains("1"));
+    }
+
+    /// Importation de `json` dans la table `items` avec `root` et le mapping (chemin, colonne)
+    fn import_root(
+        json: &str,
+        schema: &str,
+        mapping: &[(&str, &str)],
+        root: RootOptions,
+    ) -> (String, ImportSummary) {
+        let json_path = temp_json(json);
+        let db_path = temp_db(schema);
+        let source = read(&json_path, root.json_root, DEFAULT_STREAMING_THRESHOLD_BYTES);
+        let mapping: HashMap<String, String> = mapping
+            .iter()
+            .map(|(path, column)| (path.to_string(), column.to_string()))
+            .collect();
+        let summary = insert_json_data(
+            &json_path,
+            source,
+            &db_path,
+            "items",
+            &mapping,
+            root,
+            InsertOptions::default(),
+            |_| {},
+        )
+        .unwrap();
+        (db_path, summary)
+    }
+
+    #[test]
+    fn scalar_elements_among_objects_follow_skip_non_objects() {
+        let json = r#"[{"id": 1, "name": "a"}, "note", {"id": 2, "name": "b"}, [3]]"#;
+        let schema = "CREATE TABLE items (id INTEGER, name TEXT);";
+        let mapping = [("id", "id"), ("name", "name")];
+
+        let root = RootOptions {
+            skip_non_objects: true,
+            ..Default::default()
+        };
+        let (db_path, summary) = import_root(json, schema, &mapping, root);
+        assert_eq!((summary.total, summary.inserted, summary.skipped), (4, 2, 2));
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, [1, 2]);
+
+        let (db_path, summary) = import_root(json, schema, &mapping, RootOptions::default());
+        assert_eq!((summary.total, summary.inserted, summary.skipped), (4, 4, 0));
+        let empty_rows: Vec<i64> =
+            query_column(&db_path, "SELECT COUNT(*) FROM items WHERE id IS NULL");
+        assert_eq!(empty_rows, [2]);
+    }
+
+    #[test]
+    fn row_errors_do_not_stop_a_batched_import() {
+        let objects: Vec<JsonValue> = (1..=10).map(|id| json!({"id": id})).collect();
+        let options = InsertOptions {
+            commit_every: Some(2),
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(
+            "CREATE TABLE items (id INTEGER CHECK (id <> 6));",
+            JsonValue::Array(objects),
+            &["id"],
+            options,
+        );
+
+        let summary = result.unwrap();
+        assert_eq!((summary.inserted, summary.failed), (9, 1));
+        assert_eq!((summary.committed_batches, summary.resume_offset), (Some(5), Some(10)));
+        assert_eq!(summary.errors[0].index, 5);
+        let ids: Vec<i64> = query_column(&db_path, "SELECT COUNT(*) FROM items");
+        assert_eq!(ids, [9]);
+    }
+
+    #[test]
+    fn failing_batch_keeps_previously_committed_batches() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER, payload TEXT);");
+        let mut conn = Connection::open(&db_path).unwrap();
+        // Base pleine au 3e lot sur 5: chaque ligne occupe environ une page
+        let pages: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0)).unwrap();
+        conn.execute_batch(&format!("PRAGMA max_page_count = {}", pages + 5)).unwrap();
+        let objects: Vec<JsonValue> =
+            (1..=10).map(|id| json!({"id": id, "payload": "x".repeat(3000)})).collect();
+        let mapping = HashMap::from([
+            ("id".to_string(), "id".to_string()),
+            ("payload".to_string(), "payload".to_string()),
+        ]);
+        let options = InsertOptions {
+            commit_every: Some(2),
+            connection: Some(&mut conn),
+            ..Default::default()
+        };
+
+        let summary =
+            insert_objects(objects.into(), &db_path, "items", &mapping, options, |_| {}).unwrap();
+
+        assert_eq!((summary.inserted, summary.committed_batches), (4, Some(2)));
+        assert_eq!(summary.resume_offset, Some(4));
+        assert_eq!(summary.errors.last().unwrap().sqlite_code, Some(rusqlite::ffi::SQLITE_FULL));
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn root_object_entries_become_key_value_rows() {
+        let json = r#"{"setting_a": 1, "setting_b": "x", "limits": {"max": [1, 2]}, "off": null}"#;
+        let root = RootOptions {
+            key_value_columns: Some(("name", "setting")),
+            ..Default::default()
+        };
+
+        let (db_path, summary) = import_root(
+            json,
+            "CREATE TABLE items (name TEXT PRIMARY KEY, setting TEXT);",
+            &[],
+            root,
+        );
+
+        assert_eq!(summary.inserted, 4);
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT name || '=' || ifnull(setting, 'NULL') FROM items ORDER BY name",
+        );
+        assert_eq!(rows, ["limits={\"max\":[1,2]}", "off=NULL", "setting_a=1", "setting_b=x"]);
+    }
+
+    #[test]
+    fn failed_rows_are_retried_after_their_parent() {
+        // Le trigger joue le rôle d'une clé étrangère vers la ligne parente
+        let schema = "CREATE TABLE items (id INTEGER PRIMARY KEY, parent_id INTEGER);
+             CREATE TRIGGER parent_exists BEFORE INSERT ON items
+             WHEN new.parent_id IS NOT NULL
+                 AND NOT EXISTS (SELECT 1 FROM items WHERE id = new.parent_id)
+             BEGIN SELECT RAISE(ABORT, 'parent absent'); END;";
+        let objects = json!([
+            {"id": 2, "parent_id": 1},
+            {"id": 1, "parent_id": null},
+            {"id": 3, "parent_id": 9},
+        ]);
+        let columns = ["id", "parent_id"];
+
+        let (_, result) = insert_items(schema, objects.clone(), &columns, Default::default());
+        assert_eq!(result.unwrap().inserted, 1);
+
+        let options = InsertOptions {
+            retry_failed: true,
+            ..Default::default()
+        };
+        let (db_path, result) = insert_items(schema, objects, &columns, options);
+        let summary = result.unwrap();
+        assert_eq!((summary.inserted, summary.failed), (2, 1));
+        assert_eq!(summary.retry_inserted, Some(1));
+        assert_eq!(summary.errors.len(), 1);
+        assert_eq!(summary.errors[0].index, 2);
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, [1, 2]);
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced_only_in_lossy_mode() {
+        let json_path = temp_json("");
+        let mut bytes = br#"[{"id": 1, "name": "caf"#.to_vec();
+        bytes.extend_from_slice(b"\xE9\"}, {\"id\": 2, \"name\": \"ok\xFF\xFE\"}]");
+        std::fs::write(&json_path, bytes).unwrap();
+        let read_utf8 = |lossy_utf8, threshold| {
+            let http = HttpOptions::default();
+            read_json_source(&json_path, "", &http, lossy_utf8, false, threshold, |_| {})
+        };
+
+        let error = read_utf8(false, DEFAULT_STREAMING_THRESHOLD_BYTES).err().unwrap();
+        assert!(error.contains("UTF-8 invalide"), "{}", error);
+
+        // Le mode tolérant désactive la lecture en streaming
+        let source = read_utf8(true, 0).unwrap();
+        assert_eq!(source.streamed, Some(false));
+        let (db_path, summary) = import(&json_path, source, "");
+        assert_eq!(summary.inserted, 2);
+        assert_eq!(summary.replaced_utf8_sequences, Some(3));
+        let names: Vec<String> = query_column(&db_path, "SELECT name FROM items ORDER BY id");
+        assert_eq!(names, ["caf\u{FFFD}", "ok\u{FFFD}\u{FFFD}"]);
+    }
+
+    #[test]
+    fn array_default_is_stored_as_canonical_json() {
+        let objects = json!([{"id": 1}, {"id": 2, "tags": ["own"]}]);
+        let options = InsertOptions {
+            defaults: Some(HashMap::from([(
+                "tags".to_string(),
+                json!(["b", {"z": 1, "a": "{{DYNAMIC}}"}]),
+            )])),
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(
+            "CREATE TABLE items (id INTEGER, tags JSON);",
+            objects,
+            &["id", "tags"],
+            options,
+        );
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let tags: Vec<String> = query_column(
+            &db_path,
+            "SELECT json_valid(tags) || ':' || tags FROM items ORDER BY id",
+        );
+        assert_eq!(tags, [r#"1:["b",{"a":"{{DYNAMIC}}","z":1}]"#, r#"1:["own"]"#]);
+    }
+
+    #[test]
+    fn supplied_primary_key_is_checked_or_dropped() {
+        let schema = "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT);
+             INSERT INTO items VALUES (1, 'ancien');";
+        let objects = json!([{"id": 1, "name": "a"}, {"id": 5, "name": "b"}]);
+        let mapping = HashMap::from([
+            ("id".to_string(), "id".to_string()),
+            ("name".to_string(), "name".to_string()),
+        ]);
+
+        let db_path = temp_db(schema);
+        let summary = insert_objects(
+            objects.as_array().cloned().unwrap().into(),
+            &db_path,
+            "items",
+            &mapping,
+            InsertOptions::default(),
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!((summary.inserted, summary.failed), (1, 1));
+        assert_eq!(
+            summary.warnings,
+            ["La clé primaire id est fournie par le mapping: \
+              1 valeur(s) existent déjà dans la table"]
+        );
+        let rows: Vec<String> =
+            query_column(&db_path, "SELECT id || ':' || name FROM items ORDER BY id");
+        assert_eq!(rows, ["1:ancien", "5:b"]);
+
+        let options = InsertOptions {
+            ignore_pk_mapping: true,
+            ..Default::default()
+        };
+        let (db_path, result) = insert_items(schema, objects, &["id", "name"], options);
+        assert_eq!(result.unwrap().inserted, 2);
+        let rows: Vec<String> =
+            query_column(&db_path, "SELECT id || ':' || name FROM items ORDER BY id");
+        assert_eq!(rows, ["1:ancien", "2:a", "3:b"]);
+    }
+
+    #[test]
+    fn empty_array_and_empty_file_are_reported_differently() {
+        let schema = "CREATE TABLE items (id INTEGER);";
+        let mapping = [("id", "id")];
+        let root = |json_root| RootOptions {
+            json_root,
+            ..Default::default()
+        };
+
+        let (_, summary) = import_root(r#"{"data": []}"#, schema, &mapping, root("data[]"));
+        assert_eq!((summary.total, summary.inserted), (0, 0));
+        assert_eq!(
+            summary.warnings,
+            ["Aucun objet extrait à la racine 'data[]': vérifiez le chemin json_root"]
+        );
+
+        let (_, summary) = import_root(" \n\t ", schema, &mapping, root(""));
+        assert_eq!(summary.total, 0);
+        assert_eq!(summary.warnings.len(), 1);
+        assert!(summary.warnings[0].ends_with("est vide: aucun objet à importer"));
+
+        let json_path = temp_json(r#"{"data": []}"#);
+        let source = read(&json_path, "", DEFAULT_STREAMING_THRESHOLD_BYTES);
+        let result = insert_json_data(
+            &json_path,
+            source,
+            &temp_db(schema),
+            "items",
+            &HashMap::from([("id".to_string(), "id".to_string())]),
+            root("records[]"),
+            InsertOptions::default(),
+            |_| {},
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn post_sql_runs_after_the_import_commits() {
+        let schema = "CREATE TABLE items (raw TEXT, normalized TEXT);";
+        let objects = json!([{"raw": "AbC"}, {"raw": "XYZ"}]);
+        let options = |post_sql| InsertOptions {
+            post_sql: Some(post_sql),
+            ..Default::default()
+        };
+
+        let normalize = "UPDATE items SET normalized = lower(raw);";
+        let (db_path, result) = insert_items(schema, objects.clone(), &["raw"], options(normalize));
+        let summary = result.unwrap();
+        assert_eq!((summary.inserted, summary.post_sql_succeeded), (2, Some(true)));
+        let normalized: Vec<String> = query_column(&db_path, "SELECT normalized FROM items");
+        assert_eq!(normalized, ["abc", "xyz"]);
+
+        // Un script en échec est annulé en entier, sans annuler l'importation
+        let failing = "UPDATE items SET normalized = 'x'; INSERT INTO missing VALUES (1);";
+        let (db_path, result) = insert_items(schema, objects, &["raw"], options(failing));
+        let summary = result.unwrap();
+        assert_eq!((summary.inserted, summary.post_sql_succeeded), (2, Some(false)));
+        assert!(summary.post_sql_error.unwrap().contains("missing"));
+        let normalized: Vec<Option<String>> =
+            query_column(&db_path, "SELECT normalized FROM items");
+        assert_eq!(normalized, [None, None]);
+    }
+
+    #[test]
+    fn nan_loads_as_null_in_lenient_mode() {
+        let json_path =
+            temp_json(r#"[{"id": 1, "name": NaN}, {"id": 2, "name": "NaN"}, {"id": -Infinity}]"#);
+        let read_lenient = |lenient_numbers| {
+            let http = HttpOptions::default();
+            read_json_source(&json_path, "", &http, false, lenient_numbers, u64::MAX, |_| {})
+        };
+
+        assert!(read_lenient(false).is_err());
+
+        let (db_path, summary) = import(&json_path, read_lenient(true).unwrap(), "");
+        assert_eq!(summary.inserted, 3);
+        assert_eq!(summary.replaced_non_finite_numbers, Some(2));
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT ifnull(id, 'NULL') || ':' || ifnull(name, 'NULL') FROM items ORDER BY rowid",
+        );
+        assert_eq!(rows, ["1:NULL", "2:NaN", "NULL:NULL"]);
+    }
+
+    #[test]
+    fn whole_elements_are_stored_as_json_documents() {
+        let json = r#"[
+            {"id": 1, "user": {"name": "a"}},
+            {"tags": ["x"]},
+            {"id": 3, "b": 1, "a": 2}
+        ]"#;
+        let root = RootOptions {
+            whole_document_column: Some("doc"),
+            ..Default::default()
+        };
+
+        let (db_path, summary) = import_root(
+            json,
+            "CREATE TABLE items (doc JSON);",
+            &[("ignoré", "doc")],
+            root,
+        );
+
+        assert_eq!(summary.inserted, 3);
+        let names: Vec<Option<String>> =
+            query_column(&db_path, "SELECT json_extract(doc, '$.user.name') FROM items");
+        assert_eq!(names, [Some("a".to_string()), None, None]);
+        let docs: Vec<String> =
+            query_column(&db_path, "SELECT doc FROM items WHERE json_valid(doc)");
+        assert_eq!(docs[2], r#"{"a":2,"b":1,"id":3}"#);
+    }
+
+    #[test]
+    fn header_and_footer_elements_are_trimmed_before_offset() {
+        let json = r#"[{"name": "en-tête"}, {"id": 1}, {"id": 2}, {"id": 3}, {"name": "pied"}]"#;
+        let root = RootOptions {
+            skip_first: 1,
+            skip_last: 1,
+            ..Default::default()
+        };
+        let (db_path, summary) = import_root(
+            json,
+            "CREATE TABLE items (id INTEGER, name TEXT);",
+            &[("id", "id"), ("name", "name")],
+            root,
+        );
+        assert_eq!((summary.total, summary.inserted), (3, 3));
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, [1, 2, 3]);
+
+        let json_path = temp_json(json);
+        let source = read(&json_path, "", DEFAULT_STREAMING_THRESHOLD_BYTES);
+        let db_path = temp_db("CREATE TABLE items (id INTEGER, name TEXT);");
+        let options = InsertOptions {
+            offset: Some(1),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let root = RootOptions {
+            skip_first: 1,
+            skip_last: 1,
+            ..Default::default()
+        };
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        insert_json_data(&json_path, source, &db_path, "items", &mapping, root, options, |_| {})
+            .unwrap();
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items");
+        assert_eq!(ids, [2]);
+    }
+
+    #[test]
+    fn execute_rollback_reports_constraint_errors_without_persisting() {
+        let schema = "CREATE TABLE items (code TEXT UNIQUE, n INTEGER);";
+        let objects = json!([{"code": "a", "n": 1}, {"code": "b"}, {"code": "a", "n": 3}]);
+        let dry_run = |dry_run_mode| InsertOptions {
+            dry_run: true,
+            dry_run_mode,
+            ..Default::default()
+        };
+
+        let rollback = dry_run(DryRunMode::ExecuteRollback);
+        let (db_path, result) = insert_items(schema, objects.clone(), &["code", "n"], rollback);
+        let summary = result.unwrap();
+        assert_eq!((summary.inserted, summary.failed), (2, 1));
+        assert_eq!(summary.errors[0].index, 2);
+        assert_eq!(summary.errors[0].extended_code, Some(2067));
+        let codes: Vec<String> = query_column(&db_path, "SELECT code FROM items");
+        assert!(codes.is_empty());
+
+        // Le simple décompte ne touche pas la base et ne voit donc pas la violation
+        let (_, result) = insert_items(schema, objects, &["code", "n"], dry_run(DryRunMode::Count));
+        assert_eq!(result.unwrap().failed, 0);
+    }
+
+    #[test]
+    fn unmapped_null_default_and_mapped_columns_are_distinct() {
+        let schema = "CREATE TABLE items (id INTEGER, status TEXT DEFAULT 'nouveau',
+             note TEXT DEFAULT 'aucune', label TEXT DEFAULT 'sans');";
+        let objects = json!([{"id": 1, "note": "n", "label": "l"}, {"id": 2}]);
+        let options = || InsertOptions {
+            defaults: Some(HashMap::from([("note".to_string(), JsonValue::Null)])),
+            ..Default::default()
+        };
+
+        // status: non mappée (DEFAULT de la table); note: défaut null (NULL explicite);
+        // label: mappée (valeur source, NULL si absente)
+        let columns = ["id", "note", "label"];
+        let (db_path, result) = insert_items(schema, objects.clone(), &columns, options());
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT id || '|' || status || '|' || ifnull(note, 'NULL') || '|'
+                 || ifnull(label, 'NULL') FROM items ORDER BY id",
+        );
+        assert_eq!(rows, ["1|nouveau|n|l", "2|nouveau|NULL|NULL"]);
+
+        // Un défaut null s'applique aussi à une colonne absente du mapping
+        let (db_path, result) = insert_items(schema, objects, &["id"], options());
+        assert_eq!(result.unwrap().inserted, 2);
+        let notes: Vec<Option<String>> = query_column(&db_path, "SELECT note FROM items");
+        assert_eq!(notes, [None, None]);
+    }
+
+    #[test]
+    fn map_root_keys_become_a_column() {
+        let json = r#"{"catalog": {"sku123": {"name": "a"}, "sku456": {"name": "b"}}}"#;
+        let root = RootOptions {
+            json_root: "catalog",
+            map_root: true,
+            ..Default::default()
+        };
+
+        let (db_path, summary) = import_root(
+            json,
+            "CREATE TABLE items (sku TEXT, name TEXT);",
+            &[("@key", "sku"), ("name", "name")],
+            root,
+        );
+
+        assert_eq!(summary.inserted, 2);
+        let rows: Vec<String> =
+            query_column(&db_path, "SELECT sku || ':' || name FROM items ORDER BY sku");
+        assert_eq!(rows, ["sku123:a", "sku456:b"]);
+    }
+
+    #[test]
+    fn sequence_follows_source_order() {
+        let schema = "CREATE TABLE items (name TEXT, seq INTEGER);
+             INSERT INTO items VALUES ('ancien', 7);";
+        let objects = json!([{"name": "c"}, {"name": "a"}, {"name": "b"}]);
+        let options = |sequence_start| InsertOptions {
+            sequence_column: Some("seq"),
+            sequence_start,
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(schema, objects.clone(), &["name"], options(None));
+        assert_eq!(result.unwrap().inserted, 3);
+        let rows: Vec<String> =
+            query_column(&db_path, "SELECT seq || ':' || name FROM items ORDER BY seq");
+        assert_eq!(rows, ["7:ancien", "8:c", "9:a", "10:b"]);
+
+        let (db_path, result) = insert_items(schema, objects, &["name"], options(Some(100)));
+        assert_eq!(result.unwrap().inserted, 3);
+        let rows: Vec<String> =
+            query_column(&db_path, "SELECT seq || ':' || name FROM items WHERE seq >= 100");
+        assert_eq!(rows, ["100:c", "101:a", "102:b"]);
+    }
+
+    #[test]
+    fn persistent_counter_continues_across_runs() {
+        let db_path = temp_db("CREATE TABLE items (ext_id INTEGER, name TEXT);");
+        let mapping = HashMap::from([("name".to_string(), "name".to_string())]);
+        let run = |names: &[&str], dry_run: bool| {
+            let objects: Vec<JsonValue> = names.iter().map(|name| json!({"name": name})).collect();
+            let options = InsertOptions {
+                counter_column: Some("ext_id"),
+                counter_name: Some("externe"),
+                dry_run,
+                dry_run_mode: DryRunMode::ExecuteRollback,
+                ..Default::default()
+            };
+            insert_objects(objects.into(), &db_path, "items", &mapping, options, |_| {}).unwrap()
+        };
+
+        run(&["a", "b", "c"], false);
+        run(&["simulé"], true);
+        run(&["d", "e"], false);
+
+        let rows: Vec<String> =
+            query_column(&db_path, "SELECT ext_id || ':' || name FROM items ORDER BY ext_id");
+        assert_eq!(rows, ["1:a", "2:b", "3:c", "4:d", "5:e"]);
+        let stored: Vec<i64> =
+            query_column(&db_path, "SELECT value FROM _json_counters WHERE name = 'externe'");
+        assert_eq!(stored, [5]);
+    }
+
+    #[test]
+    fn require_empty_table_rejects_a_populated_table() {
+        let objects = json!([{"id": 1}, {"id": 2}]);
+        let options = || InsertOptions {
+            require_empty_table: true,
+            ..Default::default()
+        };
+
+        let populated = "CREATE TABLE items (id INTEGER); INSERT INTO items VALUES (9);";
+        let (db_path, result) = insert_items(populated, objects.clone(), &["id"], options());
+        assert_eq!(
+            result.unwrap_err(),
+            "La table items contient déjà des données (require_empty_table)"
+        );
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items");
+        assert_eq!(ids, [9]);
+
+        let empty = "CREATE TABLE items (id INTEGER);";
+        let (db_path, result) = insert_items(empty, objects, &["id"], options());
+        assert_eq!(result.unwrap().inserted, 2);
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, [1, 2]);
+    }
+
+    #[test]
+    fn rows_fill_one_column_from_different_paths() {
+        let objects = json!([
+            {"id": 1, "email": "a@x"},
+            {"id": 2, "emailAddress": "b@x"},
+            {"id": 3, "contact": {"email": "c@x"}},
+            {"id": 4, "email": null, "contact": {"email": "d@x"}},
+            {"id": 5},
+        ]);
+        let coalesce = HashMap::from([(
+            "email".to_string(),
+            vec!["emailAddress".to_string(), "contact.email".to_string()],
+        )]);
+        let options = InsertOptions {
+            mapping_coalesce: Some(&coalesce),
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(
+            "CREATE TABLE items (id INTEGER, email TEXT);",
+            objects,
+            &["id", "email"],
+            options,
+        );
+
+        assert_eq!(result.unwrap().inserted, 5);
+        let emails: Vec<Option<String>> =
+            query_column(&db_path, "SELECT email FROM items ORDER BY id");
+        let expected = [Some("a@x"), Some("b@x"), Some("c@x"), Some("d@x"), None];
+        assert_eq!(emails, expected.map(|email| email.map(String::from)));
+    }
+
+    #[test]
+    fn conversion_failures_follow_the_column_policy() {
+        let epoch = |columns: &[&str]| {
+            columns
+                .iter()
+                .map(|column| (column.to_string(), EpochResolution::Seconds))
+                .collect::<HashMap<_, _>>()
+        };
+        let day = "2024-01-01";
+        let objects = json!([
+            {"id": 1, "strict": day, "nullable": day, "fallback": day, "unset": day},
+            {"id": 2, "strict": day, "nullable": "?", "fallback": "?", "unset": day},
+            {"id": 3, "strict": "?", "nullable": day, "fallback": day, "unset": day},
+            {"id": 4, "strict": day, "nullable": day, "fallback": day, "unset": "?"},
+        ]);
+        let policies = HashMap::from([
+            ("strict".to_string(), ConversionErrorPolicy::Error),
+            ("nullable".to_string(), ConversionErrorPolicy::Null),
+            ("fallback".to_string(), ConversionErrorPolicy::Default),
+        ]);
+        let options = InsertOptions {
+            epoch_columns: Some(epoch(&["strict", "nullable", "fallback", "unset"])),
+            conversion_error_policy: Some(policies.clone()),
+            defaults: Some(HashMap::from([("fallback".to_string(), json!(0))])),
+            ..Default::default()
+        };
+
+        let columns = ["id", "strict", "nullable", "fallback", "unset"];
+        let schema = "CREATE TABLE items (id INTEGER, strict INTEGER, nullable INTEGER,
+             fallback INTEGER, unset INTEGER);";
+        let (db_path, result) = insert_items(schema, objects.clone(), &columns, options);
+
+        let summary = result.unwrap();
+        assert_eq!((summary.inserted, summary.failed), (2, 2));
+        let failed: Vec<usize> = summary.errors.iter().map(|e| e.index).collect();
+        assert_eq!(failed, [2, 3]);
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT id || ':' || ifnull(nullable, 'NULL') || ':' || fallback FROM items",
+        );
+        assert_eq!(rows, ["1:1704067200:1704067200", "2:NULL:0"]);
+
+        // La politique `default` exige une valeur dans defaults
+        let options = InsertOptions {
+            conversion_error_policy: Some(policies),
+            ..Default::default()
+        };
+        let (_, result) = insert_items(schema, objects, &columns, options);
+        assert_eq!(
+            result.unwrap_err(),
+            "Politique de conversion 'default' sans valeur dans defaults pour: fallback"
+        );
+    }
+
+    #[test]
+    fn out_of_range_integer_falls_back_to_null() {
+        let objects = json!([{"id": 1, "big": 18446744073709551615_u64}]);
+        let options = InsertOptions {
+            strict_integers: true,
+            conversion_error_policy: Some(HashMap::from([(
+                "big".to_string(),
+                ConversionErrorPolicy::Null,
+            )])),
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(
+            "CREATE TABLE items (id INTEGER, big INTEGER);",
+            objects,
+            &["id", "big"],
+            options,
+        );
+
+        assert_eq!(result.unwrap().inserted, 1);
+        let big: Vec<Option<i64>> = query_column(&db_path, "SELECT big FROM items");
+        assert_eq!(big, [None]);
+    }
+
+    #[test]
+    fn flatten_collision_aborts_the_import() {
+        let json = r#"[{"a": {"b_c": 1}, "a_b": {"c": 2}}]"#;
+        let json_path = temp_json(json);
+        let db_path = temp_db("CREATE TABLE items (a_b_c INTEGER);");
+        let root = RootOptions {
+            flatten_separator: Some("_"),
+            ..Default::default()
+        };
+
+        let source = read(&json_path, "", DEFAULT_STREAMING_THRESHOLD_BYTES);
+        let mapping = HashMap::new();
+        let result = insert_json_data(
+            &json_path,
+            source,
+            &db_path,
+            "items",
+            &mapping,
+            root,
+            InsertOptions::default(),
+            |_| {},
+        );
+
+        let error = result.unwrap_err();
+        assert!(error.contains("a_b_c ← a.b_c, a_b.c"), "{}", error);
+        let rows: Vec<i64> = query_column(&db_path, "SELECT COUNT(*) FROM items");
+        assert_eq!(rows, [0]);
+    }
+
+    fn rejects(path: &str) -> Vec<JsonValue> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn failed_rows_are_appended_to_the_reject_file() {
+        let reject_path = temp_path("ndjson");
+        let objects = json!([{"id": 1, "name": "a"}, {"id": null, "name": "b"}]);
+        let options = || InsertOptions {
+            reject_file: Some(&reject_path),
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(
+            "CREATE TABLE items (id INTEGER NOT NULL, name TEXT);",
+            objects.clone(),
+            &["id", "name"],
+            options(),
+        );
+        let error = result.unwrap().errors[0].message.clone();
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items");
+        assert_eq!(ids, [1]);
+        assert_eq!(rejects(&reject_path), [json!({"id": null, "name": "b", "_error": error})]);
+
+        // Un second import ajoute ses rejets à la suite des précédents
+        let (_, result) = insert_items(
+            "CREATE TABLE items (id INTEGER NOT NULL, name TEXT);",
+            objects,
+            &["id", "name"],
+            options(),
+        );
+        assert_eq!(result.unwrap().failed, 1);
+        assert_eq!(rejects(&reject_path).len(), 2);
+    }
+
+    #[test]
+    fn streamed_sources_still_write_their_rejects() {
+        let json_path = temp_json(r#"[{"id": 1}, {"id": "x"}, {"id": 3}]"#);
+        let db_path = temp_db("CREATE TABLE items (id INTEGER CHECK (id < 3));");
+        let reject_path = temp_path("ndjson");
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        let options = InsertOptions {
+            reject_file: Some(&reject_path),
+            ..Default::default()
+        };
+
+        let summary = insert_json_data(
+            &json_path,
+            read(&json_path, "", 0),
+            &db_path,
+            "items",
+            &mapping,
+            RootOptions::default(),
+            options,
+            |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(summary.inserted, 1);
+        let rejected: Vec<_> = rejects(&reject_path).iter().map(|r| r["id"].clone()).collect();
+        assert_eq!(rejected, [json!("x"), json!(3)]);
+        assert!(rejects(&reject_path).iter().all(|r| r["_error"].is_string()));
+    }
+
+    #[test]
+    fn rejects_are_the_source_objects_and_skip_dry_runs() {
+        let schema = "CREATE TABLE items (id INTEGER NOT NULL, name TEXT);";
+        let reject_path = temp_path("ndjson");
+        let objects = json!([{"Id": 1, "Name": "a"}, {"Id": null, "Name": "b"}]);
+        let options = |dry_run| InsertOptions {
+            reject_file: Some(&reject_path),
+            normalize_keys: KeyNormalization::Lower,
+            dry_run,
+            dry_run_mode: DryRunMode::ExecuteRollback,
+            ..Default::default()
+        };
+
+        let (_, result) = insert_items(schema, objects.clone(), &["id", "name"], options(true));
+        assert_eq!(result.unwrap().failed, 1);
+        assert!(!Path::new(&reject_path).exists());
+
+        let (_, result) = insert_items(schema, objects, &["id", "name"], options(false));
+        let error = result.unwrap().errors[0].message.clone();
+        assert_eq!(rejects(&reject_path), [json!({"Id": null, "Name": "b", "_error": error})]);
+    }
+
+    #[test]
+    fn streamed_rejects_are_written_before_a_read_error() {
+        let json_path = temp_json(r#"[{"id": "x"}, {"id": 2}, {"id": "#);
+        let db_path = temp_db("CREATE TABLE items (id INTEGER CHECK (typeof(id) = 'integer'));");
+        let reject_path = temp_path("ndjson");
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        let options = InsertOptions {
+            reject_file: Some(&reject_path),
+            ..Default::default()
+        };
+
+        let result = insert_json_data(
+            &json_path,
+            read(&json_path, "", 0),
+            &db_path,
+            "items",
+            &mapping,
+            RootOptions::default(),
+            options,
+            |_| {},
+        );
+
+        assert!(result.unwrap_err().starts_with("Lecture interrompue"));
+        let rejected: Vec<_> = rejects(&reject_path).iter().map(|r| r["id"].clone()).collect();
+        assert_eq!(rejected, [json!("x")]);
+    }
+
+    #[test]
+    fn rejects_are_kept_when_a_batch_fails() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER NOT NULL, payload TEXT);");
+        let reject_path = temp_path("ndjson");
+        let mut conn = Connection::open(&db_path).unwrap();
+        // Base pleine après quelques lots, la ligne en échec n'étant pas un rejet
+        let pages: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0)).unwrap();
+        conn.execute_batch(&format!("PRAGMA max_page_count = {}", pages + 5)).unwrap();
+        let objects: Vec<JsonValue> = (1..=10)
+            .map(|id| json!({"id": (id != 2).then_some(id), "payload": "x".repeat(3000)}))
+            .collect();
+        let mapping = HashMap::from([
+            ("id".to_string(), "id".to_string()),
+            ("payload".to_string(), "payload".to_string()),
+        ]);
+        let options = InsertOptions {
+            commit_every: Some(2),
+            reject_file: Some(&reject_path),
+            connection: Some(&mut conn),
+            ..Default::default()
+        };
+
+        let summary =
+            insert_objects(objects.into(), &db_path, "items", &mapping, options, |_| {}).unwrap();
+
+        assert_eq!(summary.errors.last().unwrap().sqlite_code, Some(rusqlite::ffi::SQLITE_FULL));
+        let rejected: Vec<_> = rejects(&reject_path).iter().map(|r| r["id"].clone()).collect();
+        assert_eq!(rejected, [JsonValue::Null]);
+    }
+
+    fn fts_options<'a>(content_columns: &[&str]) -> InsertOptions<'a> {
+        InsertOptions {
+            fts_sync: Some(FtsSync {
+                fts_table: "items_fts".to_string(),
+                content_columns: content_columns.iter().map(|c| c.to_string()).collect(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fts_table_is_filled_with_the_inserted_rows() {
+        let schema = "CREATE TABLE items (id INTEGER PRIMARY KEY, title TEXT, body TEXT);
+             CREATE VIRTUAL TABLE items_fts USING fts5(title, body);";
+        let objects = json!([
+            {"id": 10, "title": "Rapport annuel", "body": "chiffres du printemps"},
+            {"id": 20, "title": "Compte rendu", "body": "réunion d'automne"},
+        ]);
+
+        let (db_path, result) = insert_items(
+            schema,
+            objects,
+            &["id", "title", "body"],
+            fts_options(&["title", "body"]),
+        );
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let found: Vec<i64> =
+            query_column(&db_path, "SELECT rowid FROM items_fts WHERE items_fts MATCH 'automne'");
+        assert_eq!(found, [20]);
+        let found: Vec<String> = query_column(
+            &db_path,
+            "SELECT items.title FROM items_fts JOIN items ON items.id = items_fts.rowid
+             WHERE items_fts MATCH 'rapport'",
+        );
+        assert_eq!(found, ["Rapport annuel"]);
+    }
+
+    #[test]
+    fn invalid_fts_targets_are_rejected_before_inserting() {
+        let schema = "CREATE TABLE items (id INTEGER PRIMARY KEY, title TEXT);
+             CREATE VIRTUAL TABLE items_fts USING fts5(title);";
+        let (db_path, result) =
+            insert_items(schema, json!([{"id": 1}]), &["id"], fts_options(&["body"]));
+        assert!(result.unwrap_err().starts_with("Table plein texte items_fts invalide"));
+        let count: Vec<i64> = query_column(&db_path, "SELECT COUNT(*) FROM items");
+        assert_eq!(count, [0]);
+
+        let schema = "CREATE TABLE items (id INTEGER PRIMARY KEY, title TEXT) WITHOUT ROWID;
+             CREATE VIRTUAL TABLE items_fts USING fts5(title);";
+        let (_, result) =
+            insert_items(schema, json!([{"id": 1}]), &["id"], fts_options(&["title"]));
+        assert!(result.unwrap_err().starts_with("La table items est WITHOUT ROWID"));
+    }
+
+    #[test]
+    fn delimited_field_is_split_into_child_rows() {
+        let schema = "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT);
+             CREATE TABLE tags (item_id INTEGER REFERENCES items (id), tag TEXT);";
+        let objects = json!([
+            {"id": 7, "name": "a", "tags": "rouge, vert,,bleu"},
+            {"id": 9, "name": "b", "tags": "vert"},
+            {"id": 11, "name": "c"},
+        ]);
+        let options = InsertOptions {
+            split_into_child: Some(vec![SplitIntoChild {
+                source_path: "tags".to_string(),
+                delimiter: ",".to_string(),
+                child_table: "tags".to_string(),
+                value_column: "tag".to_string(),
+                parent_fk_column: "item_id".to_string(),
+            }]),
+            ..Default::default()
+        };
+
+        let (db_path, result) = insert_items(schema, objects, &["id", "name"], options);
+
+        let summary = result.unwrap();
+        assert_eq!((summary.inserted, summary.child_rows_inserted), (3, Some(4)));
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT item_id || ':' || tag FROM tags ORDER BY rowid",
+        );
+        assert_eq!(rows, ["7:rouge", "7:vert", "7:bleu", "9:vert"]);
+    }
+
+    #[test]
+    fn each_row_omits_its_own_absent_columns() {
+        let schema = "CREATE TABLE items (id INTEGER, status TEXT DEFAULT 'nouveau', \
+             score INTEGER DEFAULT 10);";
+        let objects = json!([
+            {"id": 1, "status": "actif"},
+            {"id": 2, "score": 3},
+            {"id": 3, "status": "clos", "score": 7},
+            {"id": 4, "status": "actif"},
+        ]);
+        let columns = ["id", "status", "score"];
+        let rows_sql = "SELECT id || ':' || ifnull(status, '-') || ':' || ifnull(score, '-') \
+             FROM items ORDER BY id";
+
+        let options = InsertOptions {
+            per_row_columns: true,
+            ..Default::default()
+        };
+        let (db_path, result) = insert_items(schema, objects.clone(), &columns, options);
+        assert_eq!(result.unwrap().inserted, 4);
+        let rows: Vec<String> = query_column(&db_path, rows_sql);
+        assert_eq!(rows, ["1:actif:10", "2:nouveau:3", "3:clos:7", "4:actif:10"]);
+
+        // Sans l'option, les colonnes absentes reçoivent NULL
+        let (db_path, _) = insert_items(schema, objects, &columns, InsertOptions::default());
+        let rows: Vec<String> = query_column(&db_path, rows_sql);
+        assert_eq!(rows, ["1:actif:-", "2:-:3", "3:clos:7", "4:actif:-"]);
+    }
+
+    #[test]
+    fn versioned_append_keeps_every_version_of_a_key() {
+        let schema = "CREATE TABLE items (sku TEXT, price INTEGER, version INTEGER);
+             INSERT INTO items VALUES ('a', 5, 2);";
+        let db_path = temp_db(schema);
+        let versioned = VersionedAppend {
+            key_column: "sku".to_string(),
+            version_column: "version".to_string(),
+        };
+        let mapping = HashMap::from([
+            ("sku".to_string(), "sku".to_string()),
+            ("price".to_string(), "price".to_string()),
+        ]);
+        let import = |objects: JsonValue| {
+            let options = InsertOptions {
+                versioned_append: Some(&versioned),
+                ..Default::default()
+            };
+            let objects = objects.as_array().cloned().unwrap();
+            insert_objects(objects.into(), &db_path, "items", &mapping, options, |_| {}).unwrap()
+        };
+
+        import(json!([
+            {"sku": "a", "price": 6},
+            {"sku": "b", "price": 1},
+            {"sku": "a", "price": 7},
+        ]));
+        let summary = import(json!([{"sku": "a", "price": 8}, {"sku": null, "price": 0}]));
+
+        assert_eq!((summary.inserted, summary.failed), (1, 1));
+        assert_eq!(summary.errors[0].message, "Clé métier sku absente ou nulle");
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT sku || ':' || version || ':' || price FROM items ORDER BY sku, version",
+        );
+        assert_eq!(rows, ["a:2:5", "a:3:6", "a:4:7", "a:5:8", "b:1:1"]);
+    }
+
+    /// Importe trois objets avec `offset`/`limit`, chargés (`threshold` élevé) ou en streaming
+    fn import_window(offset: u32, limit: Option<u32>, threshold: u64) -> (Vec<i64>, Vec<String>) {
+        let json_path = temp_json(r#"[{"id": 1}, {"id": 2}, {"id": 3}]"#);
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        let options = InsertOptions {
+            offset: Some(offset),
+            limit,
+            ..Default::default()
+        };
+        let summary = insert_json_data(
+            &json_path,
+            read(&json_path, "", threshold),
+            &db_path,
+            "items",
+            &mapping,
+            RootOptions::default(),
+            options,
+            |_| {},
+        )
+        .unwrap();
+        (query_column(&db_path, "SELECT id FROM items ORDER BY id"), summary.warnings)
+    }
+
+    #[test]
+    fn offset_at_or_past_the_end_selects_nothing() {
+        for threshold in [DEFAULT_STREAMING_THRESHOLD_BYTES, 0] {
+            let (ids, warnings) = import_window(2, None, threshold);
+            assert_eq!(ids, [3]);
+            assert!(warnings.is_empty());
+
+            for offset in [3, 10] {
+                let (ids, warnings) = import_window(offset, None, threshold);
+                assert!(ids.is_empty());
+                let expected = format!("Aucun des 3 objet(s) sélectionné (offset {})", offset);
+                assert_eq!(warnings, [expected]);
+            }
+
+            let (ids, warnings) = import_window(5, Some(1), threshold);
+            assert!(ids.is_empty());
+            assert_eq!(warnings, ["Aucun des 3 objet(s) sélectionné (offset 5, limit 1)"]);
+        }
+    }
+
+    #[test]
+    fn mapping_targets_match_columns_whatever_their_case() {
+        let (db_path, result) = insert_mapped(
+            "CREATE TABLE items (username TEXT, \"Email\" TEXT, age INTEGER);",
+            json!([{"name": "ana", "mail": "a@x", "age": 30}, {"name": "bo", "age": 41}]),
+            &[("name", "UserName"), ("mail", "EMAIL"), ("age", "age")],
+            InsertOptions::default(),
+        );
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT username || ':' || ifnull(\"Email\", '-') || ':' || age FROM items",
+        );
+        assert_eq!(rows, ["ana:a@x:30", "bo:-:41"]);
+    }
+
+    #[test]
+    fn exact_case_wins_over_a_case_insensitive_alias() {
+        let (db_path, result) = insert_mapped(
+            "CREATE TABLE items (code TEXT);",
+            json!([{"a": "exact", "b": "alias"}, {"b": "alias"}]),
+            &[("a", "code"), ("b", "CODE")],
+            InsertOptions::default(),
+        );
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let codes: Vec<String> = query_column(&db_path, "SELECT code FROM items ORDER BY rowid");
+        assert_eq!(codes, ["exact", "alias"]);
+    }
+
+    #[test]
+    fn derived_keys_are_stable_across_runs() {
+        let db_path = temp_db("CREATE TABLE items (key TEXT UNIQUE NOT NULL, label TEXT);");
+        let derived = DerivedKey {
+            column: "key".to_string(),
+            source_paths: vec!["site".to_string(), "ref".to_string()],
+            strategy: DerivedKeyStrategy::Hash,
+        };
+        let mapping = HashMap::from([("label".to_string(), "label".to_string())]);
+        let import = |objects: JsonValue| {
+            let options = InsertOptions {
+                derived_key: Some(&derived),
+                skip_existing_key: Some("key".to_string()),
+                ..Default::default()
+            };
+            let objects = objects.as_array().cloned().unwrap();
+            insert_objects(objects.into(), &db_path, "items", &mapping, options, |_| {}).unwrap()
+        };
+
+        let keys_sql = "SELECT key FROM items ORDER BY rowid";
+        let first = import(json!([{"site": "a", "ref": 1, "label": "x"}, {"site": "b", "ref": 1}]));
+        let keys: Vec<String> = query_column(&db_path, keys_sql);
+
+        // Réimportation: mêmes clés, les lignes déjà présentes sont ignorées
+        let second = import(json!([{"ref": 1, "site": "b"}, {"ref": 2, "site": "a"}]));
+        assert_eq!((first.inserted, second.inserted), (2, 1));
+        let rerun_keys: Vec<String> = query_column(&db_path, keys_sql);
+        assert_eq!(rerun_keys[..2], keys[..]);
+        assert_ne!(rerun_keys[2], keys[0]);
+    }
+
+    #[test]
+    fn root_path_objects_are_inserted_as_they_are_reached() {
+        let json = r#"{"data": [{"items": [{"id": 1}, {"id": 2}]}, {"items": [{"id": 3}]}]}"#;
+        let schema = "CREATE TABLE items (id INTEGER);";
+        let root = || RootOptions {
+            json_root: "data[].items[]",
+            ..Default::default()
+        };
+
+        let (db_path, summary) = import_root(json, schema, &[("id", "id")], root());
+        assert_eq!((summary.total, summary.inserted), (3, 3));
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY rowid");
+        assert_eq!(ids, [1, 2, 3]);
+
+        // Un élément sans la propriété de la racine interrompt l'importation après les objets
+        // déjà parcourus, sans rien valider
+        let json_path = temp_json(r#"{"data": [{"items": [{"id": 1}, {"id": 2}]}, {"x": []}]}"#);
+        let db_path = temp_db(schema);
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        let error = insert_json_data(
+            &json_path,
+            read(&json_path, "data[].items[]", DEFAULT_STREAMING_THRESHOLD_BYTES),
+            &db_path,
+            "items",
+            &mapping,
+            root(),
+            InsertOptions::default(),
+            |_| {},
+        )
+        .unwrap_err();
+
+        assert!(error.starts_with("Lecture interrompue après 2 objet(s): La propriété 'items'"));
+        let count: Vec<i64> = query_column(&db_path, "SELECT COUNT(*) FROM items");
+        assert_eq!(count, [0]);
+    }
+}
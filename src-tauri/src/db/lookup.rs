@@ -0,0 +1,186 @@
+use crate::commands::{Lookup, LookupMissingPolicy};
+use crate::db::convert::{json_to_sql_value, ConvertOpts};
+use crate::db::export::sqlite_value_to_json;
+use crate::db::{quote_identifier, validate_identifier};
+use crate::json::canonical_json;
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+/// Résolution des valeurs mappées via des tables de correspondance (ex: nom de pays → id).
+/// Chaque valeur distincte n'est recherchée qu'une fois par colonne.
+pub struct LookupResolver {
+    lookups: HashMap<String, Lookup>,
+    /// Colonne → (valeur source canonique → valeur trouvée, None si introuvable)
+    cache: HashMap<String, HashMap<String, Option<JsonValue>>>,
+    pub cache_hits: u32,
+}
+
+impl LookupResolver {
+    /// Vérifie les identifiants et l'existence des tables et colonnes de correspondance
+    pub fn new(conn: &Connection, lookups: HashMap<String, Lookup>) -> Result<Self, String> {
+        for (column, lookup) in &lookups {
+            validate_identifier(column)?;
+            validate_identifier(&lookup.table)?;
+            validate_identifier(&lookup.match_column)?;
+            validate_identifier(&lookup.return_column)?;
+
+            conn.prepare_cached(&lookup_query(lookup)).map_err(|e| {
+                format!("Correspondance invalide pour la colonne {}: {}", column, e)
+            })?;
+        }
+
+        Ok(LookupResolver {
+            lookups,
+            cache: HashMap::new(),
+            cache_hits: 0,
+        })
+    }
+
+    /// Remplace les valeurs des colonnes à correspondance; une valeur null reste null.
+    /// Une valeur introuvable donne une erreur ou NULL selon la politique de la colonne.
+    pub fn resolve(
+        &mut self,
+        conn: &Connection,
+        mapped_data: &mut HashMap<String, Option<JsonValue>>,
+    ) -> Result<(), String> {
+        for (column, lookup) in &self.lookups {
+            let value = match mapped_data.get(column) {
+                Some(Some(value)) if !value.is_null() => value,
+                _ => continue,
+            };
+
+            let key = canonical_json(value);
+            let column_cache = self.cache.entry(column.clone()).or_default();
+            let resolved = match column_cache.get(&key) {
+                Some(resolved) => {
+                    self.cache_hits += 1;
+                    resolved.clone()
+                }
+                None => {
+                    let param = json_to_sql_value(value, None, &ConvertOpts::default())
+                        .map_err(|e| format!("Colonne {}: {}", column, e))?;
+                    let resolved = conn
+                        .prepare_cached(&lookup_query(lookup))
+                        .and_then(|mut stmt| {
+                            stmt.query_row([param], |row| Ok(sqlite_value_to_json(row.get_ref(0)?)))
+                                .optional()
+                        })
+                        .map_err(|e| {
+                            format!("Erreur de correspondance pour la colonne {}: {}", column, e)
+                        })?;
+                    column_cache.insert(key, resolved.clone());
+                    resolved
+                }
+            };
+
+            match (resolved, lookup.on_missing) {
+                (Some(found), _) => {
+                    mapped_data.insert(column.clone(), Some(found));
+                }
+                (None, LookupMissingPolicy::Null) => {
+                    mapped_data.insert(column.clone(), Some(JsonValue::Null));
+                }
+                (None, LookupMissingPolicy::Error) => {
+                    return Err(format!(
+                        "Colonne {}: valeur {} introuvable dans {}.{}",
+                        column, value, lookup.table, lookup.match_column
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn lookup_query(lookup: &Lookup) -> String {
+    format!(
+        "SELECT {} FROM {} WHERE {} = ? LIMIT 1",
+        quote_identifier(&lookup.return_column),
+        quote_identifier(&lookup.table),
+        quote_identifier(&lookup.match_column)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn countries(on_missing: LookupMissingPolicy) -> (Connection, LookupResolver) {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE countries (id INTEGER PRIMARY KEY, name TEXT);
+             INSERT INTO countries VALUES (1, 'France'), (2, 'Suisse');",
+        )
+        .unwrap();
+        let lookup = Lookup {
+            table: "countries".to_string(),
+            match_column: "name".to_string(),
+            return_column: "id".to_string(),
+            on_missing,
+        };
+        let resolver =
+            LookupResolver::new(&conn, HashMap::from([("country_id".to_string(), lookup)]))
+                .unwrap();
+        (conn, resolver)
+    }
+
+    fn resolve(
+        resolver: &mut LookupResolver,
+        conn: &Connection,
+        value: JsonValue,
+    ) -> Result<Option<JsonValue>, String> {
+        let mut mapped = HashMap::from([("country_id".to_string(), Some(value))]);
+        resolver.resolve(conn, &mut mapped)?;
+        Ok(mapped.remove("country_id").flatten())
+    }
+
+    #[test]
+    fn names_resolve_to_ids_through_the_cache() {
+        let (conn, mut resolver) = countries(LookupMissingPolicy::Error);
+
+        assert_eq!(resolve(&mut resolver, &conn, json!("France")), Ok(Some(json!(1))));
+        assert_eq!(resolver.cache_hits, 0);
+
+        // Servie par le cache: la table n'est plus interrogée
+        conn.execute_batch("DELETE FROM countries").unwrap();
+        assert_eq!(resolve(&mut resolver, &conn, json!("France")), Ok(Some(json!(1))));
+        assert_eq!(resolver.cache_hits, 1);
+
+        assert_eq!(resolve(&mut resolver, &conn, JsonValue::Null), Ok(Some(JsonValue::Null)));
+        assert_eq!(resolver.cache_hits, 1);
+    }
+
+    #[test]
+    fn unresolved_values_follow_the_policy() {
+        let (conn, mut resolver) = countries(LookupMissingPolicy::Error);
+        assert_eq!(
+            resolve(&mut resolver, &conn, json!("Italie")),
+            Err(r#"Colonne country_id: valeur "Italie" introuvable dans countries.name"#.into())
+        );
+
+        let (conn, mut resolver) = countries(LookupMissingPolicy::Null);
+        assert_eq!(resolve(&mut resolver, &conn, json!("Italie")), Ok(Some(JsonValue::Null)));
+        assert_eq!(resolve(&mut resolver, &conn, json!("Italie")), Ok(Some(JsonValue::Null)));
+        assert_eq!(resolver.cache_hits, 1);
+    }
+
+    #[test]
+    fn unknown_lookup_table_is_rejected_upfront() {
+        let conn = Connection::open_in_memory().unwrap();
+        let lookup = Lookup {
+            table: "countries".to_string(),
+            match_column: "name".to_string(),
+            return_column: "id".to_string(),
+            on_missing: LookupMissingPolicy::Error,
+        };
+
+        let error = LookupResolver::new(&conn, HashMap::from([("country_id".to_string(), lookup)]))
+            .err()
+            .unwrap();
+
+        assert!(error.starts_with("Correspondance invalide pour la colonne country_id"));
+    }
+}
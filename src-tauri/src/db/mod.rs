@@ -1,19 +1,298 @@
-use crate::commands::{ColumnInfo, ImportConfig, ImportProgress, TableInfo, UpdateConfig};
+use crate::commands::{
+    BeginMode, ColumnInfo, CsvImportConfig, ImportConfig, ImportProgress, ImportSummary,
+    ParseProgress, TableInfo, UpdateConfig,
+};
 use chrono::Utc;
-use rusqlite::{Connection, Result as SqliteResult, Row};
+use rusqlite::{
+    Connection, ErrorCode, Result as SqliteResult, Row, Savepoint, Transaction,
+    TransactionBehavior,
+};
+use serde::de::DeserializeOwned;
 use serde_json::{json, Map, Value as JsonValue};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
+use std::ops::Deref;
 use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
 use uuid::Uuid;
 
+pub mod audit;
+pub mod checkpoints;
+pub mod child_split;
+pub mod conflicts;
+pub mod convert;
+pub mod counters;
+pub mod create;
+pub mod estimate;
+pub mod export;
+pub mod fts;
 pub mod insert;
+pub mod lookup;
+pub mod multi;
+pub mod progress_log;
+pub mod proposal;
+pub mod provenance;
+#[cfg(test)]
+pub mod test_support;
 pub mod update;
+pub mod versions;
 
-use insert::insert_json_data;
+pub use audit::get_import_history;
+pub use conflicts::check_conflicts;
+pub use create::create_table_from_json;
+pub use estimate::estimate_import;
+pub use export::{export_schema, export_table_to_ndjson};
+pub use multi::import_json_files_to_sqlite;
+pub use proposal::propose_import;
+pub use provenance::get_provenance;
+use crate::json::csv_source::read_csv_objects;
+use crate::json::extract::DEFAULT_FLATTEN_SEPARATOR;
+use crate::json::remote::{is_url, HttpOptions};
+use convert::LossyNumbers;
+use checkpoints::read_checkpoint;
+use insert::{
+    insert_json_data, insert_objects, read_json_source, InsertOptions, JsonSource, RootOptions,
+    DEFAULT_STREAMING_THRESHOLD_BYTES,
+};
+use progress_log::with_progress_log;
 use update::update_sqlite_from_json_data;
 
+/// Métacaractères SQL refusés dans les identifiants (séparateur d'instructions, guillemets,
+/// délimiteurs d'identifiants et débuts de commentaires)
+const IDENTIFIER_FORBIDDEN: [&str; 8] = [";", "'", "\"", "`", "[", "]", "--", "/*"];
+
+/// Vérifie qu'un nom de table ou de colonne fourni par la configuration est sûr avant de
+/// construire du SQL, en complément de `quote_identifier`: un nom vide, un caractère de
+/// contrôle (dont l'octet NUL) ou un métacaractère SQL est refusé. Les espaces, les tirets
+/// et les lettres accentuées (`first name`, `a-b`, `prénom`) restent acceptés, ces noms étant
+/// toujours cités.
+pub fn validate_identifier(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err(format!("Identifiant invalide: {:?} (nom vide)", name));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(format!(
+            "Identifiant invalide: {:?} (caractère de contrôle ou octet NUL)",
+            name
+        ));
+    }
+    if let Some(forbidden) = IDENTIFIER_FORBIDDEN.iter().find(|m| name.contains(*m)) {
+        return Err(format!(
+            "Identifiant invalide: {:?} (métacaractère SQL `{}` interdit)",
+            name, forbidden
+        ));
+    }
+
+    Ok(())
+}
+
+/// Vérifie un nom de colonne issu d'un chemin (`user.address.city`): chaque segment séparé
+/// par un point doit être un identifiant valide. Ces noms ne sont utilisés que cités.
+pub fn validate_column_path(name: &str) -> Result<(), String> {
+    name.split('.').try_for_each(validate_identifier)
+}
+
+/// Entoure un identifiant SQL de guillemets doubles en échappant ceux qu'il contient
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Charge des extensions SQLite (bibliothèques natives) sur la connexion.
+/// Sécurité: une extension est du code natif exécuté dans le processus de l'application,
+/// avec ses droits; seules des bibliothèques de confiance doivent être chargées. Le chargement
+/// est réactivé le temps de l'appel puis désactivé, pour que la fonction SQL
+/// `load_extension()` reste inaccessible aux requêtes.
+pub fn load_extensions(conn: &Connection, extensions: &[String]) -> Result<(), String> {
+    if extensions.is_empty() {
+        return Ok(());
+    }
+
+    // SAFETY: les chemins proviennent de la configuration fournie par l'utilisateur,
+    // qui choisit explicitement les bibliothèques à charger
+    unsafe { conn.load_extension_enable() }
+        .map_err(|e| format!("Impossible d'activer le chargement des extensions: {}", e))?;
+
+    let result = extensions.iter().try_for_each(|path| {
+        // SAFETY: voir ci-dessus
+        unsafe { conn.load_extension(path, None) }
+            .map_err(|e| format!("Erreur lors du chargement de l'extension {}: {}", path, e))
+    });
+
+    conn.load_extension_disable()
+        .map_err(|e| format!("Impossible de désactiver le chargement des extensions: {}", e))?;
+
+    result
+}
+
+/// Applique la taille de page et l'encodage demandés à une base nouvellement créée.
+/// Ces réglages n'ont d'effet que sur une base vide: sur une base existante, ils sont ignorés
+/// et un avertissement est retourné.
+pub fn apply_new_database_pragmas(
+    conn: &Connection,
+    page_size: Option<u32>,
+    encoding: Option<&str>,
+) -> Result<Option<String>, String> {
+    if page_size.is_none() && encoding.is_none() {
+        return Ok(None);
+    }
+
+    if let Some(size) = page_size {
+        if !(512..=65536).contains(&size) || !size.is_power_of_two() {
+            return Err(format!(
+                "Taille de page invalide: {} (puissance de 2 entre 512 et 65536)",
+                size
+            ));
+        }
+    }
+
+    if let Some(enc) = encoding {
+        if !["UTF-8", "UTF-16", "UTF-16le", "UTF-16be"]
+            .iter()
+            .any(|valid| valid.eq_ignore_ascii_case(enc))
+        {
+            return Err(format!(
+                "Encodage invalide: {} (UTF-8, UTF-16, UTF-16le ou UTF-16be)",
+                enc
+            ));
+        }
+    }
+
+    let object_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get(0))
+        .map_err(|e| format!("Erreur lors de la lecture du schéma: {}", e))?;
+
+    if object_count > 0 {
+        return Ok(Some(
+            "page_size/encoding ignorés: ils ne s'appliquent qu'à une base vide".to_string(),
+        ));
+    }
+
+    // L'encodage doit être défini avant toute écriture dans la base
+    if let Some(enc) = encoding {
+        conn.execute_batch(&format!("PRAGMA encoding = '{}'", enc))
+            .map_err(|e| format!("Erreur lors de la définition de l'encodage: {}", e))?;
+    }
+
+    if let Some(size) = page_size {
+        conn.execute_batch(&format!("PRAGMA page_size = {}", size))
+            .map_err(|e| format!("Erreur lors de la définition de la taille de page: {}", e))?;
+    }
+
+    Ok(None)
+}
+
+/// Applique les réglages de performance de la connexion d'importation (cache de pages et
+/// mémoire partagée); ils disparaissent avec la connexion, sans restauration nécessaire
+pub fn apply_performance_pragmas(
+    conn: &Connection,
+    cache_size: Option<i64>,
+    mmap_size: Option<u64>,
+) -> Result<(), String> {
+    if let Some(size) = cache_size {
+        if size == 0 {
+            return Err("cache_size invalide: 0 (pages si positif, Kio si négatif)".to_string());
+        }
+        conn.execute_batch(&format!("PRAGMA cache_size = {}", size))
+            .map_err(|e| format!("Erreur lors de la définition de cache_size: {}", e))?;
+    }
+
+    if let Some(size) = mmap_size {
+        if size == 0 || size > i64::MAX as u64 {
+            return Err(format!("mmap_size invalide: {} (octets, strictement positif)", size));
+        }
+        conn.execute_batch(&format!("PRAGMA mmap_size = {}", size))
+            .map_err(|e| format!("Erreur lors de la définition de mmap_size: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Comportement rusqlite correspondant au mode de début de transaction
+pub fn transaction_behavior(mode: BeginMode) -> TransactionBehavior {
+    match mode {
+        BeginMode::Deferred => TransactionBehavior::Deferred,
+        BeginMode::Immediate => TransactionBehavior::Immediate,
+        BeginMode::Exclusive => TransactionBehavior::Exclusive,
+    }
+}
+
+/// Transaction d'une importation: transaction dédiée, ou point de sauvegarde lorsque la
+/// connexion est déjà dans la transaction de l'appelant (importation multi-fichiers)
+pub enum ImportTransaction<'c> {
+    Transaction(Transaction<'c>),
+    Savepoint(Savepoint<'c>),
+}
+
+impl<'c> ImportTransaction<'c> {
+    pub fn begin(conn: &'c mut Connection, mode: BeginMode) -> SqliteResult<Self> {
+        if conn.is_autocommit() {
+            conn.transaction_with_behavior(transaction_behavior(mode))
+                .map(ImportTransaction::Transaction)
+        } else {
+            conn.savepoint().map(ImportTransaction::Savepoint)
+        }
+    }
+
+    pub fn commit(self) -> SqliteResult<()> {
+        match self {
+            ImportTransaction::Transaction(tx) => tx.commit(),
+            ImportTransaction::Savepoint(savepoint) => savepoint.commit(),
+        }
+    }
+
+    pub fn rollback(self) -> SqliteResult<()> {
+        match self {
+            ImportTransaction::Transaction(tx) => tx.rollback(),
+            ImportTransaction::Savepoint(mut savepoint) => {
+                savepoint.rollback()?;
+                savepoint.commit()
+            }
+        }
+    }
+}
+
+impl Deref for ImportTransaction<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            ImportTransaction::Transaction(tx) => tx,
+            ImportTransaction::Savepoint(savepoint) => savepoint,
+        }
+    }
+}
+
+/// Vérifie qu'aucune autre connexion ne verrouille la base en tentant un `BEGIN IMMEDIATE`.
+/// En cas de verrou, retourne une erreur explicite mentionnant les fichiers -wal/-journal présents.
+pub fn check_database_not_locked(conn: &Connection, db_path: &str) -> Result<(), String> {
+    match conn.execute_batch("BEGIN IMMEDIATE; ROLLBACK;") {
+        Ok(_) => Ok(()),
+        Err(rusqlite::Error::SqliteFailure(err, _))
+            if err.code == ErrorCode::DatabaseBusy || err.code == ErrorCode::DatabaseLocked =>
+        {
+            let sidecars: Vec<String> = ["-wal", "-journal"]
+                .iter()
+                .map(|suffix| format!("{}{}", db_path, suffix))
+                .filter(|path| Path::new(path).exists())
+                .collect();
+
+            let mut message = "La base de données est verrouillée par une autre connexion; \
+                               fermez les autres outils qui l'utilisent"
+                .to_string();
+            if !sidecars.is_empty() {
+                message.push_str(&format!(" (fichiers présents: {})", sidecars.join(", ")));
+            }
+
+            Err(message)
+        }
+        Err(e) => Err(format!(
+            "Erreur lors de la vérification du verrou de la base de données: {}",
+            e
+        )),
+    }
+}
+
 /// Récupère la liste des tables d'une base de données SQLite
 pub fn get_tables(db_path: &str) -> Result<Vec<String>, rusqlite::Error> {
     let conn = Connection::open(db_path)?;
@@ -28,6 +307,43 @@ pub fn get_tables(db_path: &str) -> Result<Vec<String>, rusqlite::Error> {
     Ok(table_names)
 }
 
+/// Valeur retournée par `get_table_counts` pour une table dont le comptage échoue
+pub const TABLE_COUNT_ERROR: i64 = -1;
+
+/// Nombre de lignes de chaque table, sur une seule connexion. Une table illisible
+/// (ex: corrompue) reçoit `TABLE_COUNT_ERROR` sans interrompre le comptage des autres.
+pub fn get_table_counts(db_path: &str) -> Result<HashMap<String, i64>, String> {
+    let conn = Connection::open(db_path)
+        .map_err(|e| format!("Erreur à l'ouverture de la base de données: {}", e))?;
+
+    let table_names = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%'")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<String>, _>>()
+        })
+        .map_err(|e| format!("Erreur lors de la lecture des tables: {}", e))?;
+
+    let counts = table_names
+        .into_iter()
+        .map(|name| {
+            let count = conn
+                .query_row(
+                    &format!("SELECT COUNT(*) FROM {}", quote_identifier(&name)),
+                    [],
+                    |row| row.get::<_, i64>(0),
+                )
+                .unwrap_or_else(|e| {
+                    eprintln!("Erreur lors du comptage de la table {}: {}", name, e);
+                    TABLE_COUNT_ERROR
+                });
+            (name, count)
+        })
+        .collect();
+
+    Ok(counts)
+}
+
 /// Analyse la structure d'une table SQLite de manière robuste
 pub fn analyze_table(db_path: &str, table_name: &str) -> Result<TableInfo, String> {
     let conn = match Connection::open(db_path) {
@@ -192,27 +508,333 @@ pub fn analyze_table(db_path: &str, table_name: &str) -> Result<TableInfo, Strin
     })
 }
 
-/// Fonction principale pour importer des données JSON dans SQLite
-pub fn import_json_to_sqlite<F>(
+/// Charge un fichier JSON de paires `{clé: valeur}` (mapping, défauts, valeurs forcées...)
+fn load_map_file<T: DeserializeOwned>(file_path: &str) -> Result<HashMap<String, T>, String> {
+    let content = std::fs::read_to_string(file_path).map_err(|e| {
+        format!(
+            "Erreur lors de la lecture du fichier de mapping {}: {}",
+            file_path, e
+        )
+    })?;
+
+    serde_json::from_str(&content).map_err(|e| {
+        format!(
+            "Fichier de mapping {} invalide (objet {{clé: valeur}} attendu): {}",
+            file_path, e
+        )
+    })
+}
+
+/// Fusionne les valeurs d'un fichier externe avec les valeurs explicites de la configuration.
+/// Les valeurs explicites sont prioritaires en cas de conflit de clé.
+fn merge_with_file<T: DeserializeOwned>(
+    inline: Option<HashMap<String, T>>,
+    file_path: Option<&str>,
+) -> Result<Option<HashMap<String, T>>, String> {
+    let file_path = match file_path {
+        Some(p) => p,
+        None => return Ok(inline),
+    };
+
+    let mut merged = load_map_file::<T>(file_path)?;
+    if let Some(inline_values) = inline {
+        merged.extend(inline_values);
+    }
+
+    Ok(Some(merged))
+}
+
+/// Verrous par base de données: les opérations d'écriture sur une même base sont
+/// sérialisées, tandis que celles sur des bases différentes s'exécutent en parallèle
+static DATABASE_LOCKS: OnceLock<Mutex<HashMap<String, Arc<Mutex<()>>>>> = OnceLock::new();
+
+/// Retourne le verrou associé à une base de données (clé: chemin canonique si possible)
+fn database_lock(db_path: &str) -> Arc<Mutex<()>> {
+    let key = std::fs::canonicalize(db_path)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| db_path.to_string());
+
+    let mut locks = DATABASE_LOCKS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    locks.entry(key).or_default().clone()
+}
+
+/// Nom effectif de la table cible, avec le préfixe et le suffixe éventuels
+fn resolve_table_name(table_name: &str, prefix: Option<&str>, suffix: Option<&str>) -> String {
+    format!(
+        "{}{}{}",
+        prefix.unwrap_or_default(),
+        table_name,
+        suffix.unwrap_or_default()
+    )
+}
+
+/// Fonction principale pour importer des données JSON dans SQLite; `parse_progress` reçoit
+/// l'avancement de la lecture du fichier, avant la progression de l'importation
+pub fn import_json_to_sqlite<F, P>(
+    config: ImportConfig,
+    progress_callback: F,
+    parse_progress: P,
+) -> Result<ImportSummary, String>
+where
+    F: Fn(ImportProgress) + Send + 'static,
+    P: Fn(ParseProgress) + Send + 'static,
+{
+    // Une seule opération d'écriture à la fois sur une même base
+    let lock = database_lock(&config.db_path);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+    let progress_callback = with_progress_log(config.progress_log.as_deref(), progress_callback)?;
+
+    let source = read_json_source(
+        &config.json_path,
+        &config.json_root,
+        &http_options(&config),
+        config.lossy_utf8.unwrap_or(false),
+        config.lenient_numbers.unwrap_or(false),
+        config
+            .streaming_threshold_bytes
+            .unwrap_or(DEFAULT_STREAMING_THRESHOLD_BYTES),
+        parse_progress,
+    )?;
+
+    insert_json_source(config, source, None, progress_callback)
+}
+
+/// Options HTTP d'une source distante
+fn http_options(config: &ImportConfig) -> HttpOptions {
+    HttpOptions {
+        timeout_secs: config.http_timeout_secs,
+        headers: config.http_headers.clone(),
+    }
+}
+
+/// Insère une source JSON déjà lue selon la configuration d'importation (le verrou de la
+/// base doit être détenu par l'appelant), éventuellement dans la transaction en cours de
+/// la connexion `connection`
+fn insert_json_source<F>(
     config: ImportConfig,
+    source: JsonSource,
+    connection: Option<&mut Connection>,
     progress_callback: F,
-) -> Result<ImportProgress, String>
+) -> Result<ImportSummary, String>
 where
     F: Fn(ImportProgress) + Send + 'static,
 {
-    // Conversion du type ImportConfig en paramètres pour la fonction insert_json_data
+    // Fusion des fichiers de mapping externes avec les valeurs explicites de la configuration
+    let mapping = merge_with_file(Some(config.mapping), config.mapping_file.as_deref())?
+        .unwrap_or_default();
+    let defaults = merge_with_file(config.defaults, config.defaults_file.as_deref())?;
+    let forced = merge_with_file(config.forced, config.forced_file.as_deref())?;
+    let dynamic = merge_with_file(config.dynamic, config.dynamic_file.as_deref())?;
+
+    let table_name = resolve_table_name(
+        &config.table_name,
+        config.table_prefix.as_deref(),
+        config.table_suffix.as_deref(),
+    );
+
+    // Point de reprise: enregistré avec chaque lot validé, relu pour reprendre l'importation
+    let checkpoint_name = config
+        .checkpoint_name
+        .clone()
+        .unwrap_or_else(|| format!("{}:{}", table_name, config.json_path));
+    let checkpoint_name = (config.checkpoint_every.is_some() || config.resume.unwrap_or(false))
+        .then_some(checkpoint_name);
+    let mut offset = config.offset;
+    if let (Some(name), true) = (&checkpoint_name, config.resume.unwrap_or(false)) {
+        let conn = Connection::open(&config.db_path)
+            .map_err(|e| format!("Erreur à l'ouverture de la base de données: {}", e))?;
+        if let Some(next_index) = read_checkpoint(&conn, name)? {
+            offset = Some(next_index);
+        }
+    }
+
+    // Perte de précision: les écritures des nombres sont relevées dans le fichier source,
+    // le JSON parsé ne les conservant pas (une source distante n'est pas relue)
+    let precision_loss = config
+        .detect_precision_loss
+        .unwrap_or(false)
+        .then(|| config.precision_loss_policy.unwrap_or_default());
+    let lossy_numbers = match precision_loss {
+        Some(_) if !is_url(&config.json_path) => Some(
+            LossyNumbers::from_file(&config.json_path)
+                .map_err(|e| format!("Erreur de lecture du fichier JSON: {}", e))?,
+        ),
+        _ => None,
+    };
+
+    let root = RootOptions {
+        json_root: &config.json_root,
+        secondary_roots: config.secondary_roots,
+        skip_first: config.root_skip_first.unwrap_or(0),
+        skip_last: config.root_skip_last.unwrap_or(0),
+        map_root: config.map_root.unwrap_or(false),
+        skip_non_objects: config.skip_non_objects.unwrap_or(false),
+        key_value_columns: config.as_key_value.unwrap_or(false).then(|| {
+            (
+                config.key_column.as_deref().unwrap_or("key"),
+                config.value_column.as_deref().unwrap_or("value"),
+            )
+        }),
+        whole_document_column: config.whole_document_column.as_deref(),
+        flatten_separator: config.auto_flatten.unwrap_or(false).then(|| {
+            config
+                .flatten_separator
+                .as_deref()
+                .unwrap_or(DEFAULT_FLATTEN_SEPARATOR)
+        }),
+        flatten_all: config.flatten_all.unwrap_or(false),
+    };
+
+    let options = InsertOptions {
+        mapping_coalesce: config.mapping_coalesce.as_ref(),
+        defaults,
+        forced,
+        dynamic,
+        expressions: config.expressions,
+        transforms: config.transforms,
+        epoch_columns: config.epoch_columns,
+        conversion_error_policy: config.conversion_error_policy,
+        strict_integers: config.strict_integers.unwrap_or(false),
+        precision_loss,
+        lossy_numbers: lossy_numbers.as_ref(),
+        lookups: config.lookups,
+        fts_sync: config.fts_sync,
+        split_into_child: config.split_into_child,
+        limit: config.limit,
+        offset,
+        trigger_policy: config.trigger_policy.unwrap_or_default(),
+        profile_after: config.profile_after.unwrap_or(false),
+        replace_contents: config.replace_contents.unwrap_or(false),
+        reset_autoincrement: config.reset_autoincrement.unwrap_or(false),
+        require_empty_table: config.require_empty_table.unwrap_or(false),
+        duplicate_target_policy: config.duplicate_target_policy.unwrap_or_default(),
+        normalize_keys: config.normalize_keys.unwrap_or_default(),
+        return_inserted_keys: config.return_inserted_keys.unwrap_or(false),
+        skip_existing_key: config.skip_existing_key,
+        sequence_column: config.sequence_column.as_deref(),
+        sequence_start: config.sequence_start,
+        counter_column: config.persistent_counter_column.as_deref(),
+        counter_name: config.counter_name.as_deref(),
+        versioned_append: config.versioned_append.as_ref(),
+        derived_key: config.derived_key.as_ref(),
+        commit_every: config.commit_every.or(config.checkpoint_every),
+        checkpoint_name: checkpoint_name.as_deref(),
+        begin_mode: config.begin_mode.unwrap_or_default(),
+        retry_failed: config.retry_failed.unwrap_or(false),
+        ignore_pk_mapping: config.ignore_pk_mapping.unwrap_or(false),
+        per_row_columns: config.per_row_columns.unwrap_or(false),
+        page_size: config.page_size,
+        encoding: config.encoding.as_deref(),
+        cache_size: config.cache_size,
+        mmap_size: config.mmap_size,
+        warn_on_missing_path: config.warn_on_missing_path.unwrap_or(false),
+        reject_file: config.reject_file.as_deref(),
+        audit_source: config.audit.unwrap_or(false).then_some(config.json_path.as_str()),
+        provenance_source: config
+            .record_provenance
+            .unwrap_or(false)
+            .then_some(config.json_path.as_str()),
+        extensions: &config.extensions,
+        post_sql: config.post_sql.as_deref(),
+        dry_run: config.dry_run,
+        dry_run_mode: config.dry_run_mode.unwrap_or_default(),
+        text_columns: Vec::new(),
+        connection,
+    };
+
     insert_json_data(
         &config.json_path,
+        source,
         &config.db_path,
-        &config.json_root,
+        &table_name,
+        &mapping,
+        root,
+        options,
+        progress_callback,
+    )
+}
+
+/// Importe un fichier CSV dans SQLite en réutilisant le pipeline de mapping/insertion JSON
+pub fn import_csv_to_sqlite<F>(
+    config: CsvImportConfig,
+    progress_callback: F,
+) -> Result<ImportSummary, String>
+where
+    F: Fn(ImportProgress) + Send + 'static,
+{
+    // Le séparateur doit être un unique caractère ASCII (virgule par défaut)
+    let delimiter = match config.delimiter.as_deref() {
+        None | Some("") => b',',
+        Some("\\t") => b'\t',
+        Some(d) if d.len() == 1 && d.is_ascii() => d.as_bytes()[0],
+        Some(d) => return Err(format!("Séparateur CSV invalide: '{}'", d)),
+    };
+
+    let objects = read_csv_objects(
+        &config.csv_path,
+        delimiter,
+        config.has_headers.unwrap_or(true),
+    )?;
+
+    let table_name = resolve_table_name(
         &config.table_name,
+        config.table_prefix.as_deref(),
+        config.table_suffix.as_deref(),
+    );
+
+    // Une seule opération d'écriture à la fois sur une même base
+    let lock = database_lock(&config.db_path);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+    let options = InsertOptions {
+        defaults: config.defaults,
+        forced: config.forced,
+        dynamic: config.dynamic,
+        expressions: config.expressions,
+        transforms: config.transforms,
+        epoch_columns: config.epoch_columns,
+        conversion_error_policy: config.conversion_error_policy,
+        limit: config.limit,
+        offset: config.offset,
+        trigger_policy: config.trigger_policy.unwrap_or_default(),
+        profile_after: config.profile_after.unwrap_or(false),
+        replace_contents: config.replace_contents.unwrap_or(false),
+        reset_autoincrement: config.reset_autoincrement.unwrap_or(false),
+        require_empty_table: config.require_empty_table.unwrap_or(false),
+        duplicate_target_policy: config.duplicate_target_policy.unwrap_or_default(),
+        normalize_keys: config.normalize_keys.unwrap_or_default(),
+        return_inserted_keys: config.return_inserted_keys.unwrap_or(false),
+        skip_existing_key: config.skip_existing_key,
+        commit_every: config.commit_every,
+        begin_mode: config.begin_mode.unwrap_or_default(),
+        retry_failed: config.retry_failed.unwrap_or(false),
+        ignore_pk_mapping: config.ignore_pk_mapping.unwrap_or(false),
+        page_size: config.page_size,
+        encoding: config.encoding.as_deref(),
+        cache_size: config.cache_size,
+        mmap_size: config.mmap_size,
+        warn_on_missing_path: config.warn_on_missing_path.unwrap_or(false),
+        audit_source: config.audit.unwrap_or(false).then_some(config.csv_path.as_str()),
+        provenance_source: config
+            .record_provenance
+            .unwrap_or(false)
+            .then_some(config.csv_path.as_str()),
+        dry_run: config.dry_run,
+        dry_run_mode: config.dry_run_mode.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    insert_objects(
+        objects.into(),
+        &config.db_path,
+        &table_name,
         &config.mapping,
-        config.defaults,
-        config.forced,
-        config.dynamic,
-        config.limit,
-        config.offset,
-        config.dry_run,
+        options,
         progress_callback,
     )
 }
@@ -221,10 +843,14 @@ where
 pub fn update_sqlite_from_json<F>(
     config: UpdateConfig,
     progress_callback: F,
-) -> Result<ImportProgress, String>
+) -> Result<ImportSummary, String>
 where
     F: Fn(ImportProgress) + Send + 'static,
 {
+    // Une seule opération d'écriture à la fois sur une même base
+    let lock = database_lock(&config.db_path);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
     // Conversion du type UpdateConfig en paramètres pour la fonction update_sqlite_from_json_data
     update_sqlite_from_json_data(
         &config.json_path,
@@ -234,7 +860,482 @@ where
         &config.key_column,
         &config.update_columns,
         &config.mapping,
+        config.skip_missing_source.unwrap_or(false),
+        config.null_sets_column.unwrap_or(true),
+        config.minimal_update.unwrap_or(false),
+        config
+            .expected_version_column
+            .as_deref()
+            .zip(config.expected_version_path.as_deref()),
+        config.begin_mode.unwrap_or_default(),
         config.dry_run,
         progress_callback,
     )
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{query_column, temp_db, temp_path};
+
+    #[test]
+    fn identifiers_reject_only_unsafe_characters() {
+        for name in ["users", "_private", "Order_Items2", "first name", "prénom", "a-b", "2024"] {
+            assert!(validate_identifier(name).is_ok(), "{}", name);
+        }
+        for name in ["", "  ", "a;b", "a\"b", "a'b", "a`b", "[a]", "a--b", "a/*b", "a\tb", "a\nb"] {
+            assert!(validate_identifier(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn identifier_with_semicolon_names_the_metacharacter() {
+        assert_eq!(
+            validate_identifier("users;"),
+            Err("Identifiant invalide: \"users;\" (métacaractère SQL `;` interdit)".to_string())
+        );
+    }
+
+    #[test]
+    fn quoted_names_with_spaces_and_accents_can_be_imported() {
+        let db_path = temp_db("CREATE TABLE \"mes données\" (\"first name\" TEXT, prénom TEXT);");
+        let mapping = HashMap::from([
+            ("first".to_string(), "first name".to_string()),
+            ("prenom".to_string(), "prénom".to_string()),
+        ]);
+
+        let summary = insert::insert_objects(
+            vec![json!({"first": "Ana", "prenom": "Zoé"})].into(),
+            &db_path,
+            "mes données",
+            &mapping,
+            InsertOptions::default(),
+            |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(summary.inserted, 1);
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT \"first name\" || ':' || prénom FROM \"mes données\"",
+        );
+        assert_eq!(rows, ["Ana:Zoé"]);
+    }
+
+    #[test]
+    fn identifier_with_nul_byte_is_rejected() {
+        assert!(validate_identifier("users\0").is_err());
+        assert!(validate_column_path("user.na\0me").is_err());
+    }
+
+    #[test]
+    fn identifier_with_semicolon_is_rejected_before_any_sql() {
+        let db_path = temp_db("CREATE TABLE users (id INTEGER);");
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+
+        let result = insert::insert_objects(
+            vec![json!({ "id": 1 })].into(),
+            &db_path,
+            "users; DROP TABLE users",
+            &mapping,
+            InsertOptions::default(),
+            |_| {},
+        );
+
+        assert!(result.unwrap_err().starts_with("Identifiant invalide"));
+        let conn = Connection::open(&db_path).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn quoted_identifiers_escape_double_quotes() {
+        assert_eq!(quote_identifier("users"), "\"users\"");
+        assert_eq!(quote_identifier("a\"b"), "\"a\"\"b\"");
+    }
+
+    /// Importation du CSV `content` dans la table `items` créée par `schema`
+    fn import_csv(
+        schema: &str,
+        content: &str,
+        options: JsonValue,
+    ) -> (String, Result<ImportSummary, String>) {
+        let csv_path = temp_path("csv");
+        std::fs::write(&csv_path, content).unwrap();
+        let db_path = temp_db(schema);
+        let mut config = json!({
+            "csv_path": csv_path,
+            "db_path": db_path,
+            "table_name": "items",
+            "dry_run": false,
+        });
+        config.as_object_mut().unwrap().extend(options.as_object().unwrap().clone());
+
+        let config: CsvImportConfig = serde_json::from_value(config).unwrap();
+        (db_path, import_csv_to_sqlite(config, |_| {}))
+    }
+
+    #[test]
+    fn csv_with_custom_delimiter_goes_through_the_mapping() {
+        let (db_path, result) = import_csv(
+            "CREATE TABLE items (id INTEGER, label TEXT, origin TEXT);",
+            "id;name\n1;a,b\n2;c\n",
+            json!({
+                "delimiter": ";",
+                "mapping": {"id": "id", "name": "label"},
+                "forced": {"origin": "csv"},
+            }),
+        );
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT id || '|' || label || '|' || origin FROM items ORDER BY id",
+        );
+        assert_eq!(rows, ["1|a,b|csv", "2|c|csv"]);
+    }
+
+    #[test]
+    fn csv_without_headers_uses_column_numbers() {
+        let (db_path, result) = import_csv(
+            "CREATE TABLE items (id INTEGER, label TEXT);",
+            "1\ta\n2\tb\n",
+            json!({
+                "delimiter": "\\t",
+                "has_headers": false,
+                "mapping": {"column_1": "id", "column_2": "label"},
+            }),
+        );
+
+        assert_eq!(result.unwrap().inserted, 2);
+        let labels: Vec<String> = query_column(&db_path, "SELECT label FROM items ORDER BY id");
+        assert_eq!(labels, ["a", "b"]);
+    }
+
+    #[test]
+    fn csv_delimiter_must_be_one_ascii_character() {
+        let (_, result) = import_csv(
+            "CREATE TABLE items (id INTEGER);",
+            "id\n1\n",
+            json!({"delimiter": "||", "mapping": {"id": "id"}}),
+        );
+        assert_eq!(result.unwrap_err(), "Séparateur CSV invalide: '||'");
+    }
+
+    #[test]
+    fn database_locks_are_shared_per_path() {
+        let first = temp_db("");
+        let second = temp_db("");
+
+        assert!(Arc::ptr_eq(&database_lock(&first), &database_lock(&first)));
+        assert!(!Arc::ptr_eq(&database_lock(&first), &database_lock(&second)));
+    }
+
+    /// Configuration d'importation de `json_path` dans la table `items`, complétée par `options`
+    fn json_config(json_path: &str, db_path: &str, options: JsonValue) -> ImportConfig {
+        let mut config = json!({
+            "json_path": json_path,
+            "db_path": db_path,
+            "json_root": "",
+            "table_name": "items",
+            "dry_run": false,
+        });
+        config.as_object_mut().unwrap().extend(options.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[test]
+    fn concurrent_imports_into_the_same_database_are_serialized() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER, batch INTEGER);");
+        let imports: Vec<_> = (0..4)
+            .map(|batch| {
+                let rows: Vec<JsonValue> =
+                    (0..200).map(|id| json!({"id": id, "batch": batch})).collect();
+                let json_path = temp_path("json");
+                std::fs::write(&json_path, JsonValue::Array(rows).to_string()).unwrap();
+                let mapping = json!({"mapping": {"id": "id", "batch": "batch"}});
+                let config = json_config(&json_path, &db_path, mapping);
+                std::thread::spawn(move || import_json_to_sqlite(config, |_| {}, |_| {}))
+            })
+            .collect();
+
+        for import in imports {
+            assert_eq!(import.join().unwrap().unwrap().inserted, 200);
+        }
+        let counts: Vec<i64> = query_column(
+            &db_path,
+            "SELECT COUNT(*) FROM items GROUP BY batch ORDER BY batch",
+        );
+        assert_eq!(counts, [200; 4]);
+    }
+
+    #[test]
+    fn prefix_and_suffix_resolve_the_target_table() {
+        let db_path = temp_db(
+            "CREATE TABLE items (code TEXT);
+             CREATE TABLE t_acme_items_v2 (code TEXT);
+             CREATE UNIQUE INDEX acme_code ON t_acme_items_v2 (code);
+             INSERT INTO t_acme_items_v2 VALUES ('a');",
+        );
+        let json_path = temp_path("json");
+        std::fs::write(&json_path, r#"[{"code": "a"}, {"code": "b"}]"#).unwrap();
+        let options = json!({
+            "mapping": {"code": "code"},
+            "table_prefix": "t_acme_",
+            "table_suffix": "_v2",
+        });
+
+        let report = conflicts::check_conflicts(json_config(&json_path, &db_path, options.clone()))
+            .unwrap();
+        assert_eq!((report.conflicting_rows, report.unique_columns), (1, vec!["code".into()]));
+
+        let summary =
+            import_json_to_sqlite(json_config(&json_path, &db_path, options), |_| {}, |_| {})
+                .unwrap();
+        assert_eq!((summary.inserted, summary.failed), (1, 1));
+        let codes: Vec<String> = query_column(&db_path, "SELECT code FROM t_acme_items_v2");
+        assert_eq!(codes, ["a", "b"]);
+        let untouched: Vec<String> = query_column(&db_path, "SELECT code FROM items");
+        assert!(untouched.is_empty());
+    }
+
+    #[test]
+    fn page_size_and_encoding_apply_to_a_new_database() {
+        let db_path = temp_path("db");
+        let conn = Connection::open(&db_path).unwrap();
+
+        let warning = apply_new_database_pragmas(&conn, Some(16384), Some("UTF-16le")).unwrap();
+        assert!(warning.is_none());
+        conn.execute_batch("CREATE TABLE items (id INTEGER);").unwrap();
+        drop(conn);
+
+        let page_size: Vec<i64> = query_column(&db_path, "PRAGMA page_size");
+        assert_eq!(page_size, [16384]);
+        let encoding: Vec<String> = query_column(&db_path, "PRAGMA encoding");
+        assert_eq!(encoding, ["UTF-16le"]);
+
+        let conn = Connection::open(&db_path).unwrap();
+        assert!(apply_new_database_pragmas(&conn, Some(12000), None).is_err());
+    }
+
+    #[test]
+    fn page_size_on_an_existing_database_is_ignored_with_a_warning() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        let options = InsertOptions {
+            page_size: Some(16384),
+            ..Default::default()
+        };
+
+        let objects = vec![json!({"id": 1})];
+        let summary =
+            insert::insert_objects(objects.into(), &db_path, "items", &mapping, options, |_| {})
+                .unwrap();
+
+        assert_eq!(summary.inserted, 1);
+        assert!(summary.warnings.iter().any(|w| w.starts_with("page_size/encoding ignorés")));
+        let page_size: Vec<i64> = query_column(&db_path, "PRAGMA page_size");
+        assert_ne!(page_size, [16384]);
+    }
+
+    /// Compile une extension minimale (point d'entrée par défaut, sans fonction enregistrée)
+    fn build_noop_extension() -> String {
+        let source = temp_path("c");
+        std::fs::write(
+            &source,
+            "int sqlite3_extension_init(void *db, char **err, const void *api) { return 0; }",
+        )
+        .unwrap();
+        let library = temp_path("so");
+        let status = std::process::Command::new("cc")
+            .args(["-shared", "-fPIC", "-o", &library, &source])
+            .status()
+            .expect("compilateur C (cc) requis pour ce test");
+        assert!(status.success());
+        library
+    }
+
+    fn import_with_extensions(db_path: &str, extensions: &[String]) -> Result<u32, String> {
+        let mapping = HashMap::from([("id".to_string(), "id".to_string())]);
+        let options = InsertOptions {
+            extensions,
+            ..Default::default()
+        };
+        let objects = vec![json!({"id": 1})];
+        insert::insert_objects(objects.into(), db_path, "items", &mapping, options, |_| {})
+            .map(|summary| summary.inserted)
+    }
+
+    #[test]
+    #[ignore = "compile une extension avec un compilateur C (cc)"]
+    fn extensions_are_loaded_before_the_import() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+        let extension = build_noop_extension();
+
+        let inserted = import_with_extensions(&db_path, std::slice::from_ref(&extension));
+
+        assert_eq!(inserted, Ok(1));
+    }
+
+    #[test]
+    fn missing_extension_stops_the_import_before_inserting() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+
+        let error =
+            import_with_extensions(&db_path, &["/introuvable/libabsente.so".to_string()])
+                .unwrap_err();
+
+        assert!(error.starts_with("Erreur lors du chargement de l'extension"), "{}", error);
+        assert!(error.contains("/introuvable/libabsente.so"), "{}", error);
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items");
+        assert!(ids.is_empty());
+    }
+
+    #[test]
+    fn extension_loading_is_disabled_afterwards() {
+        let conn = Connection::open_in_memory().unwrap();
+        let missing = "/introuvable/libabsente.so".to_string();
+
+        // Désactivé même après un échec de chargement
+        assert!(load_extensions(&conn, std::slice::from_ref(&missing)).is_err());
+
+        let from_sql = conn
+            .query_row("SELECT load_extension(?)", [&missing], |_| Ok(()))
+            .unwrap_err();
+        assert!(from_sql.to_string().contains("not authorized"), "{}", from_sql);
+    }
+
+    #[test]
+    fn begin_mode_decides_when_locks_are_taken() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+        let other = Connection::open(&db_path).unwrap();
+        other.busy_timeout(std::time::Duration::ZERO).unwrap();
+
+        // (mode, autre écrivain possible, autre lecteur possible)
+        let expected = [
+            (BeginMode::Deferred, true, true),
+            (BeginMode::Immediate, false, true),
+            (BeginMode::Exclusive, false, false),
+        ];
+        for (mode, can_write, can_read) in expected {
+            let mut conn = Connection::open(&db_path).unwrap();
+            let tx = ImportTransaction::begin(&mut conn, mode).unwrap();
+            assert!(matches!(tx, ImportTransaction::Transaction(_)));
+
+            let write = other.execute_batch("BEGIN IMMEDIATE; ROLLBACK;");
+            assert_eq!(write.is_ok(), can_write, "{:?}", mode);
+            let read =
+                other.query_row("SELECT COUNT(*) FROM items", [], |row| row.get::<_, i64>(0));
+            assert_eq!(read.is_ok(), can_read, "{:?}", mode);
+            if !can_write {
+                let error = check_database_not_locked(&other, &db_path).unwrap_err();
+                assert!(error.starts_with("La base de données est verrouillée"), "{}", error);
+            }
+
+            tx.rollback().unwrap();
+        }
+    }
+
+    #[test]
+    fn import_inside_an_open_transaction_uses_a_savepoint() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE items (id INTEGER); BEGIN; INSERT INTO items VALUES (1);")
+            .unwrap();
+
+        let tx = ImportTransaction::begin(&mut conn, BeginMode::Immediate).unwrap();
+        assert!(matches!(tx, ImportTransaction::Savepoint(_)));
+        tx.execute("INSERT INTO items VALUES (2)", []).unwrap();
+        tx.rollback().unwrap();
+
+        let count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+        assert!(!conn.is_autocommit());
+    }
+
+    #[test]
+    fn table_counts_cover_every_table_and_flag_unreadable_ones() {
+        let db_path = temp_db(
+            "CREATE TABLE a (x INTEGER); INSERT INTO a VALUES (1), (2), (3);
+             CREATE TABLE \"b c\" (x INTEGER); INSERT INTO \"b c\" VALUES (1);
+             CREATE TABLE empty (x INTEGER);
+             CREATE VIEW v AS SELECT * FROM a;
+             PRAGMA writable_schema = ON;
+             INSERT INTO sqlite_master VALUES
+                 ('table', 'ghost', 'ghost', 0, 'CREATE VIRTUAL TABLE ghost USING absent');",
+        );
+
+        let counts = get_table_counts(&db_path).unwrap();
+
+        let expected = HashMap::from([
+            ("a".to_string(), 3),
+            ("b c".to_string(), 1),
+            ("empty".to_string(), 0),
+            ("ghost".to_string(), TABLE_COUNT_ERROR),
+        ]);
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn performance_pragmas_are_applied_to_the_connection() {
+        let conn = Connection::open(temp_db("CREATE TABLE items (id INTEGER);")).unwrap();
+        let pragma = |name: &str| -> i64 {
+            conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get(0)).unwrap()
+        };
+
+        apply_performance_pragmas(&conn, Some(-8192), Some(1 << 20)).unwrap();
+        assert_eq!((pragma("cache_size"), pragma("mmap_size")), (-8192, 1 << 20));
+
+        apply_performance_pragmas(&conn, Some(500), None).unwrap();
+        assert_eq!((pragma("cache_size"), pragma("mmap_size")), (500, 1 << 20));
+    }
+
+    #[test]
+    fn invalid_performance_pragmas_are_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        let error = apply_performance_pragmas(&conn, Some(0), None).unwrap_err();
+        assert_eq!(error, "cache_size invalide: 0 (pages si positif, Kio si négatif)");
+        let error = apply_performance_pragmas(&conn, None, Some(0)).unwrap_err();
+        assert_eq!(error, "mmap_size invalide: 0 (octets, strictement positif)");
+        assert!(apply_performance_pragmas(&conn, None, Some(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn interrupted_streaming_import_resumes_from_its_checkpoint() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+        let rows: Vec<JsonValue> = (1..=9).map(|id| json!({"id": id})).collect();
+        let content = JsonValue::Array(rows).to_string();
+        let json_path = temp_path("json");
+        let options = json!({
+            "mapping": {"id": "id"},
+            "streaming_threshold_bytes": 0,
+            "checkpoint_every": 2,
+            "resume": true,
+        });
+
+        let import = || {
+            let config = json_config(&json_path, &db_path, options.clone());
+            import_json_to_sqlite(config, |_| {}, |_| {})
+        };
+        let checkpoint_name = format!("items:{}", json_path);
+
+        // Fichier tronqué après le 6e objet: le lot en cours est annulé, les deux premiers
+        // lots restent validés avec leur point de reprise
+        let truncated = &content[..content.find(r#"{"id":7}"#).unwrap() + 4];
+        std::fs::write(&json_path, truncated).unwrap();
+        assert!(import().unwrap_err().starts_with("Lecture interrompue après 6 objet(s)"));
+        let conn = Connection::open(&db_path).unwrap();
+        assert_eq!(read_checkpoint(&conn, &checkpoint_name), Ok(Some(4)));
+
+        std::fs::write(&json_path, &content).unwrap();
+        assert_eq!(import().unwrap().inserted, 5);
+        assert_eq!(read_checkpoint(&conn, &checkpoint_name), Ok(Some(9)));
+
+        // Tout est importé: une nouvelle reprise ne relit aucun élément validé
+        assert_eq!(import().unwrap().inserted, 0);
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, (1..=9).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,436 @@
+use crate::commands::{
+    FileOutcome, ImportConfig, ImportIsolation, ImportProgress, ImportSummary, RowError,
+};
+use rusqlite::Connection;
+use std::sync::mpsc::sync_channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use super::insert::{
+    read_json_source, run_post_sql, JsonSource, DEFAULT_STREAMING_THRESHOLD_BYTES,
+};
+use super::progress_log::with_progress_log;
+use super::{check_database_not_locked, database_lock, http_options, insert_json_source};
+
+/// Nombre maximal de fichiers parsés en attente d'insertion (borne la mémoire utilisée)
+const PARSED_FILES_QUEUE_DEPTH: usize = 2;
+
+/// Nombre de threads de parsing par défaut
+const DEFAULT_PARSE_THREADS: usize = 4;
+
+/// Importe plusieurs fichiers JSON dans la même table: un groupe de threads lit et parse
+/// les fichiers pendant qu'un unique écrivain (SQLite n'en accepte qu'un) insère ceux déjà
+/// prêts. Les fichiers sont insérés dans l'ordre où leur parsing se termine; `json_path`
+/// de la configuration est remplacé par chaque fichier. Un fichier illisible ou en échec
+/// annule tous les fichiers (`all_or_nothing`) ou seulement ce fichier (`per_file`).
+/// Le script `post_sql` est exécuté une seule fois, après le dernier fichier, et le profil
+/// des colonnes (`profile_after`) calculé avec le dernier fichier; l'historique
+/// (`audit`) et les points de reprise restent propres à chaque fichier.
+pub fn import_json_files_to_sqlite<F>(
+    config: ImportConfig,
+    json_paths: Vec<String>,
+    parse_threads: Option<u32>,
+    isolation: ImportIsolation,
+    progress_callback: F,
+) -> Result<ImportSummary, String>
+where
+    F: Fn(ImportProgress) + Send + Sync + 'static,
+{
+    let start_time = Instant::now();
+
+    if json_paths.is_empty() {
+        return Err("Aucun fichier JSON à importer".to_string());
+    }
+
+    // Une seule opération d'écriture à la fois sur une même base
+    let lock = database_lock(&config.db_path);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+    let progress_callback = with_progress_log(config.progress_log.as_deref(), progress_callback)?;
+
+    let file_count = json_paths.len();
+    let thread_count = parse_threads
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_PARSE_THREADS)
+        .clamp(1, file_count);
+
+    // Tout-ou-rien: une transaction englobe tous les fichiers, chacun y devenant un point de
+    // sauvegarde; en cas d'erreur, la connexion fermée sans commit annule la transaction
+    let mut shared_conn = match isolation {
+        ImportIsolation::AllOrNothing => {
+            let conn = Connection::open(&config.db_path)
+                .map_err(|e| format!("Erreur de connexion à la base de données: {}", e))?;
+            check_database_not_locked(&conn, &config.db_path)?;
+            conn.execute_batch("BEGIN IMMEDIATE")
+                .map_err(|e| format!("Erreur lors de la création de la transaction: {}", e))?;
+            Some(conn)
+        }
+        ImportIsolation::PerFile => None,
+    };
+
+    // Producteurs: chaque thread prend le prochain fichier de la file et envoie le résultat
+    // du parsing; l'envoi bloque tant que la file des fichiers parsés est pleine
+    let pending = Arc::new(Mutex::new(json_paths.into_iter()));
+    let (sender, receiver) = sync_channel::<(String, Result<JsonSource, String>)>(
+        PARSED_FILES_QUEUE_DEPTH,
+    );
+
+    let http = http_options(&config);
+    let json_root = config.json_root.clone();
+    let lossy_utf8 = config.lossy_utf8.unwrap_or(false);
+    let lenient_numbers = config.lenient_numbers.unwrap_or(false);
+    let streaming_threshold = config
+        .streaming_threshold_bytes
+        .unwrap_or(DEFAULT_STREAMING_THRESHOLD_BYTES);
+
+    for _ in 0..thread_count {
+        let pending = Arc::clone(&pending);
+        let sender = sender.clone();
+        let http = http.clone();
+        let json_root = json_root.clone();
+
+        thread::spawn(move || loop {
+            let next = pending.lock().unwrap_or_else(|e| e.into_inner()).next();
+            let Some(path) = next else { break };
+
+            let source = read_json_source(
+                &path,
+                &json_root,
+                &http,
+                lossy_utf8,
+                lenient_numbers,
+                streaming_threshold,
+                |_| {},
+            );
+            // Récepteur fermé: l'importation s'est arrêtée sur une erreur
+            if sender.send((path, source)).is_err() {
+                break;
+            }
+        });
+    }
+    drop(sender);
+
+    // Écrivain: insertion des fichiers parsés, avec une progression cumulée
+    let progress_callback = Arc::new(progress_callback);
+    let mut summary = ImportSummary {
+        file_outcomes: (isolation == ImportIsolation::PerFile).then(Vec::new),
+        ..Default::default()
+    };
+    let mut files_done = 0;
+
+    for (path, source) in receiver {
+        files_done += 1;
+
+        let source = match source {
+            Ok(source) => source,
+            Err(e) => match summary.file_outcomes.as_mut() {
+                Some(outcomes) => {
+                    eprintln!("Erreur lors de la lecture de {}: {}", path, e);
+                    summary.warnings.push(format!("Fichier {} ignoré: {}", path, e));
+                    outcomes.push(failed_outcome(path, e));
+                    continue;
+                }
+                None => {
+                    return Err(format!(
+                        "Erreur lors de la lecture de {}: {} (aucun fichier importé)",
+                        path, e
+                    ))
+                }
+            },
+        };
+
+        let base = ImportProgress {
+            total: summary.total,
+            processed: summary.total,
+            succeeded: summary.inserted,
+            failed: summary.failed,
+            no_change: summary.ignored + summary.skipped,
+            ..Default::default()
+        };
+        let file_label = format!("[{}/{}] {}", files_done, file_count, path);
+        let callback = Arc::clone(&progress_callback);
+
+        // Script de finalisation exécuté une fois pour tous les fichiers; profil calculé
+        // avec le dernier fichier, la table contenant alors toutes les données
+        let mut file_config = config.clone();
+        file_config.json_path = path.clone();
+        file_config.post_sql = None;
+        file_config.profile_after = config.profile_after.filter(|_| files_done == file_count);
+
+        let connection = shared_conn.as_mut();
+        let result = insert_json_source(file_config, source, connection, move |progress| {
+            callback(ImportProgress {
+                total: base.total + progress.total,
+                processed: base.processed + progress.processed,
+                succeeded: base.succeeded + progress.succeeded,
+                failed: base.failed + progress.failed,
+                no_change: base.no_change + progress.no_change,
+                conflicts: base.conflicts + progress.conflicts,
+                phase: progress.phase,
+                status: format!("{}: {}", file_label, progress.status),
+            })
+        });
+
+        let file_summary = match result {
+            Ok(file_summary) => file_summary,
+            Err(e) => match summary.file_outcomes.as_mut() {
+                Some(outcomes) => {
+                    eprintln!("Erreur lors de l'importation de {}: {}", path, e);
+                    outcomes.push(failed_outcome(path, e));
+                    continue;
+                }
+                None => {
+                    return Err(format!(
+                        "Erreur lors de l'importation de {}: {} (aucun fichier importé)",
+                        path, e
+                    ))
+                }
+            },
+        };
+
+        if let Some(outcomes) = summary.file_outcomes.as_mut() {
+            outcomes.push(FileOutcome {
+                path: path.clone(),
+                succeeded: true,
+                inserted: file_summary.inserted,
+                failed: file_summary.failed,
+                error: None,
+            });
+        }
+        merge_summary(&mut summary, file_summary, &path);
+    }
+
+    if let Some(conn) = shared_conn.as_ref() {
+        conn.execute_batch("COMMIT")
+            .map_err(|e| format!("Erreur lors du commit de la transaction: {}", e))?;
+    }
+
+    // Script de finalisation, après la validation de tous les fichiers (sauf simulation):
+    // les données importées restent validées même si le script échoue
+    let post_sql = config.post_sql.as_deref().filter(|sql| !sql.trim().is_empty());
+    if let Some(sql) = post_sql.filter(|_| !config.dry_run) {
+        let mut conn = match shared_conn {
+            Some(conn) => conn,
+            None => Connection::open(&config.db_path)
+                .map_err(|e| format!("Erreur de connexion à la base de données: {}", e))?,
+        };
+        match run_post_sql(&mut conn, sql) {
+            Ok(()) => summary.post_sql_succeeded = Some(true),
+            Err(e) => {
+                eprintln!("Erreur lors du script de finalisation: {}", e);
+                summary.post_sql_succeeded = Some(false);
+                summary.post_sql_error = Some(e);
+            }
+        }
+    }
+
+    summary.duration_ms = start_time.elapsed().as_millis() as u64;
+
+    Ok(summary)
+}
+
+/// Résultat d'un fichier qui n'a pas pu être lu ou importé
+fn failed_outcome(path: String, error: String) -> FileOutcome {
+    FileOutcome {
+        path,
+        succeeded: false,
+        inserted: 0,
+        failed: 0,
+        error: Some(error),
+    }
+}
+
+/// Cumule le résumé d'un fichier dans le résumé global (erreurs et avertissements préfixés
+/// par le nom du fichier, l'index des erreurs restant relatif au fichier)
+fn merge_summary(summary: &mut ImportSummary, file_summary: ImportSummary, path: &str) {
+    summary.total += file_summary.total;
+    summary.inserted += file_summary.inserted;
+    summary.updated += file_summary.updated;
+    summary.ignored += file_summary.ignored;
+    summary.failed += file_summary.failed;
+    summary.not_found += file_summary.not_found;
+    summary.skipped += file_summary.skipped;
+
+    summary
+        .errors
+        .extend(file_summary.errors.into_iter().map(|error| RowError {
+            message: format!("{}: {}", path, error.message),
+            ..error
+        }));
+    summary.warnings.extend(
+        file_summary
+            .warnings
+            .into_iter()
+            .map(|warning| format!("{}: {}", path, warning)),
+    );
+
+    if file_summary.column_profiles.is_some() {
+        summary.column_profiles = file_summary.column_profiles;
+    }
+    if let Some(keys) = file_summary.inserted_keys {
+        summary.inserted_keys.get_or_insert_with(Vec::new).extend(keys);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{query_column, temp_db, temp_json};
+    use serde_json::{json, Value as JsonValue};
+
+    /// Importation dans la table `items`, chaque fichier étant lu en streaming
+    fn config(db_path: &str, mapping: JsonValue) -> ImportConfig {
+        serde_json::from_value(json!({
+            "json_path": "",
+            "db_path": db_path,
+            "json_root": "",
+            "table_name": "items",
+            "mapping": mapping,
+            "streaming_threshold_bytes": 0,
+            "dry_run": false,
+        }))
+        .unwrap()
+    }
+
+    /// Trois fichiers lus en streaming, le deuxième s'interrompant sur une erreur de syntaxe
+    fn import(
+        isolation: ImportIsolation,
+    ) -> (String, Vec<String>, Result<ImportSummary, String>) {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+        let paths = vec![
+            temp_json(r#"[{"id": 1}, {"id": 2}]"#),
+            temp_json(r#"[{"id": 3}, {"id": }]"#),
+            temp_json(r#"[{"id": 5}, {"id": 6}]"#),
+        ];
+        let config = config(&db_path, json!({"id": "id"}));
+
+        let result =
+            import_json_files_to_sqlite(config, paths.clone(), Some(1), isolation, |_| {});
+        (db_path, paths, result)
+    }
+
+    #[test]
+    fn all_or_nothing_failure_rolls_back_every_file() {
+        let (db_path, paths, result) = import(ImportIsolation::AllOrNothing);
+
+        let error = result.unwrap_err();
+        assert!(error.contains(&paths[1]), "{}", error);
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items");
+        assert!(ids.is_empty());
+    }
+
+    #[test]
+    fn per_file_keeps_the_files_that_succeeded() {
+        let (db_path, paths, result) = import(ImportIsolation::PerFile);
+
+        let summary = result.unwrap();
+        assert_eq!(summary.inserted, 4);
+        let outcomes = summary.file_outcomes.unwrap();
+        let got: Vec<(&str, bool, u32)> = outcomes
+            .iter()
+            .map(|o| (o.path.as_str(), o.succeeded, o.inserted))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                (paths[0].as_str(), true, 2),
+                (paths[1].as_str(), false, 0),
+                (paths[2].as_str(), true, 2),
+            ]
+        );
+        assert!(outcomes[1].error.as_ref().unwrap().contains("Lecture interrompue"));
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items ORDER BY id");
+        assert_eq!(ids, [1, 2, 5, 6]);
+    }
+
+    #[test]
+    fn files_parsed_in_parallel_are_all_inserted() {
+        let db_path = temp_db("CREATE TABLE items (file INTEGER, id INTEGER);");
+        let paths: Vec<String> = (0..8)
+            .map(|file| {
+                let rows: Vec<JsonValue> =
+                    (0..50).map(|id| json!({"file": file, "id": id})).collect();
+                temp_json(&JsonValue::Array(rows).to_string())
+            })
+            .collect();
+        let config = config(&db_path, json!({"file": "file", "id": "id"}));
+        let last_progress = Arc::new(Mutex::new(None));
+        let seen = Arc::clone(&last_progress);
+
+        let summary = import_json_files_to_sqlite(
+            config,
+            paths,
+            Some(4),
+            ImportIsolation::AllOrNothing,
+            move |progress| *seen.lock().unwrap() = Some(progress),
+        )
+        .unwrap();
+
+        assert_eq!((summary.total, summary.inserted, summary.failed), (400, 400, 0));
+        let progress = last_progress.lock().unwrap().take().unwrap();
+        assert_eq!((progress.processed, progress.succeeded), (400, 400));
+        let per_file: Vec<i64> =
+            query_column(&db_path, "SELECT COUNT(DISTINCT id) FROM items GROUP BY file");
+        assert_eq!(per_file, [50; 8]);
+    }
+
+    #[test]
+    fn all_or_nothing_stops_on_an_unreadable_file() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER);");
+        let paths = vec![
+            temp_json(r#"[{"id": 1}, {"id": 2}]"#),
+            temp_json(r#"[{"id": 3}, {"id": }]"#),
+        ];
+        let mut config = config(&db_path, json!({"id": "id"}));
+        config.streaming_threshold_bytes = Some(u64::MAX);
+
+        let result = import_json_files_to_sqlite(
+            config,
+            paths.clone(),
+            Some(1),
+            ImportIsolation::AllOrNothing,
+            |_| {},
+        );
+
+        let error = result.unwrap_err();
+        assert!(error.contains(&paths[1]), "{}", error);
+        let ids: Vec<i64> = query_column(&db_path, "SELECT id FROM items");
+        assert!(ids.is_empty());
+    }
+
+    #[test]
+    fn post_sql_runs_once_after_every_file() {
+        let db_path = temp_db(
+            "CREATE TABLE items (id INTEGER); CREATE TABLE runs (total INTEGER);",
+        );
+        let paths = vec![
+            temp_json(r#"[{"id": 1}, {"id": 2}]"#),
+            temp_json(r#"[{"id": 3}]"#),
+        ];
+        let mut profiled_rows = Vec::new();
+        for isolation in [ImportIsolation::AllOrNothing, ImportIsolation::PerFile] {
+            let mut config = config(&db_path, json!({"id": "id"}));
+            config.post_sql =
+                Some("INSERT INTO runs SELECT COUNT(*) FROM items".to_string());
+            config.profile_after = Some(true);
+
+            let summary =
+                import_json_files_to_sqlite(config, paths.clone(), Some(1), isolation, |_| {})
+                    .unwrap();
+
+            assert_eq!(summary.post_sql_succeeded, Some(true));
+            profiled_rows.push(summary.column_profiles.unwrap()[0].non_null_count);
+        }
+        assert_eq!(profiled_rows, [3, 6]);
+        let runs: Vec<i64> = query_column(&db_path, "SELECT total FROM runs");
+        assert_eq!(runs, [3, 6]);
+    }
+
+    #[test]
+    fn per_table_is_accepted_for_per_file() {
+        let isolation: ImportIsolation = serde_json::from_value(json!("per_table")).unwrap();
+        assert_eq!(isolation, ImportIsolation::PerFile);
+        assert_eq!(json!(isolation), json!("per_file"));
+    }
+}
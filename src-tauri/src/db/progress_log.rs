@@ -0,0 +1,130 @@
+use crate::commands::{ImportPhase, ImportProgress};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::sync::Mutex;
+
+/// Nombre de lignes écrites entre deux vidages du tampon vers le fichier
+const FLUSH_EVERY_LINES: u32 = 20;
+
+/// Journal de progression: chaque instantané est ajouté en fin de fichier sur une ligne JSON
+struct ProgressLog {
+    writer: BufWriter<File>,
+    pending_lines: u32,
+}
+
+impl ProgressLog {
+    fn open(path: &str) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Impossible d'ouvrir le journal de progression {}: {}", path, e))?;
+
+        Ok(ProgressLog {
+            writer: BufWriter::new(file),
+            pending_lines: 0,
+        })
+    }
+
+    /// Ajoute un instantané; le tampon est vidé périodiquement et à chaque fin d'opération.
+    /// Une erreur d'écriture n'interrompt pas l'importation (comme l'émission d'événements).
+    fn append(&mut self, progress: &ImportProgress) {
+        let Ok(line) = serde_json::to_string(progress) else {
+            return;
+        };
+        if let Err(e) = writeln!(self.writer, "{}", line) {
+            eprintln!("Erreur d'écriture dans le journal de progression: {}", e);
+            return;
+        }
+
+        self.pending_lines += 1;
+        let finished = matches!(
+            progress.phase,
+            ImportPhase::Done | ImportPhase::Cancelled | ImportPhase::Failed
+        );
+        if finished || self.pending_lines >= FLUSH_EVERY_LINES {
+            let _ = self.writer.flush();
+            self.pending_lines = 0;
+        }
+    }
+}
+
+/// Ajoute au callback de progression l'écriture de chaque instantané dans `path` (NDJSON),
+/// indépendamment de l'émission des événements Tauri
+pub fn with_progress_log<F>(
+    path: Option<&str>,
+    progress_callback: F,
+) -> Result<impl Fn(ImportProgress) + Send + 'static, String>
+where
+    F: Fn(ImportProgress) + Send + 'static,
+{
+    let log = path.map(ProgressLog::open).transpose()?.map(Mutex::new);
+
+    Ok(move |progress: ImportProgress| {
+        if let Some(ref log) = log {
+            log.lock().unwrap_or_else(|e| e.into_inner()).append(&progress);
+        }
+        progress_callback(progress);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::temp_path;
+    use std::sync::Arc;
+
+    fn logged_lines(path: &str) -> Vec<ImportProgress> {
+        std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn snapshot(processed: u32, phase: ImportPhase) -> ImportProgress {
+        ImportProgress {
+            processed,
+            phase,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn each_snapshot_is_logged_and_forwarded() {
+        let path = temp_path("ndjson");
+        let forwarded = Arc::new(Mutex::new(0));
+        let count = Arc::clone(&forwarded);
+        let callback =
+            with_progress_log(Some(&path), move |_| *count.lock().unwrap() += 1).unwrap();
+
+        for processed in 1..FLUSH_EVERY_LINES {
+            callback(snapshot(processed, ImportPhase::Inserting));
+        }
+        assert!(logged_lines(&path).is_empty());
+
+        callback(snapshot(FLUSH_EVERY_LINES, ImportPhase::Inserting));
+        assert_eq!(logged_lines(&path).len(), FLUSH_EVERY_LINES as usize);
+
+        callback(snapshot(21, ImportPhase::Inserting));
+        callback(snapshot(22, ImportPhase::Done));
+        let lines = logged_lines(&path);
+        assert_eq!(lines.len(), 22);
+        assert_eq!(lines.last().unwrap().processed, 22);
+        assert_eq!(*forwarded.lock().unwrap(), 22);
+    }
+
+    #[test]
+    fn later_runs_append_to_the_log() {
+        let path = temp_path("ndjson");
+
+        for _ in 0..2 {
+            let callback = with_progress_log(Some(&path), |_| {}).unwrap();
+            callback(snapshot(1, ImportPhase::Inserting));
+            callback(snapshot(2, ImportPhase::Done));
+        }
+
+        assert_eq!(logged_lines(&path).len(), 4);
+        assert!(with_progress_log(Some("/introuvable/journal.ndjson"), |_| {}).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::commands::ImportProposal;
+use crate::db::create::build_create_table_ddl;
+use crate::json::cache::read_json_file_cached;
+use crate::json::extract::{extract_root_objects, flatten_mapping, DEFAULT_FLATTEN_SEPARATOR};
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Nombre d'objets utilisés pour déduire les colonnes et leurs types
+const PROPOSAL_SAMPLE_SIZE: usize = 200;
+
+/// Profondeur maximale de recherche de la racine
+const MAX_ROOT_SEARCH_DEPTH: usize = 5;
+
+/// Séparateur de repli quand l'aplatissement avec `_` produit des collisions
+const FALLBACK_FLATTEN_SEPARATOR: &str = "__";
+
+/// Propose une importation complète pour un fichier JSON, sans rien exécuter: racine choisie
+/// (le tableau contenant le plus d'objets), mapping aplati des champs des objets échantillonnés
+/// et CREATE TABLE avec les types déduits. L'utilisateur peut ensuite l'ajuster avant de créer
+/// la table et de lancer l'importation.
+pub fn propose_import(json_path: &str) -> Result<ImportProposal, String> {
+    let json_data = read_json_file_cached(json_path)?;
+
+    let (json_root, object_count) = find_best_root(&json_data);
+    let sample: Vec<JsonValue> = extract_root_objects(&json_data, &json_root)?
+        .into_iter()
+        .filter(|obj| obj.is_object())
+        .take(PROPOSAL_SAMPLE_SIZE)
+        .collect();
+
+    if sample.is_empty() {
+        return Err(format!("Aucun objet trouvé dans {}", json_path));
+    }
+
+    let no_explicit_mapping = HashMap::new();
+    let flattened = flatten_mapping(&sample, DEFAULT_FLATTEN_SEPARATOR, &no_explicit_mapping)
+        .or_else(|_| flatten_mapping(&sample, FALLBACK_FLATTEN_SEPARATOR, &no_explicit_mapping))?;
+
+    // Noms de colonnes rendus valides (et uniques) pour `validate_identifier`
+    let mut paths: Vec<(String, String)> = flattened.into_iter().collect();
+    paths.sort();
+    let mut used_columns: HashSet<String> = HashSet::new();
+    let mapping: HashMap<String, String> = paths
+        .into_iter()
+        .map(|(path, column)| {
+            let base = sanitize_identifier(&column);
+            let mut column = base.clone();
+            let mut suffix = 2;
+            while !used_columns.insert(column.clone()) {
+                column = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            (path, column)
+        })
+        .collect();
+
+    let table_name = Path::new(json_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(sanitize_identifier)
+        .unwrap_or_else(|| "import".to_string());
+
+    let ddl = build_create_table_ddl(&table_name, &sample, &mapping, &HashMap::new())?;
+
+    Ok(ImportProposal {
+        json_root,
+        object_count: object_count as u32,
+        sample_size: sample.len() as u32,
+        table_name,
+        ddl,
+        mapping,
+    })
+}
+
+/// Racine la plus probable: le tableau contenant le plus d'objets (à égalité, le moins
+/// profond), ou la racine du document s'il n'en contient aucun
+fn find_best_root(json_data: &JsonValue) -> (String, usize) {
+    let mut best = (String::new(), count_objects(json_data));
+    let mut level: Vec<(String, &JsonValue)> = vec![(String::new(), json_data)];
+
+    for _ in 0..MAX_ROOT_SEARCH_DEPTH {
+        let mut next_level = Vec::new();
+
+        for (path, value) in level {
+            let JsonValue::Object(map) = value else { continue };
+            for (key, child) in map {
+                // Une clé contenant un point ne peut pas être désignée par un chemin
+                if key.contains('.') {
+                    continue;
+                }
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+
+                let count = count_objects(child);
+                if child.is_array() && count > best.1 {
+                    best = (format!("{}[]", child_path), count);
+                }
+                next_level.push((child_path, child));
+            }
+        }
+
+        level = next_level;
+    }
+
+    best
+}
+
+/// Nombre d'objets importables depuis une valeur (éléments objets d'un tableau, ou l'objet)
+fn count_objects(value: &JsonValue) -> usize {
+    match value {
+        JsonValue::Array(items) => items.iter().filter(|item| item.is_object()).count(),
+        JsonValue::Object(_) => 1,
+        _ => 0,
+    }
+}
+
+/// Nom utilisable comme identifiant: caractères non autorisés remplacés par `_`,
+/// préfixe `_` s'il commence par un chiffre
+fn sanitize_identifier(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+
+    match sanitized.chars().next() {
+        Some(first) if first.is_alphabetic() || first == '_' => sanitized,
+        _ => format!("_{}", sanitized),
+    }
+}
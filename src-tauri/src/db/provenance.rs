@@ -0,0 +1,95 @@
+use crate::commands::ColumnProvenance;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+/// Nom de la table de provenance des colonnes (chemin JSON → colonne)
+pub const PROVENANCE_TABLE: &str = "_json_column_provenance";
+
+/// Crée la table de provenance si elle n'existe pas encore
+fn ensure_provenance_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {} (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            recorded_at TEXT NOT NULL,
+            source_file TEXT NOT NULL,
+            table_name TEXT NOT NULL,
+            json_path TEXT NOT NULL,
+            column_name TEXT NOT NULL
+        )",
+        PROVENANCE_TABLE
+    ))
+    .map_err(|e| format!("Erreur lors de la création de la table de provenance: {}", e))
+}
+
+/// Enregistre le mapping utilisé par une importation (une ligne par chemin JSON)
+pub fn record_provenance(
+    conn: &Connection,
+    source_file: &str,
+    table_name: &str,
+    mapping: &HashMap<String, String>,
+) -> Result<(), String> {
+    ensure_provenance_table(conn)?;
+
+    let recorded_at = Utc::now().to_rfc3339();
+    let mut paths: Vec<(&String, &String)> = mapping.iter().collect();
+    paths.sort();
+
+    for (json_path, column_name) in paths {
+        conn.execute(
+            &format!(
+                "INSERT INTO {} (recorded_at, source_file, table_name, json_path, column_name)
+                 VALUES (?, ?, ?, ?, ?)",
+                PROVENANCE_TABLE
+            ),
+            params![recorded_at, source_file, table_name, json_path, column_name],
+        )
+        .map_err(|e| format!("Erreur lors de l'enregistrement de la provenance: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Récupère la provenance des colonnes d'une table (la plus récente en premier)
+pub fn get_provenance(db_path: &str, table_name: &str) -> Result<Vec<ColumnProvenance>, String> {
+    let conn = match Connection::open(db_path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
+    };
+
+    let exists: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name = ?",
+            [PROVENANCE_TABLE],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Erreur lors de la lecture de la provenance: {}", e))?;
+
+    if exists == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, recorded_at, source_file, table_name, json_path, column_name
+             FROM {} WHERE table_name = ? ORDER BY id DESC",
+            PROVENANCE_TABLE
+        ))
+        .map_err(|e| format!("Erreur lors de la préparation de la requête: {}", e))?;
+
+    let entries = stmt
+        .query_map([table_name], |row| {
+            Ok(ColumnProvenance {
+                id: row.get(0)?,
+                recorded_at: row.get(1)?,
+                source_file: row.get(2)?,
+                table_name: row.get(3)?,
+                json_path: row.get(4)?,
+                column_name: row.get(5)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Erreur lors de la lecture de la provenance: {}", e))?;
+
+    Ok(entries)
+}
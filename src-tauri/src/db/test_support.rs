@@ -0,0 +1,41 @@
+//! Fichiers temporaires partagés par les tests (bases de données et documents JSON)
+
+use rusqlite::types::FromSql;
+use rusqlite::Connection;
+use uuid::Uuid;
+
+/// Chemin unique dans le répertoire temporaire, avec l'extension donnée
+pub fn temp_path(extension: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("json_to_sqlite_{}.{}", Uuid::new_v4(), extension))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Fichier JSON temporaire contenant `content`
+pub fn temp_json(content: &str) -> String {
+    let path = temp_path("json");
+    std::fs::write(&path, content).expect("écriture du fichier JSON de test");
+    path
+}
+
+/// Base SQLite temporaire créée avec le schéma `schema`
+pub fn temp_db(schema: &str) -> String {
+    let path = temp_path("db");
+    Connection::open(&path)
+        .and_then(|conn| conn.execute_batch(schema))
+        .expect("création de la base de test");
+    path
+}
+
+/// Première colonne de chaque ligne de `query`
+pub fn query_column<T: FromSql>(db_path: &str, query: &str) -> Vec<T> {
+    let conn = Connection::open(db_path).expect("ouverture de la base de test");
+    let mut stmt = conn.prepare(query).expect("requête de test");
+    let rows = stmt
+        .query_map([], |row| row.get::<_, T>(0))
+        .expect("lecture des lignes de test")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("lignes de test");
+    rows
+}
@@ -1,11 +1,57 @@
-use crate::commands::ImportProgress;
+use crate::commands::{BeginMode, ImportPhase, ImportProgress, ImportSummary, RowError};
+use crate::db::convert::{json_to_sql_value, ConvertOpts};
+use crate::db::insert::get_table_columns;
+use crate::db::{
+    check_database_not_locked, quote_identifier, transaction_behavior, validate_identifier,
+};
+use crate::json::format_parse_error;
 use crate::json::extract::{apply_mapping, extract_root_objects, get_value_by_path};
+use rusqlite::types::Value as SqlValue;
 use rusqlite::{params_from_iter, Connection, Result as SqliteResult};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
+use std::time::Instant;
+
+/// Noms réservés désignant le rowid SQLite comme colonne clé
+const ROWID_KEY_NAMES: [&str; 2] = ["rowid", "_rowid_"];
+
+/// Indique si la colonne clé désigne le rowid de la table
+fn is_rowid_key(key_column: &str) -> bool {
+    ROWID_KEY_NAMES.iter().any(|name| name.eq_ignore_ascii_case(key_column))
+}
+
+/// Colonne clé dans le SQL généré: le rowid reste un mot-clé (cité, il serait pris pour une
+/// chaîne littérale dans une table WITHOUT ROWID), les autres noms sont cités
+fn key_column_sql(key_column: &str) -> String {
+    if is_rowid_key(key_column) {
+        key_column.to_string()
+    } else {
+        quote_identifier(key_column)
+    }
+}
+
+/// Valeur de clé liée aux requêtes: entier pour le rowid, texte sinon
+fn key_parameter(key_value: &JsonValue, rowid_key: bool) -> Result<SqlValue, String> {
+    if !rowid_key {
+        return Ok(SqlValue::Text(match key_value {
+            JsonValue::String(s) => s.clone(),
+            _ => key_value.to_string(),
+        }));
+    }
+
+    let rowid = match key_value {
+        JsonValue::Number(n) => n.as_i64(),
+        JsonValue::String(s) => s.trim().parse::<i64>().ok(),
+        _ => None,
+    };
+
+    rowid
+        .map(SqlValue::Integer)
+        .ok_or_else(|| format!("Valeur de rowid non entière: {}", key_value))
+}
 
 /// Fonction principale pour mettre à jour des données SQLite à partir de JSON
 pub fn update_sqlite_from_json_data<F>(
@@ -16,18 +62,35 @@ pub fn update_sqlite_from_json_data<F>(
     key_column: &str,
     update_columns: &[String],
     mapping: &HashMap<String, String>,
+    skip_missing_source: bool,
+    null_sets_column: bool,
+    minimal_update: bool,
+    expected_version: Option<(&str, &str)>,
+    begin_mode: BeginMode,
     dry_run: bool,
     progress_callback: F,
-) -> Result<ImportProgress, String>
+) -> Result<ImportSummary, String>
 where
     F: Fn(ImportProgress) + Send + 'static,
 {
+    let start_time = Instant::now();
+
+    // Validation des noms de table et de colonnes avant toute construction de SQL
+    validate_identifier(table_name)?;
+    validate_identifier(key_column)?;
+    for column in update_columns.iter().chain(mapping.values()) {
+        validate_identifier(column)?;
+    }
+    if let Some((version_column, _)) = expected_version {
+        validate_identifier(version_column)?;
+    }
+
     // Lecture du fichier JSON
     let json_content = std::fs::read_to_string(json_path)
         .map_err(|e| format!("Erreur de lecture du fichier JSON: {}", e))?;
 
     let json_data: JsonValue = serde_json::from_str(&json_content)
-        .map_err(|e| format!("Erreur de parsing JSON: {}", e))?;
+        .map_err(|e| format_parse_error(&e))?;
 
     // Extraction des objets à la racine spécifiée
     let root_objects = extract_root_objects(&json_data, json_root)?;
@@ -40,9 +103,17 @@ where
         processed: 0,
         succeeded: 0,
         failed: 0,
+        no_change: 0,
+        conflicts: 0,
+        phase: ImportPhase::Preparing,
         status: "Préparation...".to_string(),
     };
 
+    let mut summary = ImportSummary {
+        total: total_objects as u32,
+        ..Default::default()
+    };
+
     // Appel du callback pour notifier le début du processus
     progress_callback(current_progress.clone());
 
@@ -64,9 +135,11 @@ where
 
     // En mode dry run, on ne fait rien de plus
     if dry_run {
+        current_progress.phase = ImportPhase::Done;
         current_progress.status = "Simulation terminée (dry run)".to_string();
         progress_callback(current_progress.clone());
-        return Ok(current_progress);
+        summary.duration_ms = start_time.elapsed().as_millis() as u64;
+        return Ok(summary);
     }
 
     // Connexion à la base de données
@@ -75,29 +148,10 @@ where
         Err(e) => return Err(format!("Erreur de connexion à la base de données: {}", e)),
     };
 
-    // Vérification de l'existence de la table et des colonnes
-    let table_info = match conn.prepare(&format!("PRAGMA table_info({})", table_name)) {
-        Ok(mut stmt) => {
-            let rows = stmt
-                .query_map([], |row| {
-                    Ok((
-                        row.get::<_, String>(1)?, // name
-                        row.get::<_, String>(2)?, // type
-                    ))
-                })
-                .map_err(|e| e.to_string())?;
-
-            let result = rows.collect::<Result<Vec<_>, _>>();
-            match result {
-                Ok(info) => info,
-                Err(e) => {
-                    return Err(format!(
-                        "Erreur lors de la lecture des informations de table: {}",
-                        e
-                    ))
-                }
-            }
-        }
+    // Vérification de l'existence de la table et des colonnes (le type déclaré des colonnes
+    // guide aussi la conversion des valeurs, comme à l'insertion)
+    let table_info = match get_table_columns(&conn, table_name) {
+        Ok(info) => info,
         Err(e) => return Err(format!("Erreur lors de la vérification de la table: {}", e)),
     };
 
@@ -108,9 +162,22 @@ where
         ));
     }
 
-    // Vérifier si la colonne clé existe
-    let key_exists = table_info.iter().any(|(name, _)| name == key_column);
-    if !key_exists {
+    // Vérifier si la colonne clé existe (le rowid n'apparaît pas dans table_info, mais
+    // n'existe pas dans une table WITHOUT ROWID)
+    let rowid_key = is_rowid_key(key_column);
+    if rowid_key {
+        let probe = format!(
+            "SELECT {} FROM {} LIMIT 0",
+            key_column_sql(key_column),
+            quote_identifier(table_name)
+        );
+        if let Err(e) = conn.prepare(&probe) {
+            return Err(format!(
+                "La table {} n'a pas de rowid (table WITHOUT ROWID ?): {}",
+                table_name, e
+            ));
+        }
+    } else if !table_info.iter().any(|column| column.name == key_column) {
         return Err(format!(
             "La colonne clé {} n'existe pas dans la table {}",
             key_column, table_name
@@ -118,7 +185,7 @@ where
     }
 
     // Vérifier si les colonnes à mettre à jour existent
-    let table_columns: Vec<String> = table_info.iter().map(|(name, _)| name.clone()).collect();
+    let table_columns: Vec<String> = table_info.iter().map(|column| column.name.clone()).collect();
 
     let missing_columns: Vec<&String> = update_columns
         .iter()
@@ -136,8 +203,37 @@ where
         ));
     }
 
+    if let Some((version_column, _)) = expected_version {
+        if !table_columns.iter().any(|col| col == version_column) {
+            return Err(format!(
+                "La colonne de version {} n'existe pas dans la table {}",
+                version_column, table_name
+            ));
+        }
+    }
+
+    // Signaler les colonnes à mettre à jour qui n'ont aucun chemin source dans le mapping
+    let unmapped_columns: Vec<&String> = update_columns
+        .iter()
+        .filter(|col| !mapping.values().any(|mapped| mapped == *col))
+        .collect();
+
+    if !unmapped_columns.is_empty() {
+        eprintln!(
+            "Colonnes à mettre à jour sans chemin source dans le mapping: {}",
+            unmapped_columns
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    // Vérification qu'aucune autre connexion ne verrouille la base
+    check_database_not_locked(&conn, db_path)?;
+
     // Démarrage de la transaction
-    let tx = match conn.transaction() {
+    let tx = match conn.transaction_with_behavior(transaction_behavior(begin_mode)) {
         Ok(t) => t,
         Err(e) => {
             return Err(format!(
@@ -148,12 +244,18 @@ where
     };
 
     // Traitement des objets
+    current_progress.phase = ImportPhase::Inserting;
     current_progress.status = "Mise à jour des données...".to_string();
     progress_callback(current_progress.clone());
 
-    let mut success_count = 0;
-    let mut error_count = 0;
-    let mut not_found_count = 0;
+    let mut success_count: u32 = 0;
+    let mut error_count: u32 = 0;
+    let mut not_found_count: u32 = 0;
+    let mut no_change_count: u32 = 0;
+    let mut conflict_count: u32 = 0;
+
+    let convert_opts = ConvertOpts::default();
+    let column_metadata = |name: &str| table_info.iter().find(|column| column.name == name);
 
     for (index, obj) in root_objects.iter().enumerate() {
         current_progress.processed += 1;
@@ -168,6 +270,10 @@ where
                 error_count += 1;
                 current_progress.failed += 1;
                 eprintln!("Erreur: Valeur de clé manquante pour l'objet {}", index);
+                summary.errors.push(RowError::new(
+                    index,
+                    format!("Valeur de clé manquante pour la colonne {}", key_column),
+                ));
                 continue;
             }
         };
@@ -175,16 +281,23 @@ where
         // Vérifier si la ligne existe
         let check_query = format!(
             "SELECT COUNT(*) FROM {} WHERE {} = ?",
-            table_name, key_column
+            quote_identifier(table_name),
+            key_column_sql(key_column)
         );
 
-        // Pour une valeur JSON String
-        let key_value_string = match &key_value {
-            JsonValue::String(s) => s.clone(),
-            _ => key_value.to_string(),
+        // Valeur liée: texte pour une colonne ordinaire, entier pour le rowid
+        let key_param = match key_parameter(&key_value, rowid_key) {
+            Ok(value) => value,
+            Err(e) => {
+                error_count += 1;
+                current_progress.failed += 1;
+                eprintln!("Erreur pour l'objet {}: {}", index, e);
+                summary.errors.push(RowError::new(index, e));
+                continue;
+            }
         };
 
-        let count: i64 = match tx.query_row(&check_query, [&key_value_string], |row| row.get(0)) {
+        let count: i64 = match tx.query_row(&check_query, [&key_param], |row| row.get(0)) {
             Ok(c) => c,
             Err(e) => {
                 error_count += 1;
@@ -193,6 +306,7 @@ where
                     "Erreur lors de la vérification de l'existence de la ligne: {}",
                     e
                 );
+                summary.errors.push(RowError::from_sqlite(index, &e));
                 continue;
             }
         };
@@ -205,20 +319,131 @@ where
         }
 
         // Construction de la requête UPDATE
-        let mut set_clauses = Vec::new();
+        let mut set_columns = Vec::new();
         let mut update_values = Vec::new();
 
         for column in update_columns {
-            if let Some(Some(value)) = mapped_data.get(column) {
-                set_clauses.push(format!("{} = ?", column));
-                update_values.push(value.clone());
+            match mapped_data.get(column) {
+                // Chemin présent avec une valeur null: mise à NULL seulement si demandé
+                Some(Some(JsonValue::Null)) if !null_sets_column => {}
+                Some(Some(value)) => {
+                    set_columns.push(column.as_str());
+                    update_values.push(value);
+                }
+                // Chemin absent de l'objet source: la colonne est ignorée
+                _ => {}
+            }
+        }
+
+        let mut update_values = match set_columns
+            .iter()
+            .zip(update_values)
+            .map(|(column, value)| {
+                json_to_sql_value(value, column_metadata(column), &convert_opts)
+            })
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(values) => values,
+            Err(e) => {
+                error_count += 1;
+                current_progress.failed += 1;
+                eprintln!("Erreur de conversion pour l'objet {}: {}", index, e);
+                summary.errors.push(RowError::new(index, e.to_string()));
+                continue;
+            }
+        };
+
+        // Version attendue: la ligne ne doit pas avoir changé depuis la lecture de la source
+        let expected = match expected_version {
+            Some((version_column, version_path)) => match get_value_by_path(obj, version_path) {
+                Some(value) => {
+                    json_to_sql_value(&value, column_metadata(version_column), &convert_opts)
+                        .map(|value| Some((version_column, value)))
+                        .map_err(|e| e.to_string())
+                }
+                None => Err(format!("Version attendue absente au chemin {}", version_path)),
+            },
+            None => Ok(None),
+        };
+        let expected = match expected {
+            Ok(expected) => expected,
+            Err(e) => {
+                error_count += 1;
+                current_progress.failed += 1;
+                summary.errors.push(RowError::new(index, e));
+                continue;
+            }
+        };
+
+        // Valeurs actuelles de la ligne: SQLite compte comme modifiée toute ligne trouvée, même
+        // si ses valeurs ne changent pas; une ligne inchangée est comptée `no_change`
+        // (version actuelle lue avec les valeurs, après celles-ci)
+        let mut read_columns = set_columns.clone();
+        if let Some((version_column, _)) = expected {
+            read_columns.push(version_column);
+        }
+        let current_values = if set_columns.is_empty() {
+            Vec::new()
+        } else {
+            match read_current_values(&tx, table_name, key_column, &key_param, &read_columns) {
+                Ok(values) => values,
+                Err(e) => {
+                    error_count += 1;
+                    current_progress.failed += 1;
+                    eprintln!("Erreur lors de la lecture des valeurs actuelles: {}", e);
+                    summary.errors.push(RowError::from_sqlite(index, &e));
+                    continue;
+                }
             }
+        };
+        let unchanged = !set_columns.is_empty()
+            && update_values
+                .iter()
+                .zip(current_values.iter())
+                .all(|(new_value, current)| sql_values_equal(new_value, current));
+
+        // Mise à jour minimale: on ne garde que les colonnes dont la valeur change
+        if minimal_update && !set_columns.is_empty() {
+            let (changed_columns, changed_values): (Vec<_>, Vec<_>) = set_columns
+                .into_iter()
+                .zip(update_values)
+                .zip(current_values.iter())
+                .filter(|((_, new_value), current)| !sql_values_equal(new_value, current))
+                .map(|(pair, _)| pair)
+                .unzip();
+
+            if changed_columns.is_empty() {
+                // Rien à écrire, mais une version différente reste un conflit: la ligne a été
+                // modifiée depuis la lecture de la source
+                let stale = match (&expected, current_values.last()) {
+                    (Some((_, version)), Some(current)) => !sql_values_equal(version, current),
+                    _ => false,
+                };
+                if stale {
+                    conflict_count += 1;
+                    current_progress.conflicts += 1;
+                    eprintln!("Conflit de version: {} = {:?}", key_column, key_value);
+                } else {
+                    no_change_count += 1;
+                    current_progress.no_change += 1;
+                }
+                continue;
+            }
+
+            set_columns = changed_columns;
+            update_values = changed_values;
         }
 
         // Si on n'a aucune colonne à mettre à jour, on passe à l'item suivant
-        if set_clauses.is_empty() {
-            not_found_count += 1;
-            current_progress.failed += 1;
+        if set_columns.is_empty() {
+            if skip_missing_source {
+                // Mode tolérant: l'absence de valeurs source n'est pas une erreur
+                no_change_count += 1;
+                current_progress.no_change += 1;
+            } else {
+                not_found_count += 1;
+                current_progress.failed += 1;
+            }
             eprintln!(
                 "Aucune colonne à mettre à jour pour {} = {:?}",
                 key_column, key_value
@@ -227,54 +452,67 @@ where
         }
 
         // Ajout de la valeur de clé pour la clause WHERE
-        update_values.push(key_value.clone());
+        let where_value = if rowid_key {
+            Ok(key_param)
+        } else {
+            json_to_sql_value(&key_value, column_metadata(key_column), &convert_opts)
+        };
+        match where_value {
+            Ok(value) => update_values.push(value),
+            Err(e) => {
+                error_count += 1;
+                current_progress.failed += 1;
+                summary.errors.push(RowError::new(index, e.to_string()));
+                continue;
+            }
+        }
 
-        let update_query = format!(
-            "UPDATE {} SET {} WHERE {} = ?",
-            table_name,
-            set_clauses.join(", "),
-            key_column
-        );
+        let mut version_clause = String::new();
+        if let Some((version_column, version)) = expected {
+            update_values.push(version);
+            version_clause = format!(" AND {} IS ?", quote_identifier(version_column));
+        }
 
-        // Conversion des valeurs JsonValue en rusqlite::types::Value
-        let params: Vec<_> = update_values
+        let set_clauses: Vec<String> = set_columns
             .iter()
-            .map(|val| match val {
-                JsonValue::Null => rusqlite::types::Value::Null,
-                JsonValue::Bool(b) => rusqlite::types::Value::Integer(*b as i64),
-                JsonValue::Number(n) => {
-                    if n.is_i64() {
-                        rusqlite::types::Value::Integer(n.as_i64().unwrap())
-                    } else {
-                        rusqlite::types::Value::Real(n.as_f64().unwrap())
-                    }
-                }
-                JsonValue::String(s) => rusqlite::types::Value::Text(s.clone()),
-                JsonValue::Array(_) | JsonValue::Object(_) => {
-                    rusqlite::types::Value::Text(val.to_string())
-                }
-            })
+            .map(|column| format!("{} = ?", quote_identifier(column)))
             .collect();
 
+        let update_query = format!(
+            "UPDATE {} SET {} WHERE {} = ?{}",
+            quote_identifier(table_name),
+            set_clauses.join(", "),
+            key_column_sql(key_column),
+            version_clause
+        );
+
         // Exécution de la requête UPDATE
-        match tx.execute(&update_query, params_from_iter(params.iter())) {
+        match tx.execute(&update_query, params_from_iter(update_values.iter())) {
             Ok(updated) => {
-                if updated > 0 {
+                if updated > 0 && unchanged {
+                    // Ligne trouvée, valeurs identiques (ex: ré-exécution idempotente)
+                    no_change_count += 1;
+                    current_progress.no_change += 1;
+                } else if updated > 0 {
                     success_count += 1;
                     current_progress.succeeded += 1;
+                } else if expected_version.is_some() {
+                    // La ligne existe mais sa version a changé: modification concurrente
+                    conflict_count += 1;
+                    current_progress.conflicts += 1;
+                    eprintln!("Conflit de version: {} = {:?}", key_column, key_value);
                 } else {
-                    error_count += 1;
-                    current_progress.failed += 1;
-                    eprintln!(
-                        "Aucune ligne mise à jour pour {} = {:?}",
-                        key_column, key_value
-                    );
+                    // L'existence de la ligne a été vérifiée: aucune modification n'est
+                    // un résultat normal (ex: ré-exécution idempotente), pas une erreur
+                    no_change_count += 1;
+                    current_progress.no_change += 1;
                 }
             }
             Err(e) => {
                 error_count += 1;
                 current_progress.failed += 1;
                 eprintln!("Erreur lors de la mise à jour: {}", e);
+                summary.errors.push(RowError::from_sqlite(index, &e));
             }
         }
 
@@ -291,17 +529,319 @@ where
     }
 
     // Commit de la transaction
+    current_progress.phase = ImportPhase::Committing;
+    current_progress.status = "Validation de la transaction...".to_string();
+    progress_callback(current_progress.clone());
+
     match tx.commit() {
         Ok(_) => {}
         Err(e) => return Err(format!("Erreur lors du commit de la transaction: {}", e)),
     }
 
     // Finalisation
+    current_progress.phase = ImportPhase::Done;
     current_progress.status = format!(
-        "Mise à jour terminée. Succès: {}, Échecs: {}, Non trouvés: {}",
-        success_count, error_count, not_found_count
+        "Mise à jour terminée. Succès: {}, Échecs: {}, Non trouvés: {}, Inchangés: {}, Conflits: {}",
+        success_count, error_count, not_found_count, no_change_count, conflict_count
     );
     progress_callback(current_progress.clone());
 
-    Ok(current_progress)
+    summary.updated = success_count;
+    summary.failed = error_count;
+    summary.not_found = not_found_count;
+    summary.skipped = no_change_count;
+    summary.conflicts = expected_version.map(|_| conflict_count);
+    summary.duration_ms = start_time.elapsed().as_millis() as u64;
+
+    Ok(summary)
+}
+
+/// Lit les valeurs actuelles des colonnes pour la ligne identifiée par la clé
+fn read_current_values(
+    conn: &Connection,
+    table_name: &str,
+    key_column: &str,
+    key_value: &SqlValue,
+    columns: &[&str],
+) -> SqliteResult<Vec<SqlValue>> {
+    let query = format!(
+        "SELECT {} FROM {} WHERE {} = ? LIMIT 1",
+        columns
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", "),
+        quote_identifier(table_name),
+        key_column_sql(key_column)
+    );
+
+    conn.query_row(&query, [key_value], |row| {
+        (0..columns.len()).map(|i| row.get::<_, SqlValue>(i)).collect()
+    })
+}
+
+/// Compare une valeur à écrire avec la valeur en base; les nombres sont comparés
+/// numériquement (une colonne INTEGER peut stocker un réel entier et inversement)
+fn sql_values_equal(new_value: &SqlValue, current: &SqlValue) -> bool {
+    match (new_value, current) {
+        (SqlValue::Integer(a), SqlValue::Real(b)) | (SqlValue::Real(b), SqlValue::Integer(a)) => {
+            *a as f64 == *b
+        }
+        _ => new_value == current,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{query_column, temp_db, temp_json};
+    use serde_json::json;
+
+    /// Mise à jour de `items` par `id`, chaque colonne mise à jour ayant un chemin de même nom
+    fn update(
+        db_path: &str,
+        json: &str,
+        update_columns: &[&str],
+        minimal_update: bool,
+        expected_version: Option<(&str, &str)>,
+    ) -> ImportSummary {
+        let update_columns: Vec<String> = update_columns.iter().map(|c| c.to_string()).collect();
+        let mapping: HashMap<String, String> = update_columns
+            .iter()
+            .chain(std::iter::once(&"id".to_string()))
+            .map(|column| (column.clone(), column.clone()))
+            .collect();
+
+        update_sqlite_from_json_data(
+            &temp_json(json),
+            db_path,
+            "",
+            "items",
+            "id",
+            &update_columns,
+            &mapping,
+            false,
+            true,
+            minimal_update,
+            expected_version,
+            BeginMode::default(),
+            false,
+            |_| {},
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn values_follow_declared_column_type() {
+        let db_path = temp_db(
+            "CREATE TABLE items (id INTEGER, label TEXT, amount REAL);
+             INSERT INTO items VALUES (1, 'x', 0);",
+        );
+
+        let json = r#"[{"id": 1, "label": true, "amount": 2}]"#;
+        let summary = update(&db_path, json, &["label", "amount"], false, None);
+
+        assert_eq!(summary.updated, 1);
+        let labels: Vec<String> = query_column(&db_path, "SELECT label FROM items");
+        assert_eq!(labels, vec!["true"]);
+        let types: Vec<String> = query_column(&db_path, "SELECT typeof(label) FROM items");
+        assert_eq!(types, vec!["text"]);
+    }
+
+    /// Table `items` dont chaque UPDATE est compté dans `update_count`
+    fn counted_items(rows: &str) -> String {
+        temp_db(&format!(
+            "CREATE TABLE items (id INTEGER, label TEXT, amount REAL, version INTEGER);
+             CREATE TABLE update_count (n INTEGER);
+             INSERT INTO update_count VALUES (0);
+             CREATE TRIGGER count_updates AFTER UPDATE ON items
+             BEGIN UPDATE update_count SET n = n + 1; END;
+             INSERT INTO items VALUES {};",
+            rows
+        ))
+    }
+
+    #[test]
+    fn minimal_update_issues_no_update_for_unchanged_rows() {
+        let db_path = counted_items("(1, '5', 2.0, 1), (2, 'b', 3.0, 1)");
+
+        // Nombre source vers une colonne TEXT: comparé sous sa forme stockée ('5')
+        let json = r#"[{"id": 1, "label": 5, "amount": 2}, {"id": 2, "label": "b", "amount": 4}]"#;
+        let summary = update(&db_path, json, &["label", "amount"], true, None);
+
+        assert_eq!((summary.updated, summary.skipped), (1, 1));
+        let updates: Vec<i64> = query_column(&db_path, "SELECT n FROM update_count");
+        assert_eq!(updates, vec![1]);
+    }
+
+    #[test]
+    fn minimal_update_reports_stale_version_as_conflict() {
+        let db_path = counted_items("(1, 'a', 1.0, 2)");
+
+        let json = r#"[{"id": 1, "label": "a", "amount": 1, "version": 1}]"#;
+        let expected_version = Some(("version", "version"));
+        let summary = update(&db_path, json, &["label", "amount"], true, expected_version);
+
+        assert_eq!((summary.skipped, summary.conflicts), (0, Some(1)));
+        let updates: Vec<i64> = query_column(&db_path, "SELECT n FROM update_count");
+        assert_eq!(updates, vec![0]);
+    }
+
+    #[test]
+    fn rerun_of_same_update_reports_no_change() {
+        let db_path = counted_items("(1, 'a', 1.0, 1)");
+        let json = r#"[{"id": 1, "label": "b", "amount": 2}]"#;
+
+        let first = update(&db_path, json, &["label", "amount"], false, None);
+        assert_eq!((first.updated, first.skipped, first.failed), (1, 0, 0));
+
+        let second = update(&db_path, json, &["label", "amount"], false, None);
+        assert_eq!((second.updated, second.skipped, second.failed), (0, 1, 0));
+    }
+
+    /// Mise à jour tolérante (`skip_missing_source`): les chemins absents sont ignorés
+    fn update_skipping_missing(
+        db_path: &str,
+        json: &str,
+        update_columns: &[&str],
+    ) -> Result<ImportSummary, String> {
+        let update_columns: Vec<String> = update_columns.iter().map(|c| c.to_string()).collect();
+        let mapping: HashMap<String, String> = ["id", "label", "amount"]
+            .iter()
+            .map(|column| (column.to_string(), column.to_string()))
+            .collect();
+
+        update_sqlite_from_json_data(
+            &temp_json(json),
+            db_path,
+            "",
+            "items",
+            "id",
+            &update_columns,
+            &mapping,
+            true,
+            true,
+            false,
+            None,
+            BeginMode::default(),
+            false,
+            |_| {},
+        )
+    }
+
+    #[test]
+    fn absent_source_value_skips_only_that_column() {
+        let db_path = temp_db(
+            "CREATE TABLE items (id INTEGER, label TEXT, amount REAL);
+             INSERT INTO items VALUES (1, 'a', 1.5), (2, 'b', 2.5);",
+        );
+        let json = r#"[{"id": 1, "label": "x"}, {"id": 2}]"#;
+
+        let summary = update_skipping_missing(&db_path, json, &["label", "amount"]).unwrap();
+
+        assert_eq!((summary.updated, summary.skipped, summary.failed), (1, 1, 0));
+        assert_eq!(summary.not_found, 0);
+        let rows: Vec<String> = query_column(
+            &db_path,
+            "SELECT label || ':' || amount FROM items ORDER BY id",
+        );
+        assert_eq!(rows, ["x:1.5", "b:2.5"]);
+    }
+
+    #[test]
+    fn update_column_missing_from_table_is_an_error() {
+        let db_path = temp_db("CREATE TABLE items (id INTEGER, label TEXT);");
+        let json = r#"[{"id": 1, "label": "x"}]"#;
+
+        let error = update_skipping_missing(&db_path, json, &["label", "amount"]).unwrap_err();
+        assert!(error.contains("amount"), "{}", error);
+    }
+
+    #[test]
+    fn unique_violation_reports_extended_code() {
+        let db_path = temp_db(
+            "CREATE TABLE items (id INTEGER, label TEXT UNIQUE);
+             INSERT INTO items VALUES (1, 'a'), (2, 'b');",
+        );
+
+        let json = r#"[{"id": 1, "label": "c"}, {"id": 2, "label": "c"}]"#;
+        let summary = update(&db_path, json, &["label"], false, None);
+
+        assert_eq!((summary.updated, summary.failed), (1, 1));
+        let error = &summary.errors[0];
+        let codes = (error.index, error.sqlite_code, error.extended_code);
+        assert_eq!(codes, (1, Some(19), Some(2067)));
+        assert!(error.message.contains("UNIQUE"), "{}", error.message);
+    }
+
+    #[test]
+    fn rows_are_matched_on_rowid() {
+        let db_path = temp_db(
+            "CREATE TABLE items (code TEXT, label TEXT);
+             INSERT INTO items VALUES ('a', 'x'), ('b', 'y'), ('c', 'z');",
+        );
+        let json = r#"[
+            {"rid": 1, "label": "un"},
+            {"rid": "3", "label": "trois"},
+            {"rid": 2.5, "label": "?"},
+            {"rid": 9, "label": "?"}
+        ]"#;
+        let mapping = HashMap::from([
+            ("rid".to_string(), "rowid".to_string()),
+            ("label".to_string(), "label".to_string()),
+        ]);
+
+        let summary = update_sqlite_from_json_data(
+            &temp_json(json),
+            &db_path,
+            "",
+            "items",
+            "rowid",
+            &["label".to_string()],
+            &mapping,
+            false,
+            true,
+            false,
+            None,
+            BeginMode::default(),
+            false,
+            |_| {},
+        )
+        .unwrap();
+
+        assert_eq!((summary.updated, summary.failed, summary.not_found), (2, 1, 1));
+        assert!(summary.errors[0].message.contains("rowid non entière"));
+        let labels: Vec<String> = query_column(&db_path, "SELECT label FROM items ORDER BY rowid");
+        assert_eq!(labels, ["un", "y", "trois"]);
+    }
+
+    #[test]
+    fn rowid_keys_are_bound_as_integers() {
+        assert_eq!(key_parameter(&json!(7), true), Ok(SqlValue::Integer(7)));
+        assert_eq!(key_parameter(&json!(" 7 "), true), Ok(SqlValue::Integer(7)));
+        assert!(key_parameter(&json!(true), true).is_err());
+        assert_eq!(key_parameter(&json!(7), false), Ok(SqlValue::Text("7".to_string())));
+    }
+
+    #[test]
+    fn stale_versions_are_conflicts_and_leave_the_row_untouched() {
+        let db_path = counted_items("(1, 'a', 1.0, 2), (2, 'b', 2.0, 5), (3, 'c', 3.0, 1)");
+
+        let json = r#"[
+            {"id": 1, "label": "x", "amount": 1, "version": 2},
+            {"id": 2, "label": "y", "amount": 2, "version": 4},
+            {"id": 3, "label": "z", "amount": 3}
+        ]"#;
+        let expected_version = Some(("version", "version"));
+        let summary = update(&db_path, json, &["label"], false, expected_version);
+
+        assert_eq!((summary.updated, summary.failed), (1, 1));
+        assert_eq!(summary.conflicts, Some(1));
+        assert_eq!(summary.errors[0].message, "Version attendue absente au chemin version");
+        let labels: Vec<String> = query_column(&db_path, "SELECT label FROM items ORDER BY id");
+        assert_eq!(labels, ["x", "b", "c"]);
+
+        let summary = update(&db_path, json, &["label"], false, None);
+        assert_eq!(summary.conflicts, None);
+    }
 }
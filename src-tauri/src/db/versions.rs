@@ -0,0 +1,64 @@
+use crate::commands::VersionedAppend;
+use crate::db::convert::{json_to_sql_value, ConvertOpts};
+use crate::db::insert::key_to_string;
+use crate::db::{quote_identifier, validate_identifier};
+use rusqlite::Connection;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+/// Numérotation des versions d'un import en ajout seul: chaque objet devient une nouvelle ligne
+/// dont la version suit la plus grande version existante de sa clé métier
+pub struct VersionTracker {
+    pub key_column: String,
+    query: String,
+    /// Clé métier → dernière version attribuée (lue en base au premier passage de la clé)
+    last_versions: HashMap<String, i64>,
+}
+
+impl VersionTracker {
+    pub fn new(table_name: &str, versioned: &VersionedAppend) -> Result<Self, String> {
+        validate_identifier(&versioned.key_column)?;
+        validate_identifier(&versioned.version_column)?;
+
+        let query = format!(
+            "SELECT COALESCE(MAX({}), 0) FROM {} WHERE {} = ?",
+            quote_identifier(&versioned.version_column),
+            quote_identifier(table_name),
+            quote_identifier(&versioned.key_column)
+        );
+
+        Ok(VersionTracker {
+            key_column: versioned.key_column.clone(),
+            query,
+            last_versions: HashMap::new(),
+        })
+    }
+
+    /// Version suivante pour la clé métier; consommée même si l'insertion échoue ensuite
+    pub fn next_version(
+        &mut self,
+        conn: &Connection,
+        key: Option<&JsonValue>,
+    ) -> Result<i64, String> {
+        let (key, key_string) = match key.and_then(|key| key_to_string(key).map(|s| (key, s))) {
+            Some(found) => found,
+            None => return Err(format!("Clé métier {} absente ou nulle", self.key_column)),
+        };
+
+        let last = match self.last_versions.get(&key_string) {
+            Some(last) => *last,
+            None => {
+                let param = json_to_sql_value(key, None, &ConvertOpts::default())
+                    .map_err(|e| format!("Colonne {}: {}", self.key_column, e))?;
+                conn.prepare_cached(&self.query)
+                    .and_then(|mut stmt| stmt.query_row([param], |row| row.get::<_, i64>(0)))
+                    .map_err(|e| {
+                        format!("Erreur lors de la lecture de la version de {}: {}", key_string, e)
+                    })?
+            }
+        };
+
+        self.last_versions.insert(key_string, last + 1);
+        Ok(last + 1)
+    }
+}
@@ -0,0 +1,146 @@
+use serde_json::Value as JsonValue;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use super::read_json_file;
+use super::remote::is_url;
+
+/// Nombre de documents gardés en mémoire (les plus récemment utilisés)
+const CACHE_CAPACITY: usize = 2;
+
+/// Document parsé, valide tant que le fichier garde la même date de modification et taille
+struct CachedDocument {
+    path: String,
+    modified: SystemTime,
+    size: u64,
+    data: Arc<JsonValue>,
+}
+
+/// Documents parsés pour l'analyse et l'échantillonnage, du plus au moins récemment utilisé
+static DOCUMENT_CACHE: OnceLock<Mutex<Vec<CachedDocument>>> = OnceLock::new();
+
+fn document_cache() -> &'static Mutex<Vec<CachedDocument>> {
+    DOCUMENT_CACHE.get_or_init(|| Mutex::new(Vec::with_capacity(CACHE_CAPACITY)))
+}
+
+/// Lit un fichier JSON en réutilisant le document déjà parsé si le fichier n'a pas changé
+/// depuis (même date de modification et même taille). Les sources distantes ne sont pas
+/// mises en cache.
+pub fn read_json_file_cached(file_path: &str) -> Result<Arc<JsonValue>, String> {
+    if is_url(file_path) {
+        return read_json_file(file_path).map(Arc::new);
+    }
+
+    let metadata = std::fs::metadata(file_path)
+        .map_err(|e| format!("Erreur lors de l'ouverture du fichier {}: {}", file_path, e))?;
+    let modified = metadata
+        .modified()
+        .map_err(|e| format!("Erreur lors de la lecture du fichier {}: {}", file_path, e))?;
+    let size = metadata.len();
+
+    {
+        let mut cache = document_cache().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(position) = cache.iter().position(|doc| doc.path == file_path) {
+            let doc = cache.remove(position);
+            if doc.modified == modified && doc.size == size {
+                let data = Arc::clone(&doc.data);
+                cache.insert(0, doc);
+                return Ok(data);
+            }
+        }
+    }
+
+    // Parsing hors du verrou: un gros fichier ne bloque pas les lectures des autres
+    let data = Arc::new(read_json_file(file_path)?);
+
+    let mut cache = document_cache().lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|doc| doc.path != file_path);
+    cache.insert(
+        0,
+        CachedDocument {
+            path: file_path.to_string(),
+            modified,
+            size,
+            data: Arc::clone(&data),
+        },
+    );
+    cache.truncate(CACHE_CAPACITY);
+
+    Ok(data)
+}
+
+/// Retire un document du cache (ex: analyse abandonnée), libéré dès que ses derniers
+/// utilisateurs le relâchent
+pub fn evict_json_cache(file_path: &str) {
+    document_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|doc| doc.path != file_path);
+}
+
+/// Libère les documents gardés en mémoire
+pub fn clear_json_cache() {
+    document_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::temp_json;
+    use crate::json::analyze_structure;
+    use std::fs::File;
+    use std::time::Duration;
+
+    /// Le cache est global: ses tests s'exécutent l'un après l'autre
+    static CACHE_TESTS: Mutex<()> = Mutex::new(());
+
+    /// Réécrit le fichier sans changer sa taille ni sa date de modification
+    fn overwrite_unnoticed(path: &str, content: &str) {
+        let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+        std::fs::write(path, content).unwrap();
+        File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn unchanged_file_is_not_read_again() {
+        let _serial = CACHE_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let path = temp_json(r#"{"a": 1}"#);
+
+        let first = read_json_file_cached(&path).unwrap();
+        assert_eq!(analyze_structure(&path).unwrap().len(), 1);
+
+        // Contenu invalide de même taille et même date: le document en cache est réutilisé
+        overwrite_unnoticed(&path, r#"{"a": ]}"#);
+        let second = read_json_file_cached(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(analyze_structure(&path).unwrap().len(), 1);
+
+        // Date de modification changée: le fichier est relu
+        let later = SystemTime::now() + Duration::from_secs(60);
+        File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        assert!(read_json_file_cached(&path).is_err());
+    }
+
+    #[test]
+    fn least_recently_used_document_is_evicted() {
+        let _serial = CACHE_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let paths: Vec<String> =
+            (0..=CACHE_CAPACITY).map(|i| temp_json(&i.to_string())).collect();
+
+        let oldest = read_json_file_cached(&paths[0]).unwrap();
+        let recent = read_json_file_cached(&paths[1]).unwrap();
+        for path in &paths[2..] {
+            read_json_file_cached(path).unwrap();
+        }
+
+        assert!(Arc::ptr_eq(&recent, &read_json_file_cached(&paths[1]).unwrap()));
+        assert!(!Arc::ptr_eq(&oldest, &read_json_file_cached(&paths[0]).unwrap()));
+
+        evict_json_cache(&paths[0]);
+        clear_json_cache();
+        assert!(document_cache().lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,47 @@
+use serde_json::{Map, Value as JsonValue};
+
+/// Lit un fichier CSV et convertit chaque ligne en objet JSON (en-têtes → clés).
+/// Sans ligne d'en-tête, les clés sont nommées `column_1`, `column_2`, etc.
+pub fn read_csv_objects(
+    csv_path: &str,
+    delimiter: u8,
+    has_headers: bool,
+) -> Result<Vec<JsonValue>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(has_headers)
+        .flexible(true)
+        .from_path(csv_path)
+        .map_err(|e| format!("Erreur lors de l'ouverture du fichier CSV {}: {}", csv_path, e))?;
+
+    let headers: Vec<String> = if has_headers {
+        reader
+            .headers()
+            .map_err(|e| format!("Erreur lors de la lecture des en-têtes CSV: {}", e))?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut objects = Vec::new();
+
+    for (index, record) in reader.records().enumerate() {
+        let record = record
+            .map_err(|e| format!("Erreur lors de la lecture de la ligne CSV {}: {}", index + 1, e))?;
+
+        let mut object = Map::new();
+        for (col_idx, field) in record.iter().enumerate() {
+            let key = match headers.get(col_idx) {
+                Some(header) => header.clone(),
+                None => format!("column_{}", col_idx + 1),
+            };
+            object.insert(key, JsonValue::String(field.to_string()));
+        }
+
+        objects.push(JsonValue::Object(object));
+    }
+
+    Ok(objects)
+}
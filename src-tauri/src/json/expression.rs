@@ -0,0 +1,245 @@
+use crate::json::extract::get_value_by_path;
+use serde_json::Value as JsonValue;
+
+/// Expression arithmétique simple sur des chemins JSON (`quantity * unit_price`)
+#[derive(Debug, Clone)]
+pub enum Expression {
+    Number(f64),
+    Path(String),
+    Negate(Box<Expression>),
+    Binary(Box<Expression>, char, Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Path(String),
+    Operator(char),
+    OpenParen,
+    CloseParen,
+}
+
+/// Découpe une expression en jetons (nombres, chemins, opérateurs, parenthèses)
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).map_or(false, |n| n.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = literal
+                .parse::<f64>()
+                .map_err(|_| format!("Nombre invalide dans l'expression: '{}'", literal))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.' | '[' | ']'))
+            {
+                i += 1;
+            }
+            tokens.push(Token::Path(chars[start..i].iter().collect()));
+        } else if matches!(c, '+' | '-' | '*' | '/') {
+            tokens.push(Token::Operator(c));
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::OpenParen);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::CloseParen);
+            i += 1;
+        } else {
+            return Err(format!("Caractère inattendu dans l'expression: '{}'", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Analyseur à descente récursive: expr := term (('+'|'-') term)*, term := factor (('*'|'/') factor)*
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, String> {
+        let mut left = self.parse_term()?;
+
+        while let Some(Token::Operator(op)) = self.peek() {
+            let op = *op;
+            if op != '+' && op != '-' {
+                break;
+            }
+            self.next();
+            let right = self.parse_term()?;
+            left = Expression::Binary(Box::new(left), op, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_term(&mut self) -> Result<Expression, String> {
+        let mut left = self.parse_factor()?;
+
+        while let Some(Token::Operator(op)) = self.peek() {
+            let op = *op;
+            if op != '*' && op != '/' {
+                break;
+            }
+            self.next();
+            let right = self.parse_factor()?;
+            left = Expression::Binary(Box::new(left), op, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_factor(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expression::Number(n)),
+            Some(Token::Path(p)) => Ok(Expression::Path(p)),
+            Some(Token::Operator('-')) => Ok(Expression::Negate(Box::new(self.parse_factor()?))),
+            Some(Token::OpenParen) => {
+                let inner = self.parse_expression()?;
+                match self.next() {
+                    Some(Token::CloseParen) => Ok(inner),
+                    _ => Err("Parenthèse fermante manquante dans l'expression".to_string()),
+                }
+            }
+            Some(token) => Err(format!("Jeton inattendu dans l'expression: {:?}", token)),
+            None => Err("Fin d'expression inattendue".to_string()),
+        }
+    }
+}
+
+impl Expression {
+    /// Analyse une expression arithmétique (`+ - * /`, parenthèses, nombres, chemins JSON)
+    pub fn parse(input: &str) -> Result<Expression, String> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+
+        let expression = parser.parse_expression()?;
+        if parser.position < parser.tokens.len() {
+            return Err(format!("Expression invalide: '{}'", input));
+        }
+
+        Ok(expression)
+    }
+
+    /// Évalue l'expression sur un objet JSON et retourne une valeur numérique
+    pub fn evaluate(&self, obj: &JsonValue) -> Result<JsonValue, String> {
+        let result = self.evaluate_f64(obj)?;
+
+        // Les résultats entiers sont conservés en entier
+        if result.fract() == 0.0 && result.abs() < i64::MAX as f64 {
+            Ok(JsonValue::Number((result as i64).into()))
+        } else {
+            serde_json::Number::from_f64(result)
+                .map(JsonValue::Number)
+                .ok_or_else(|| format!("Résultat non numérique: {}", result))
+        }
+    }
+
+    fn evaluate_f64(&self, obj: &JsonValue) -> Result<f64, String> {
+        match self {
+            Expression::Number(n) => Ok(*n),
+            Expression::Path(path) => match get_value_by_path(obj, path) {
+                Some(JsonValue::Number(n)) => n
+                    .as_f64()
+                    .ok_or_else(|| format!("Valeur non numérique pour '{}'", path)),
+                Some(JsonValue::String(s)) => s
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("Valeur non numérique pour '{}': \"{}\"", path, s)),
+                Some(other) => Err(format!("Valeur non numérique pour '{}': {}", path, other)),
+                None => Err(format!("Chemin '{}' absent de l'objet", path)),
+            },
+            Expression::Negate(inner) => Ok(-inner.evaluate_f64(obj)?),
+            Expression::Binary(left, op, right) => {
+                let l = left.evaluate_f64(obj)?;
+                let r = right.evaluate_f64(obj)?;
+                match op {
+                    '+' => Ok(l + r),
+                    '-' => Ok(l - r),
+                    '*' => Ok(l * r),
+                    '/' => {
+                        if r == 0.0 {
+                            Err("Division par zéro".to_string())
+                        } else {
+                            Ok(l / r)
+                        }
+                    }
+                    _ => Err(format!("Opérateur inconnu: {}", op)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn eval(expression: &str, obj: JsonValue) -> Result<JsonValue, String> {
+        Expression::parse(expression)?.evaluate(&obj)
+    }
+
+    #[test]
+    fn multiplies_paths() {
+        let order = json!({"quantity": 3, "unit_price": 2.5, "item": {"weight": "4"}});
+        assert_eq!(eval("quantity * unit_price", order.clone()), Ok(json!(7.5)));
+        assert_eq!(eval("quantity * item.weight", order), Ok(json!(12)));
+    }
+
+    #[test]
+    fn follows_operator_precedence() {
+        let obj = json!({"a": 2, "b": 3, "c": 4});
+        assert_eq!(eval("a + b * c", obj.clone()), Ok(json!(14)));
+        assert_eq!(eval("(a + b) * c", obj.clone()), Ok(json!(20)));
+        assert_eq!(eval("c - a - 1", obj.clone()), Ok(json!(1)));
+        assert_eq!(eval("c / a / 2", obj.clone()), Ok(json!(1)));
+        assert_eq!(eval("-a * -(b + .5)", obj), Ok(json!(7)));
+    }
+
+    #[test]
+    fn invalid_operands_are_errors() {
+        let obj = json!({"a": 1, "zero": 0, "label": "x", "flag": true});
+        assert_eq!(eval("a / zero", obj.clone()), Err("Division par zéro".to_string()));
+        assert_eq!(
+            eval("a + label", obj.clone()),
+            Err("Valeur non numérique pour 'label': \"x\"".to_string())
+        );
+        assert!(eval("a * flag", obj.clone()).is_err());
+        assert_eq!(eval("a + missing", obj), Err("Chemin 'missing' absent de l'objet".into()));
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        for input in ["a +", "(a + 1", "a b", ""] {
+            assert!(Expression::parse(input).is_err(), "{}", input);
+        }
+    }
+}
@@ -1,100 +1,206 @@
-use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use crate::commands::{DerivedKey, DerivedKeyStrategy, KeyNormalization, PathProbe, PathProbeStep};
+use crate::json::canonical_json;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Profondeur maximale de normalisation des clés
+const MAX_NORMALIZE_DEPTH: usize = 10;
+
+/// Séparateur par défaut des segments d'un chemin aplati en nom de colonne (`a.b` → `a_b`)
+pub const DEFAULT_FLATTEN_SEPARATOR: &str = "_";
+
+/// Chemin du mapping désignant la clé de l'entrée courante d'une racine de type dictionnaire
+pub const MAP_KEY_TOKEN: &str = "@key";
+
+/// Remplace chaque objet dictionnaire (`{"sku123": {...}, ...}`) par ses valeurs, en ajoutant
+/// à chaque valeur objet la clé de son entrée sous `@key` (une propriété `@key` existante est
+/// remplacée). Les valeurs qui ne sont pas des objets sont conservées telles quelles.
+pub fn expand_map_entries(root_objects: Vec<JsonValue>) -> Vec<JsonValue> {
+    root_objects
+        .into_iter()
+        .flat_map(|obj| match obj {
+            JsonValue::Object(map) => map
+                .into_iter()
+                .map(|(key, value)| match value {
+                    JsonValue::Object(mut entry) => {
+                        entry.insert(MAP_KEY_TOKEN.to_string(), JsonValue::String(key));
+                        JsonValue::Object(entry)
+                    }
+                    other => other,
+                })
+                .collect(),
+            other => vec![other],
+        })
+        .collect()
+}
 
 /// Extrait les objets à la racine spécifiée dans un fichier JSON
 pub fn extract_root_objects(
     json_data: &JsonValue,
     root_path: &str,
 ) -> Result<Vec<JsonValue>, String> {
+    extract_root_objects_iter(json_data, root_path)?
+        .map(|item| item.cloned())
+        .collect()
+}
+
+/// Parcourt paresseusement les objets à la racine spécifiée, sans copier les données:
+/// chaque objet est emprunté au document au moment où il est demandé
+pub fn extract_root_objects_iter<'a>(
+    json_data: &'a JsonValue,
+    root_path: &str,
+) -> Result<RootObjectsIter<'a>, String> {
     if root_path.is_empty() {
         // Si le chemin est vide, on considère que les données sont directement à la racine
-        match json_data {
-            JsonValue::Array(arr) => Ok(arr.clone()),
-            JsonValue::Object(_) => Ok(vec![json_data.clone()]),
-            _ => Err("La racine JSON n'est ni un objet ni un tableau".to_string()),
-        }
-    } else {
-        // Parse le chemin pour extraire les segments
-        let path_segments: Vec<&str> = root_path.split('.').collect();
+        let stack = match json_data {
+            JsonValue::Array(arr) => arr.iter().rev().map(|item| (item, 0)).collect(),
+            JsonValue::Object(_) => vec![(json_data, 0)],
+            _ => return Err("La racine JSON n'est ni un objet ni un tableau".to_string()),
+        };
 
-        // Appel récursif pour extraire les objets
-        process_segment(json_data, &path_segments, 0)
+        Ok(RootObjectsIter {
+            segments: Vec::new(),
+            stack,
+        })
+    } else {
+        Ok(RootObjectsIter {
+            segments: root_path.split('.').map(|s| s.to_string()).collect(),
+            stack: vec![(json_data, 0)],
+        })
     }
 }
 
-/// Traitement récursif pour extraire les données selon le chemin spécifié
-fn process_segment(
-    data: &JsonValue,
-    segments: &[&str],
-    current_index: usize,
-) -> Result<Vec<JsonValue>, String> {
-    if current_index >= segments.len() {
-        return Ok(vec![data.clone()]);
+/// Itérateur à pile sur les objets désignés par un chemin racine (`data.items[]`, `a[].b[]`...).
+/// La pile contient les valeurs restant à explorer avec l'index du prochain segment;
+/// l'ordre du document est conservé et la première erreur de navigation interrompt le parcours.
+pub struct RootObjectsIter<'a> {
+    segments: Vec<String>,
+    stack: Vec<(&'a JsonValue, usize)>,
+}
+
+impl<'a> Iterator for RootObjectsIter<'a> {
+    type Item = Result<&'a JsonValue, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((data, index)) = self.stack.pop() {
+            if index >= self.segments.len() {
+                return Some(Ok(data));
+            }
+
+            match process_segment(data, &self.segments[index]) {
+                Ok(children) => {
+                    self.stack
+                        .extend(children.into_iter().rev().map(|child| (child, index + 1)));
+                }
+                Err(e) => {
+                    self.stack.clear();
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        None
     }
+}
 
-    let mut segment = segments[current_index];
+/// Résout un segment du chemin racine sur une valeur: la propriété elle-même,
+/// ou les éléments du tableau pour un segment en `[]`
+fn process_segment<'a>(data: &'a JsonValue, segment: &str) -> Result<Vec<&'a JsonValue>, String> {
+    let mut segment = segment;
     let is_array = segment.ends_with("[]");
 
-    if is_array {
-        // Si le segment est un tableau, on supprime les crochets de la fin
+    // Si le segment est un tableau, on supprime les crochets de la fin
+    // (un `[]` par niveau d'imbrication, ex: `matrix[][]`)
+    let mut levels = 0;
+    while segment.ends_with("[]") {
         segment = &segment[0..segment.len() - 2];
+        levels += 1;
+    }
 
-        match data {
-            JsonValue::Object(obj) => {
-                if let Some(array_value) = obj.get(segment) {
-                    match array_value {
-                        JsonValue::Array(arr) => {
-                            let mut all_results = Vec::new();
-
-                            for item in arr {
-                                if current_index == segments.len() - 1 {
-                                    // C'est le dernier segment et c'est un tableau, on ajoute chaque élément directement
-                                    all_results.push(item.clone());
-                                } else {
-                                    // C'est un tableau intermédiaire, on traite les éléments suivants
-                                    let item_results =
-                                        process_segment(item, segments, current_index + 1)?;
-                                    all_results.extend(item_results);
-                                }
-                            }
-
-                            Ok(all_results)
-                        }
-                        _ => Err(format!("La propriété '{}' n'est pas un tableau", segment)),
-                    }
-                } else {
-                    Err(format!(
-                        "La propriété '{}' n'existe pas dans les données JSON",
-                        segment
-                    ))
-                }
+    let value = match data {
+        JsonValue::Object(obj) => match obj.get(segment) {
+            Some(value) => value,
+            None => {
+                return Err(format!(
+                    "La propriété '{}' n'existe pas dans les données JSON; clés disponibles: {}",
+                    segment,
+                    available_keys(obj)
+                ))
             }
-            _ => Err(format!(
-                "Impossible de naviguer dans les données JSON: attendu un objet, trouvé {:?}",
-                data
-            )),
+        },
+        _ => {
+            return Err(format!(
+                "Impossible de naviguer vers '{}' dans les données JSON: attendu un objet, trouvé {}",
+                segment,
+                describe_value(data)
+            ))
         }
-    } else {
-        match data {
-            JsonValue::Object(obj) => {
-                if let Some(value) = obj.get(segment) {
-                    process_segment(value, segments, current_index + 1)
-                } else {
-                    Err(format!(
-                        "La propriété '{}' n'existe pas dans les données JSON",
-                        segment
-                    ))
-                }
+    };
+
+    if !is_array {
+        return Ok(vec![value]);
+    }
+
+    flatten_array_levels(value, levels).ok_or_else(|| {
+        format!(
+            "La propriété '{}' n'est pas un tableau à {} niveau(x): trouvé {}",
+            segment,
+            levels,
+            describe_value(value)
+        )
+    })
+}
+
+/// Nombre maximal de clés citées dans un message d'erreur
+const MAX_LISTED_KEYS: usize = 20;
+
+/// Liste des clés d'un objet pour les messages d'erreur (`[data, meta, ...]`)
+fn available_keys(obj: &Map<String, JsonValue>) -> String {
+    if obj.is_empty() {
+        return "aucune (objet vide)".to_string();
+    }
+
+    let mut keys: Vec<&str> = obj.keys().take(MAX_LISTED_KEYS).map(|k| k.as_str()).collect();
+    if obj.len() > MAX_LISTED_KEYS {
+        keys.push("...");
+    }
+
+    format!("[{}]", keys.join(", "))
+}
+
+/// Type d'une valeur pour les messages d'erreur (avec la taille d'un tableau,
+/// sans reproduire son contenu qui peut être volumineux)
+fn describe_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Array(items) => format!("un tableau de {} élément(s)", items.len()),
+        JsonValue::Object(obj) => format!("un objet (clés: {})", available_keys(obj)),
+        other => format!("une valeur de type {}", json_type_name(other)),
+    }
+}
+
+/// Aplatit un tableau imbriqué sur `levels` niveaux et retourne les éléments les plus internes.
+/// Retourne `None` si un niveau attendu n'est pas un tableau.
+fn flatten_array_levels(value: &JsonValue, levels: usize) -> Option<Vec<&JsonValue>> {
+    let mut current = vec![value];
+
+    for _ in 0..levels {
+        let mut next = Vec::new();
+        for item in current {
+            match item {
+                JsonValue::Array(arr) => next.extend(arr.iter()),
+                _ => return None,
             }
-            _ => Err(format!(
-                "Impossible de naviguer dans les données JSON: attendu un objet, trouvé {:?}",
-                data
-            )),
         }
+        current = next;
     }
+
+    Some(current)
 }
 
 /// Récupère la valeur à partir d'un chemin dans un objet
+/// (`None` si le chemin est absent, `Some(JsonValue::Null)` si la clé existe avec null).
+/// Un chemin sans `[]` qui aboutit à un tableau retourne le tableau complet, dans l'ordre,
+/// qui sera sérialisé en JSON lors de l'insertion dans une colonne TEXT.
 pub fn get_value_by_path(obj: &JsonValue, path: &str) -> Option<JsonValue> {
     if path.is_empty() {
         return Some(obj.clone());
@@ -119,17 +225,621 @@ pub fn get_value_by_path(obj: &JsonValue, path: &str) -> Option<JsonValue> {
     Some(current.clone())
 }
 
-/// Applique un mapping à un objet JSON pour créer un dictionnaire de colonnes/valeurs
+/// Si la résolution d'un chemin échoue parce qu'un segment intermédiaire est un tableau
+/// (`tags.name` alors que `tags` est un tableau), retourne le chemin de ce tableau
+pub fn find_array_crossing(obj: &JsonValue, path: &str) -> Option<String> {
+    let parts: Vec<&str> = path.split('.').collect();
+    let mut current = obj;
+
+    for (position, part) in parts.iter().enumerate() {
+        match current {
+            JsonValue::Object(map) => current = map.get(*part)?,
+            JsonValue::Array(_) if position > 0 => return Some(parts[..position].join(".")),
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Message expliquant qu'un chemin traverse un tableau sans notation `[]`
+pub fn array_crossing_hint(path: &str, array_path: &str) -> String {
+    format!(
+        "le chemin `{}` traverse le tableau `{}`: utilisez `{}[]` comme racine pour une ligne par élément, ou `{}` pour stocker le tableau complet",
+        path, array_path, array_path, array_path
+    )
+}
+
+/// Nom du type d'une valeur JSON, tel qu'affiché dans l'analyse de structure
+fn json_type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+/// Résout un chemin comme `get_value_by_path`, en conservant le parcours segment par segment
+/// et la raison de l'échec éventuel (champ absent, valeur non navigable)
+pub fn probe_path(obj: &JsonValue, path: &str) -> PathProbe {
+    let mut trail = Vec::new();
+    let mut reason = None;
+
+    if !path.is_empty() {
+        let mut current = obj;
+
+        for part in path.split('.') {
+            match current {
+                JsonValue::Object(map) => match map.get(part) {
+                    Some(val) => {
+                        trail.push(PathProbeStep {
+                            segment: part.to_string(),
+                            found: true,
+                            value_type: json_type_name(val).to_string(),
+                        });
+                        current = val;
+                    }
+                    None => {
+                        trail.push(PathProbeStep {
+                            segment: part.to_string(),
+                            found: false,
+                            value_type: "unknown".to_string(),
+                        });
+                        reason = Some(format!("Champ '{}' introuvable", part));
+                        break;
+                    }
+                },
+                JsonValue::Array(_) => {
+                    trail.push(PathProbeStep {
+                        segment: part.to_string(),
+                        found: false,
+                        value_type: "unknown".to_string(),
+                    });
+                    reason = find_array_crossing(obj, path)
+                        .map(|array_path| array_crossing_hint(path, &array_path));
+                    break;
+                }
+                other => {
+                    trail.push(PathProbeStep {
+                        segment: part.to_string(),
+                        found: false,
+                        value_type: "unknown".to_string(),
+                    });
+                    reason = Some(format!(
+                        "Impossible de naviguer dans '{}': la valeur précédente est de type {}",
+                        part,
+                        json_type_name(other)
+                    ));
+                    break;
+                }
+            }
+        }
+    }
+
+    PathProbe {
+        value: get_value_by_path(obj, path),
+        trail,
+        reason,
+    }
+}
+
+/// Applique un mapping à un objet JSON pour créer un dictionnaire de colonnes/valeurs.
+/// Un chemin absent donne `None`, un chemin présent à null donne `Some(JsonValue::Null)`.
+/// Si plusieurs chemins ciblent la même colonne, la première valeur non nulle
+/// (dans l'ordre alphabétique des chemins) est retenue.
 pub fn apply_mapping(
     obj: &JsonValue,
     mapping: &HashMap<String, String>,
 ) -> HashMap<String, Option<JsonValue>> {
-    let mut result = HashMap::new();
+    let mut result: HashMap<String, Option<JsonValue>> = HashMap::new();
 
-    for (json_path, column_name) in mapping {
+    let mut entries: Vec<(&String, &String)> = mapping.iter().collect();
+    entries.sort();
+
+    for (json_path, column_name) in entries {
         let value = get_value_by_path(obj, json_path);
-        result.insert(column_name.clone(), value);
+
+        // Valeur non nulle déjà retenue; un chemin présent à null l'emporte sur un chemin absent
+        let already_set = matches!(
+            result.get(column_name),
+            Some(Some(existing)) if !existing.is_null() || value.is_none()
+        );
+        if !already_set {
+            result.insert(column_name.clone(), value);
+        }
     }
 
     result
 }
+
+/// Complète un résultat de `apply_mapping` avec des chemins de repli (colonne → chemins):
+/// les chemins sont essayés dans l'ordre et la première valeur non nulle est retenue.
+/// Une valeur non nulle déjà obtenue par le mapping principal est conservée.
+pub fn apply_coalesce(
+    obj: &JsonValue,
+    mapped: &mut HashMap<String, Option<JsonValue>>,
+    coalesce: &HashMap<String, Vec<String>>,
+) {
+    for (column_name, paths) in coalesce {
+        if matches!(mapped.get(column_name), Some(Some(existing)) if !existing.is_null()) {
+            continue;
+        }
+
+        // Premier chemin non nul; à défaut, un chemin présent à null plutôt qu'absent
+        let mut found = None;
+        for path in paths {
+            match get_value_by_path(obj, path) {
+                Some(value) if !value.is_null() => {
+                    found = Some(value);
+                    break;
+                }
+                Some(value) if found.is_none() => found = Some(value),
+                _ => {}
+            }
+        }
+
+        match found {
+            Some(value) => {
+                mapped.insert(column_name.clone(), Some(value));
+            }
+            None => {
+                mapped.entry(column_name.clone()).or_insert(None);
+            }
+        }
+    }
+}
+
+/// Séparateur des valeurs d'une clé dérivée par concaténation
+const DERIVED_KEY_SEPARATOR: &str = "|";
+
+/// Clé déterministe d'un objet, calculée à partir des valeurs de `source_paths`: valeurs
+/// concaténées (texte brut pour une chaîne, JSON canonique sinon) ou empreinte FNV-1a 64 bits
+/// de leur tableau canonique, en hexadécimal. Un chemin absent ou null est une erreur.
+pub fn derive_key(obj: &JsonValue, derived_key: &DerivedKey) -> Result<String, String> {
+    let mut values = Vec::with_capacity(derived_key.source_paths.len());
+    for path in &derived_key.source_paths {
+        match get_value_by_path(obj, path) {
+            Some(value) if !value.is_null() => values.push(value),
+            _ => {
+                return Err(format!(
+                    "Clé dérivée {}: chemin `{}` absent ou null",
+                    derived_key.column, path
+                ))
+            }
+        }
+    }
+
+    Ok(match derived_key.strategy {
+        DerivedKeyStrategy::Concat => values
+            .iter()
+            .map(|value| match value {
+                JsonValue::String(text) => text.clone(),
+                other => canonical_json(other),
+            })
+            .collect::<Vec<_>>()
+            .join(DERIVED_KEY_SEPARATOR),
+        DerivedKeyStrategy::Hash => {
+            let canonical = canonical_json(&JsonValue::Array(values));
+            let hash = canonical.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            });
+            format!("{:016x}", hash)
+        }
+    })
+}
+
+/// Retourne les colonnes ciblées par plusieurs chemins JSON du mapping, avec ces chemins
+pub fn find_duplicate_targets(mapping: &HashMap<String, String>) -> Vec<(String, Vec<String>)> {
+    let mut by_column: HashMap<&String, Vec<String>> = HashMap::new();
+
+    for (json_path, column_name) in mapping {
+        by_column
+            .entry(column_name)
+            .or_default()
+            .push(json_path.clone());
+    }
+
+    let mut duplicates: Vec<(String, Vec<String>)> = by_column
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(column, mut paths)| {
+            paths.sort();
+            (column.clone(), paths)
+        })
+        .collect();
+    duplicates.sort();
+
+    duplicates
+}
+
+/// Normalise une clé JSON selon le mode demandé (`userId` → `userid` ou `user_id`)
+pub fn normalize_key(key: &str, mode: KeyNormalization) -> String {
+    match mode {
+        KeyNormalization::None => key.to_string(),
+        KeyNormalization::Lower => key.to_lowercase(),
+        KeyNormalization::Snake => {
+            let chars: Vec<char> = key.chars().collect();
+            let mut result = String::with_capacity(key.len() + 4);
+
+            for (i, c) in chars.iter().enumerate() {
+                if *c == '-' || *c == ' ' {
+                    if !result.ends_with('_') {
+                        result.push('_');
+                    }
+                    continue;
+                }
+
+                if c.is_uppercase() && i > 0 {
+                    let prev = chars[i - 1];
+                    let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+                    // Début d'un nouveau mot: `userId` ou fin d'acronyme `HTTPServer`
+                    let word_start = prev.is_lowercase()
+                        || prev.is_ascii_digit()
+                        || (prev.is_uppercase() && next_is_lower);
+                    if word_start && !result.ends_with('_') {
+                        result.push('_');
+                    }
+                }
+
+                result.extend(c.to_lowercase());
+            }
+
+            result
+        }
+    }
+}
+
+/// Normalise récursivement les clés d'une valeur JSON (jusqu'à une profondeur limitée)
+pub fn normalize_keys_in_place(value: &mut JsonValue, mode: KeyNormalization, depth: usize) {
+    if mode == KeyNormalization::None || depth > MAX_NORMALIZE_DEPTH {
+        return;
+    }
+
+    match value {
+        JsonValue::Object(map) => {
+            let entries = std::mem::take(map);
+            let mut normalized = Map::new();
+
+            for (key, mut val) in entries {
+                normalize_keys_in_place(&mut val, mode, depth + 1);
+                let new_key = normalize_key(&key, mode);
+
+                // En cas de collision après normalisation, la première valeur non nulle est conservée
+                match normalized.get(&new_key) {
+                    Some(existing) if !existing.is_null() => {}
+                    _ => {
+                        normalized.insert(new_key, val);
+                    }
+                }
+            }
+
+            *map = normalized;
+        }
+        JsonValue::Array(arr) => {
+            for item in arr.iter_mut() {
+                normalize_keys_in_place(item, mode, depth + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Construit un mapping aplati à partir des feuilles des objets (valeurs simples, tableaux et
+/// null; les objets imbriqués sont parcourus): chaque chemin `a.b.c` alimente la colonne
+/// `a{sep}b{sep}c`. Les chemins du mapping explicite sont exclus. Deux chemins donnant le même
+/// nom de colonne sont une erreur, qui liste les chemins en collision plutôt que d'en écraser
+/// un silencieusement.
+pub fn flatten_mapping(
+    objects: &[JsonValue],
+    separator: &str,
+    explicit: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, String> {
+    // Colonne → chemins qui la produisent (triés pour un message stable)
+    let mut columns: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for obj in objects {
+        if let JsonValue::Object(map) = obj {
+            collect_flatten_leaves(map, &mut Vec::new(), separator, &mut columns, 0);
+        }
+    }
+    for paths in columns.values_mut() {
+        paths.retain(|path| !explicit.contains_key(path));
+    }
+
+    let collisions: Vec<String> = columns
+        .iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(column, paths)| {
+            format!(
+                "{} ← {}",
+                column,
+                paths.iter().cloned().collect::<Vec<_>>().join(", ")
+            )
+        })
+        .collect();
+
+    if !collisions.is_empty() {
+        return Err(format!(
+            "Colonnes en collision après aplatissement: {}; choisissez un séparateur plus long \
+             (flatten_separator, ex: \"__\") ou mappez ces chemins explicitement",
+            collisions.join("; ")
+        ));
+    }
+
+    Ok(columns
+        .into_iter()
+        .filter_map(|(column, paths)| paths.into_iter().next().map(|path| (path, column)))
+        .collect())
+}
+
+fn collect_flatten_leaves(
+    map: &Map<String, JsonValue>,
+    segments: &mut Vec<String>,
+    separator: &str,
+    columns: &mut BTreeMap<String, BTreeSet<String>>,
+    depth: usize,
+) {
+    for (key, value) in map {
+        // Une clé contenant un point ne peut pas être désignée par un chemin
+        if key.contains('.') {
+            continue;
+        }
+        segments.push(key.clone());
+
+        match value {
+            JsonValue::Object(inner) if depth < MAX_NORMALIZE_DEPTH && !inner.is_empty() => {
+                collect_flatten_leaves(inner, segments, separator, columns, depth + 1);
+            }
+            _ => {
+                columns
+                    .entry(segments.join(separator))
+                    .or_default()
+                    .insert(segments.join("."));
+            }
+        }
+
+        segments.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn mapped_user_ids(
+        records: Vec<JsonValue>,
+        mode: KeyNormalization,
+        path: &str,
+    ) -> Vec<JsonValue> {
+        let mapping = HashMap::from([(path.to_string(), "user_id".to_string())]);
+        records
+            .into_iter()
+            .map(|mut record| {
+                normalize_keys_in_place(&mut record, mode, 0);
+                apply_mapping(&record, &mapping)
+                    .remove("user_id")
+                    .flatten()
+                    .unwrap_or(JsonValue::Null)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mixed_case_keys_across_records_map_to_one_column() {
+        let records = vec![
+            json!({"userId": 1}),
+            json!({"userid": 2}),
+            json!({"UserID": 3}),
+            json!({"USERID": 4}),
+        ];
+        assert_eq!(
+            mapped_user_ids(records, KeyNormalization::Lower, "userid"),
+            [json!(1), json!(2), json!(3), json!(4)]
+        );
+    }
+
+    #[test]
+    fn snake_case_keys_match_nested_paths() {
+        let records = vec![
+            json!({"Account": {"userId": 1}}),
+            json!({"account": {"user_id": 2}}),
+            json!({"ACCOUNT": {"User-Id": 3}}),
+        ];
+        assert_eq!(
+            mapped_user_ids(records, KeyNormalization::Snake, "account.user_id"),
+            [json!(1), json!(2), json!(3)]
+        );
+    }
+
+    #[test]
+    fn snake_case_splits_words_and_acronyms() {
+        let snake = |key| normalize_key(key, KeyNormalization::Snake);
+        assert_eq!(snake("userId"), "user_id");
+        assert_eq!(snake("HTTPServer"), "http_server");
+        assert_eq!(snake("address2Line"), "address2_line");
+        assert_eq!(snake("first name"), "first_name");
+        assert_eq!(snake("already_snake"), "already_snake");
+    }
+
+    #[test]
+    fn duplicate_targets_are_listed_with_sorted_paths() {
+        let mapping = HashMap::from([
+            ("mail".to_string(), "email".to_string()),
+            ("email".to_string(), "email".to_string()),
+            ("contact.email".to_string(), "email".to_string()),
+            ("id".to_string(), "id".to_string()),
+        ]);
+        assert_eq!(
+            find_duplicate_targets(&mapping),
+            [(
+                "email".to_string(),
+                vec!["contact.email".to_string(), "email".to_string(), "mail".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn duplicate_target_takes_first_non_null_path() {
+        let mapping = HashMap::from([
+            ("a".to_string(), "col".to_string()),
+            ("b".to_string(), "col".to_string()),
+        ]);
+        let first_null = apply_mapping(&json!({"a": null, "b": 2}), &mapping);
+        assert_eq!(first_null["col"], Some(json!(2)));
+        let both = apply_mapping(&json!({"a": 1, "b": 2}), &mapping);
+        assert_eq!(both["col"], Some(json!(1)));
+        let none = apply_mapping(&json!({"a": null}), &mapping);
+        assert_eq!(none["col"], Some(json!(null)));
+    }
+
+    #[test]
+    fn nested_arrays_are_flattened_to_leaf_records() {
+        let data = json!({"groups": [
+            {"members": [{"id": 1}, {"id": 2}]},
+            {"members": []},
+            {"members": [{"id": 3}]},
+        ]});
+        assert_eq!(
+            extract_root_objects(&data, "groups[].members[]").unwrap(),
+            [json!({"id": 1}), json!({"id": 2}), json!({"id": 3})]
+        );
+    }
+
+    #[test]
+    fn consecutive_brackets_flatten_each_level() {
+        let data = json!({"matrix": [[{"id": 1}, {"id": 2}], [{"id": 3}]]});
+        assert_eq!(
+            extract_root_objects(&data, "matrix[][]").unwrap(),
+            [json!({"id": 1}), json!({"id": 2}), json!({"id": 3})]
+        );
+        assert_eq!(
+            extract_root_objects(&data, "matrix[]").unwrap(),
+            [json!([{"id": 1}, {"id": 2}]), json!([{"id": 3}])]
+        );
+    }
+
+    #[test]
+    fn map_entries_carry_their_key() {
+        let root = vec![json!({"sku1": {"qty": 1}, "sku2": {"qty": 2, "@key": "x"}, "total": 3})];
+
+        let entries = expand_map_entries(root);
+
+        assert_eq!(
+            entries,
+            [json!({"qty": 1, "@key": "sku1"}), json!({"qty": 2, "@key": "sku2"}), json!(3)]
+        );
+    }
+
+    #[test]
+    fn root_errors_list_what_the_data_contains() {
+        let data = json!({"data": {"results": [1, 2], "meta": null}, "total": 2});
+
+        let error = extract_root_objects(&data, "items[]").unwrap_err();
+        assert!(error.contains("'items'"), "{}", error);
+        assert!(error.ends_with("clés disponibles: [data, total]"), "{}", error);
+
+        let error = extract_root_objects(&data, "data.records").unwrap_err();
+        assert!(error.ends_with("clés disponibles: [meta, results]"), "{}", error);
+
+        let error = extract_root_objects(&data, "total.value").unwrap_err();
+        assert!(error.contains("attendu un objet, trouvé une valeur de type"), "{}", error);
+
+        let error = extract_root_objects(&data, "data[]").unwrap_err();
+        assert!(error.ends_with("trouvé un objet (clés: [meta, results])"), "{}", error);
+
+        let error = extract_root_objects(&data, "data.results[][]").unwrap_err();
+        assert!(error.contains("pas un tableau à 2 niveau(x)"), "{}", error);
+    }
+
+    #[test]
+    fn long_key_lists_are_truncated() {
+        let wide: Map<String, JsonValue> =
+            (0..30).map(|i| (format!("k{:02}", i), json!(i))).collect();
+
+        let listed = available_keys(&wide);
+
+        assert!(listed.starts_with("[k00, k01,") && listed.ends_with("k19, ...]"), "{}", listed);
+        assert_eq!(available_keys(&Map::new()), "aucune (objet vide)");
+    }
+
+    #[test]
+    fn coalesce_keeps_the_first_non_null_path() {
+        let coalesce = HashMap::from([(
+            "email".to_string(),
+            vec!["emailAddress".to_string(), "contact.email".to_string()],
+        )]);
+        let mapping = HashMap::from([("email".to_string(), "email".to_string())]);
+        let email = |obj: JsonValue| {
+            let mut mapped = apply_mapping(&obj, &mapping);
+            apply_coalesce(&obj, &mut mapped, &coalesce);
+            mapped.remove("email").unwrap()
+        };
+
+        assert_eq!(email(json!({"email": "a@x", "emailAddress": "b@x"})), Some(json!("a@x")));
+        assert_eq!(email(json!({"email": null, "emailAddress": "b@x"})), Some(json!("b@x")));
+        let nested = json!({"emailAddress": null, "contact": {"email": "c@x"}});
+        assert_eq!(email(nested), Some(json!("c@x")));
+        assert_eq!(email(json!({"emailAddress": null})), Some(JsonValue::Null));
+        assert_eq!(email(json!({"name": "d"})), None);
+    }
+
+    #[test]
+    fn flatten_collisions_are_reported() {
+        let objects = [json!({"a": {"b_c": 1}, "a_b": {"c": 2}, "d": {"e": null}})];
+
+        let error = flatten_mapping(&objects, "_", &HashMap::new()).unwrap_err();
+        let expected = "Colonnes en collision après aplatissement: a_b_c ← a.b_c, a_b.c;";
+        assert!(error.starts_with(expected), "{}", error);
+
+        let mapping = flatten_mapping(&objects, "__", &HashMap::new()).unwrap();
+        assert_eq!(mapping["a.b_c"], "a__b_c");
+        assert_eq!(mapping["a_b.c"], "a_b__c");
+        assert_eq!(mapping["d.e"], "d__e");
+
+        let explicit = HashMap::from([("a_b.c".to_string(), "other".to_string())]);
+        let mapping = flatten_mapping(&objects, "_", &explicit).unwrap();
+        assert_eq!(mapping["a.b_c"], "a_b_c");
+        assert!(!mapping.contains_key("a_b.c"));
+    }
+
+    fn order_key(strategy: DerivedKeyStrategy) -> DerivedKey {
+        DerivedKey {
+            column: "order_key".to_string(),
+            source_paths: ["country", "order.year", "order.n"].map(String::from).to_vec(),
+            strategy,
+        }
+    }
+
+    #[test]
+    fn derived_keys_only_depend_on_source_values() {
+        let order = json!({"country": "FR", "order": {"year": 2024, "n": 42}, "note": "a"});
+        let reordered = json!({"note": "b", "order": {"n": 42, "year": 2024}, "country": "FR"});
+        let other = json!({"country": "FR", "order": {"year": 2024, "n": 43}});
+
+        let concat = order_key(DerivedKeyStrategy::Concat);
+        assert_eq!(derive_key(&order, &concat), Ok("FR|2024|42".to_string()));
+        assert_eq!(derive_key(&reordered, &concat), derive_key(&order, &concat));
+
+        let hash = order_key(DerivedKeyStrategy::Hash);
+        let key = derive_key(&order, &hash).unwrap();
+        assert_eq!(key.len(), 16);
+        assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(derive_key(&reordered, &hash), Ok(key.clone()));
+        assert_ne!(derive_key(&other, &hash), Ok(key));
+    }
+
+    #[test]
+    fn derived_key_requires_every_source_value() {
+        let key = order_key(DerivedKeyStrategy::Concat);
+        for obj in [
+            json!({"country": "FR", "order": {"year": 2024}}),
+            json!({"country": null, "order": {"year": 2024, "n": 1}}),
+        ] {
+            let error = derive_key(&obj, &key).unwrap_err();
+            assert!(error.starts_with("Clé dérivée order_key: chemin"));
+        }
+    }
+}
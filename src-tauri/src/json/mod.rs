@@ -1,144 +1,337 @@
-use crate::commands::JsonPathInfo;
+use crate::commands::{
+    JsonParseError, JsonPathInfo, NdjsonPathStats, PathProbe, UnmappedField, ValueCount,
+    ValueHistogram,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use serde_json::{json, Map, Value as JsonValue};
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::Read;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use tauri::Window;
 use tauri::Emitter;
+use uuid::Uuid;
 
+pub mod cache;
+pub mod csv_source;
+pub mod expression;
 pub mod extract;
+pub mod remote;
+pub mod stream;
+pub mod transform;
 
+use cache::read_json_file_cached;
 use extract::extract_root_objects;
+use remote::{fetch_json, is_url, HttpOptions};
+use stream::{starts_with_array, stream_nested_array, stream_top_level_array, streamable_root_keys};
 
 /// Analyse la structure d'un fichier JSON et retourne les chemins possibles
 pub fn analyze_structure(json_path: &str) -> Result<Vec<JsonPathInfo>, String> {
-    // Lecture du fichier JSON
-    let json_data = read_json_file(json_path)?;
+    // Lecture du fichier JSON (document mis en cache tant que le fichier ne change pas)
+    let json_data = read_json_file_cached(json_path)?;
 
     // Extraction des chemins JSON
     let mut paths = Vec::new();
     extract_paths("", &json_data, &mut paths, 0);
 
     // Conversion des chemins en JsonPathInfo
-    let mut result = Vec::new();
-    for path in paths {
-        let value = get_value_by_path(&json_data, &path);
-        let sample = match value {
-            Some(v) => format!("{}", v),
-            None => String::from(""),
-        };
+    let result = paths
+        .into_iter()
+        .map(|path| {
+            let value = get_value_by_path(&json_data, &path);
+            path_info(path, value)
+        })
+        .collect();
 
-        let data_type = match value {
-            Some(JsonValue::Null) => "null",
-            Some(JsonValue::Bool(_)) => "boolean",
-            Some(JsonValue::Number(_)) => "number",
-            Some(JsonValue::String(_)) => "string",
-            Some(JsonValue::Array(_)) => "array",
-            Some(JsonValue::Object(_)) => "object",
-            None => "unknown",
-        };
+    Ok(result)
+}
 
-        result.push(JsonPathInfo {
-            path,
-            data_type: data_type.to_string(),
-            sample: if sample.len() > 50 {
-                // Tronquer la chaîne de manière sécurisée pour l'UTF-8
-                let truncated_sample = truncate_utf8_string(&sample, 47);
-                format!("{}...", truncated_sample)
-            } else {
-                sample
-            },
-        });
+/// Décrit un chemin à partir de la valeur atteinte (type, type déduit et échantillon tronqué)
+fn path_info(path: String, value: Option<&JsonValue>) -> JsonPathInfo {
+    let sample = match value {
+        Some(v) => format!("{}", v),
+        None => String::from(""),
+    };
+
+    let data_type = match value {
+        Some(JsonValue::Null) => "null",
+        Some(JsonValue::Bool(_)) => "boolean",
+        Some(JsonValue::Number(_)) => "number",
+        Some(JsonValue::String(_)) => "string",
+        Some(JsonValue::Array(_)) => "array",
+        Some(JsonValue::Object(_)) => "object",
+        None => "unknown",
+    };
+
+    JsonPathInfo {
+        path,
+        data_type: data_type.to_string(),
+        inferred_type: infer_string_type(value),
+        sample: if sample.len() > 50 {
+            // Tronquer la chaîne de manière sécurisée pour l'UTF-8
+            let truncated_sample = truncate_utf8_string(&sample, 47);
+            format!("{}...", truncated_sample)
+        } else {
+            sample
+        },
     }
+}
 
-    Ok(result)
+/// Indique si le fichier est au format NDJSON (un document JSON par ligne), d'après son extension
+pub fn is_ndjson_path(json_path: &str) -> bool {
+    Path::new(json_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ndjson") || ext.eq_ignore_ascii_case("jsonl"))
 }
 
-/// Analyse la structure d'un fichier JSON et envoie les chemins progressivement via un événement
-pub fn analyze_structure_progressive(json_path: &str, window: Window) -> Result<(), String> {
-    // Lecture du fichier JSON
-    let json_data = read_json_file(json_path)?;
-    
-    // Partager json_data entre deux threads
-    let json_data = std::sync::Arc::new(json_data);
-    
-    // Clone pour le premier thread
-    let json_data_clone1 = json_data.clone();
+/// Parcourt un flux NDJSON ligne par ligne: chaque nouveau chemin est signalé dès sa première
+/// apparition (décrit avec la valeur de cette ligne), puis la présence de chaque chemin est
+/// cumulée sur toutes les lignes. Les lignes vides sont ignorées, une ligne invalide interrompt
+/// l'analyse.
+pub fn scan_ndjson_paths<R, F>(
+    reader: R,
+    cancelled: &AtomicBool,
+    mut on_path: F,
+) -> Result<Vec<NdjsonPathStats>, String>
+where
+    R: BufRead,
+    F: FnMut(JsonPathInfo),
+{
+    let mut sent_paths: HashSet<String> = HashSet::new();
+    // Chemin → (lignes avec valeur non nulle, lignes à null), dans l'ordre de découverte
+    let mut counts: Vec<(String, u32, u32)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut total_lines: u32 = 0;
+
+    for (line_number, line) in reader.lines().enumerate() {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("Analyse annulée".to_string());
+        }
+        let line = line.map_err(|e| {
+            format!("Erreur lors de la lecture de la ligne {}: {}", line_number + 1, e)
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let value: JsonValue = serde_json::from_str(&line)
+            .map_err(|e| format!("Ligne {}: {}", line_number + 1, parse_error_message(&e)))?;
+        total_lines += 1;
+
+        let mut paths = Vec::new();
+        extract_paths("", &value, &mut paths, 0);
+
+        // Un même chemin peut être produit deux fois pour une ligne (ex: tableau imbriqué)
+        let mut seen_in_line: HashSet<&str> = HashSet::new();
+        for path in &paths {
+            if !seen_in_line.insert(path) {
+                continue;
+            }
+            let found = get_value_by_path(&value, path);
+
+            if sent_paths.insert(path.clone()) {
+                on_path(path_info(path.clone(), found));
+            }
+
+            let position = *positions.entry(path.clone()).or_insert_with(|| {
+                counts.push((path.clone(), 0, 0));
+                counts.len() - 1
+            });
+            match found {
+                Some(JsonValue::Null) => counts[position].2 += 1,
+                Some(_) => counts[position].1 += 1,
+                None => {}
+            }
+        }
+    }
+
+    let ratio = |count: u32| {
+        if total_lines > 0 {
+            count as f64 / total_lines as f64
+        } else {
+            0.0
+        }
+    };
+
+    Ok(counts
+        .into_iter()
+        .map(|(path, present_lines, null_lines)| {
+            let missing_lines = total_lines - present_lines - null_lines;
+            NdjsonPathStats {
+                path,
+                present_lines,
+                null_lines,
+                missing_lines,
+                in_all_lines: present_lines == total_lines,
+                null_ratio: ratio(null_lines + missing_lines),
+                missing_ratio: ratio(missing_lines),
+            }
+        })
+        .collect())
+}
+
+/// Analyse progressive d'un fichier NDJSON, lu ligne par ligne sans le charger entièrement:
+/// les chemins sont envoyés via `json-path-discovered`, puis la présence de chaque chemin
+/// sur l'ensemble des lignes via `json-path-statistics`
+fn analyze_ndjson_progressive(json_path: &str, window: Window) -> Result<String, String> {
+    let file = File::open(json_path)
+        .map_err(|e| format!("Erreur lors de l'ouverture du fichier {}: {}", json_path, e))?;
+    let (analysis_id, cancelled) = start_analysis();
+    let id = analysis_id.clone();
 
-    // Créer un canal pour envoyer les chemins progressivement
-    let (tx, rx) = std::sync::mpsc::channel();
-    
-    // Lancer l'extraction dans un thread dédié
     std::thread::spawn(move || {
-        // Utiliser une fonction modifiée qui envoie les chemins via le canal
-        let mut sent_paths = std::collections::HashSet::new();
-        extract_paths_progressive("", &*json_data_clone1, tx, 0, &mut sent_paths);
+        let result = scan_ndjson_paths(BufReader::new(file), &cancelled, |path_info| {
+            let _ = window.emit("json-path-discovered", &path_info);
+        });
+        finish_analysis(&id);
+
+        if cancelled.load(Ordering::Relaxed) {
+            let _ = window.emit("json-path-analysis-cancelled", ());
+            return;
+        }
+        match result {
+            Ok(stats) => {
+                let _ = window.emit("json-path-statistics", &stats);
+            }
+            Err(e) => eprintln!("Erreur lors de l'analyse NDJSON: {}", e),
+        }
+
+        // Envoyer un événement de fin d'analyse
+        let _ = window.emit("json-path-analysis-complete", ());
     });
-    
-    // Clone pour le second thread
-    let json_data_clone2 = json_data.clone();
-    
-    // Clone de la window pour le second thread
-    let window_clone = window.clone();
-    
-    // Traiter les chemins reçus et les envoyer à l'interface
+
+    Ok(analysis_id)
+}
+
+/// Analyses progressives en cours: identifiant → indicateur d'annulation
+static ANALYSES: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn analyses() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    ANALYSES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Enregistre une analyse progressive: identifiant et indicateur vérifié par ses threads
+fn start_analysis() -> (String, Arc<AtomicBool>) {
+    let analysis_id = Uuid::new_v4().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    analyses()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(analysis_id.clone(), Arc::clone(&cancelled));
+    (analysis_id, cancelled)
+}
+
+/// Retire une analyse terminée (ou arrêtée) du registre
+fn finish_analysis(analysis_id: &str) {
+    analyses()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(analysis_id);
+}
+
+/// Demande l'arrêt d'une analyse progressive; ses threads s'arrêtent au prochain chemin et
+/// libèrent le document. Retourne false si l'analyse est inconnue ou déjà terminée.
+pub fn cancel_analysis(analysis_id: &str) -> bool {
+    match analyses()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(analysis_id)
+    {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Analyse la structure d'un fichier JSON et envoie les chemins progressivement via un événement.
+/// Retourne l'identifiant de l'analyse, utilisable par `cancel_analysis`.
+pub fn analyze_structure_progressive(json_path: &str, window: Window) -> Result<String, String> {
+    // NDJSON: lecture ligne par ligne, les chemins étant cumulés sur toutes les lignes
+    if is_ndjson_path(json_path) {
+        return analyze_ndjson_progressive(json_path, window);
+    }
+
+    // Lecture du fichier JSON, parcouru en arrière-plan
+    let json_data = read_json_file_cached(json_path)?;
+    let (analysis_id, cancelled) = start_analysis();
+    let id = analysis_id.clone();
+    let json_path = json_path.to_string();
+
     std::thread::spawn(move || {
         let mut count = 0;
-        for path in rx {
+        scan_document_paths(json_data, &cancelled, |path_info| {
             count += 1;
-            
-            // Extraire un échantillon de valeur pour ce chemin
-            let value = get_value_by_path(&json_data_clone2, &path);
-            let sample = match value {
-                Some(v) => format!("{}", v),
-                None => String::from(""),
-            };
+            let _ = window.emit("json-path-discovered", &path_info);
 
-            let data_type = match value {
-                Some(JsonValue::Null) => "null",
-                Some(JsonValue::Bool(_)) => "boolean",
-                Some(JsonValue::Number(_)) => "number",
-                Some(JsonValue::String(_)) => "string",
-                Some(JsonValue::Array(_)) => "array",
-                Some(JsonValue::Object(_)) => "object",
-                None => "unknown",
-            };
-            
-            let truncated_sample = if sample.len() > 50 {
-                let truncated = truncate_utf8_string(&sample, 47);
-                format!("{}...", truncated)
-            } else {
-                sample
-            };
-            
-            // Créer l'objet JsonPathInfo
-            let path_info = JsonPathInfo {
-                path,
-                data_type: data_type.to_string(),
-                sample: truncated_sample,
-            };
-            
-            // Envoyer l'événement à l'interface
-            let _ = window_clone.emit("json-path-discovered", &path_info);
-            
             // Pour éviter de surcharger l'interface, on peut regrouper les envois
             if count % 10 == 0 {
                 std::thread::sleep(std::time::Duration::from_millis(1));
             }
+        });
+        finish_analysis(&id);
+
+        // Analyse abandonnée: le document n'est plus gardé en cache pour un autre usage
+        if cancelled.load(Ordering::Relaxed) {
+            cache::evict_json_cache(&json_path);
+            let _ = window.emit("json-path-analysis-cancelled", ());
+            return;
         }
-        
+
         // Envoyer un événement de fin d'analyse
-        let _ = window_clone.emit("json-path-analysis-complete", ());
+        let _ = window.emit("json-path-analysis-complete", ());
     });
-    
-    Ok(())
+
+    Ok(analysis_id)
+}
+
+/// Parcourt un document dans un thread d'extraction et signale chaque chemin découvert
+/// (décrit avec un échantillon de valeur) jusqu'à la fin du parcours ou l'annulation. Au
+/// retour, le thread d'extraction est terminé et ne garde plus de référence au document.
+pub fn scan_document_paths<F>(
+    json_data: Arc<JsonValue>,
+    cancelled: &Arc<AtomicBool>,
+    mut on_path: F,
+) where
+    F: FnMut(JsonPathInfo),
+{
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let extracted = Arc::clone(&json_data);
+    let extraction_cancelled = Arc::clone(cancelled);
+    let extraction = std::thread::spawn(move || {
+        let mut sent_paths = std::collections::HashSet::new();
+        extract_paths_progressive(
+            "",
+            &extracted,
+            tx,
+            0,
+            &mut sent_paths,
+            &extraction_cancelled,
+        );
+    });
+
+    for path in &rx {
+        // Analyse abandonnée: le thread d'extraction s'arrête au prochain chemin
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        let value = get_value_by_path(&json_data, &path);
+        on_path(path_info(path, value));
+    }
+
+    drop(rx);
+    let _ = extraction.join();
 }
 
 /// Version modifiée d'extract_paths qui envoie les chemins via un canal
-fn extract_paths_progressive(prefix: &str, value: &JsonValue, sender: std::sync::mpsc::Sender<String>, depth: usize, sent_paths: &mut std::collections::HashSet<String>) {
-    // Limite de profondeur pour éviter les récursions infinies
-    if depth > 10 {
+fn extract_paths_progressive(prefix: &str, value: &JsonValue, sender: std::sync::mpsc::Sender<String>, depth: usize, sent_paths: &mut std::collections::HashSet<String>, cancelled: &AtomicBool) {
+    // Limite de profondeur pour éviter les récursions infinies, arrêt si l'analyse est annulée
+    if depth > 10 || cancelled.load(Ordering::Relaxed) {
         return;
     }
 
@@ -161,11 +354,16 @@ fn extract_paths_progressive(prefix: &str, value: &JsonValue, sender: std::sync:
                     format!("{}.{}", prefix, key)
                 };
 
-                extract_paths_progressive(&new_prefix, val, sender.clone(), depth + 1, sent_paths);
+                extract_paths_progressive(&new_prefix, val, sender.clone(), depth + 1, sent_paths, cancelled);
             }
         }
         JsonValue::Array(arr) => {
-            // Ajoute le chemin actuel avec notation tableau
+            // Ajoute le chemin du tableau complet (sans []) puis la notation tableau
+            if !prefix.is_empty() && !prefix.ends_with("[]") && !sent_paths.contains(prefix) {
+                let _ = sender.send(prefix.to_string());
+                sent_paths.insert(prefix.to_string());
+            }
+
             let array_path = format!("{}[]", prefix);
             if !prefix.is_empty() && !sent_paths.contains(&array_path) {
                 let _ = sender.send(array_path.clone());
@@ -187,7 +385,7 @@ fn extract_paths_progressive(prefix: &str, value: &JsonValue, sender: std::sync:
                     // Pour les tableaux imbriqués, on continue avec une nouvelle notation tableau
                     JsonValue::Array(_) => {
                         let nested_array_path = format!("{}[]", array_path);
-                        extract_paths_progressive(&array_path, &arr[0], sender.clone(), depth + 1, sent_paths);
+                        extract_paths_progressive(&array_path, &arr[0], sender.clone(), depth + 1, sent_paths, cancelled);
                     },
                     // Pour les valeurs primitives, on ne fait rien de plus car le chemin a déjà été ajouté
                     _ => {}
@@ -204,6 +402,129 @@ fn extract_paths_progressive(prefix: &str, value: &JsonValue, sender: std::sync:
     }
 }
 
+/// Analyse une date au format RFC 3339, `YYYY-MM-DD HH:MM:SS`, `YYYY-MM-DDTHH:MM:SS`
+/// ou `YYYY-MM-DD` (les dates sans fuseau sont considérées en UTC)
+pub fn parse_datetime(text: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+                .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S"))
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                        .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default())
+                })
+                .map(|naive| Utc.from_utc_datetime(&naive))
+        })
+        .ok()
+}
+
+/// Type déduit du contenu d'une chaîne (`"123"` → integer, `"true"` → boolean, ...),
+/// pour signaler les valeurs convertibles vers une colonne typée
+fn infer_string_type(value: Option<&JsonValue>) -> Option<String> {
+    let text = match value {
+        Some(JsonValue::String(s)) => s.trim(),
+        _ => return None,
+    };
+
+    let inferred = if text.is_empty() {
+        return None;
+    } else if text.parse::<i64>().is_ok() {
+        "integer"
+    } else if text.parse::<f64>().map_or(false, |n| n.is_finite())
+        && text.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+    {
+        "number"
+    } else if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") {
+        "boolean"
+    } else if parse_datetime(text).is_some() {
+        "date"
+    } else {
+        "string"
+    };
+
+    Some(inferred.to_string())
+}
+
+/// Sérialise une valeur JSON sous forme canonique (clés d'objets triées, sans espaces),
+/// pour que les objets et tableaux stockés en colonne texte soient comparables
+pub fn canonical_json(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<(&String, &JsonValue)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            let body = entries
+                .into_iter()
+                .map(|(key, val)| format!("{}:{}", JsonValue::String(key.clone()), canonical_json(val)))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{{{}}}", body)
+        }
+        JsonValue::Array(items) => format!(
+            "[{}]",
+            items.iter().map(canonical_json).collect::<Vec<_>>().join(",")
+        ),
+        other => other.to_string(),
+    }
+}
+
+/// Décode des octets en remplaçant chaque séquence UTF-8 invalide par U+FFFD;
+/// retourne le texte et le nombre de séquences remplacées
+pub fn decode_utf8_lossy(bytes: &[u8]) -> (String, usize) {
+    let mut text = String::with_capacity(bytes.len());
+    let mut replaced = 0;
+
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(chunk.valid());
+        if !chunk.invalid().is_empty() {
+            text.push(char::REPLACEMENT_CHARACTER);
+            replaced += 1;
+        }
+    }
+
+    (text, replaced)
+}
+
+/// Remplace par `null` les nombres non standard (`NaN`, `Infinity`, `-Infinity`) émis par
+/// certains producteurs JavaScript, hors des chaînes; retourne le texte et le nombre de remplacements
+pub fn replace_non_finite_numbers(text: &str) -> (String, usize) {
+    const TOKENS: [&str; 4] = ["-Infinity", "+Infinity", "Infinity", "NaN"];
+
+    let mut result = String::with_capacity(text.len());
+    let mut replaced = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if let Some(token) = TOKENS.iter().find(|token| {
+            rest.starts_with(*token)
+                && !rest[token.len()..]
+                    .starts_with(|next: char| next.is_alphanumeric() || next == '_')
+        }) {
+            result.push_str("null");
+            replaced += 1;
+            rest = &rest[token.len()..];
+            continue;
+        }
+
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    (result, replaced)
+}
+
 pub fn truncate_utf8_string(s: &str, max_chars: usize) -> String {
     if s.chars().count() <= max_chars {
         return s.to_string();
@@ -213,6 +534,128 @@ pub fn truncate_utf8_string(s: &str, max_chars: usize) -> String {
     s.chars().take(max_chars).collect()
 }
 
+/// Résout un chemin sur le premier objet de la racine, avec le détail de la navigation,
+/// pour comprendre pourquoi une colonne mappée reste vide
+pub fn probe_path(json_path: &str, json_root: &str, path: &str) -> Result<PathProbe, String> {
+    let json_data = read_json_file_cached(json_path)?;
+
+    let objects = extract_root_objects(&json_data, json_root)?;
+    let first = objects
+        .first()
+        .ok_or_else(|| format!("Aucun objet trouvé à la racine '{}'", json_root))?;
+
+    Ok(extract::probe_path(first, path))
+}
+
+/// Nombre de valeurs retournées par défaut dans un histogramme
+const DEFAULT_HISTOGRAM_TOP_N: u32 = 20;
+
+/// Compte les valeurs d'un chemin sur tous les objets de la racine; seules les
+/// `top_n` valeurs les plus fréquentes sont retournées (égalités départagées par valeur)
+pub fn value_histogram(
+    json_path: &str,
+    json_root: &str,
+    path: &str,
+    top_n: Option<u32>,
+) -> Result<ValueHistogram, String> {
+    let json_data = read_json_file_cached(json_path)?;
+
+    let mut total: u32 = 0;
+    let mut null_count: u32 = 0;
+    // Clé canonique → (première valeur rencontrée, nombre d'occurrences)
+    let mut counts: HashMap<String, (JsonValue, u32)> = HashMap::new();
+
+    for obj in extract::extract_root_objects_iter(&json_data, json_root)? {
+        let obj = obj?;
+        total += 1;
+
+        match extract::get_value_by_path(obj, path) {
+            None | Some(JsonValue::Null) => null_count += 1,
+            Some(value) => {
+                counts
+                    .entry(canonical_json(&value))
+                    .or_insert_with(|| (value, 0))
+                    .1 += 1;
+            }
+        }
+    }
+
+    let distinct_count = counts.len() as u32;
+    let mut entries: Vec<(String, (JsonValue, u32))> = counts.into_iter().collect();
+    entries.sort_by(|a, b| b.1 .1.cmp(&a.1 .1).then_with(|| a.0.cmp(&b.0)));
+    entries.truncate(top_n.unwrap_or(DEFAULT_HISTOGRAM_TOP_N) as usize);
+
+    Ok(ValueHistogram {
+        total,
+        null_count,
+        distinct_count,
+        top_values: entries
+            .into_iter()
+            .map(|(_, (value, count))| ValueCount { value, count })
+            .collect(),
+    })
+}
+
+/// Nombre d'objets examinés par défaut pour la recherche des champs non mappés
+const DEFAULT_UNMAPPED_SAMPLE_SIZE: u32 = 100;
+
+/// Liste les champs présents dans un échantillon d'objets de la racine qui ne sont couverts
+/// par aucun chemin du mapping (un chemin mappé couvre aussi ses sous-champs, et un champ dont
+/// un sous-chemin est mappé est considéré comme utilisé). Seuls les champs hors tableaux et
+/// dont la valeur n'est pas un objet sont retenus, dans l'ordre de leur première apparition.
+pub fn unmapped_fields(
+    json_path: &str,
+    json_root: &str,
+    mapping: &HashMap<String, String>,
+    sample_size: Option<u32>,
+) -> Result<Vec<UnmappedField>, String> {
+    let json_data = read_json_file_cached(json_path)?;
+    let sample_size = sample_size.unwrap_or(DEFAULT_UNMAPPED_SAMPLE_SIZE) as usize;
+
+    let is_mapped = |path: &str| {
+        mapping.keys().any(|mapped| {
+            mapped == path
+                || path.starts_with(&format!("{}.", mapped))
+                || mapped.starts_with(&format!("{}.", path))
+                || mapped.starts_with(&format!("{}[", path))
+        })
+    };
+
+    let mut fields: Vec<UnmappedField> = Vec::new();
+    for obj in extract::extract_root_objects_iter(&json_data, json_root)?.take(sample_size) {
+        let obj = obj?;
+        let mut paths = Vec::new();
+        extract_paths("", obj, &mut paths, 0);
+
+        let mut seen_in_object = HashSet::new();
+        for path in paths {
+            if path.contains("[]") || !seen_in_object.insert(path.clone()) || is_mapped(&path) {
+                continue;
+            }
+            let value = match extract::get_value_by_path(obj, &path) {
+                Some(JsonValue::Object(_)) | None => continue,
+                Some(value) => value,
+            };
+
+            match fields.iter_mut().find(|field| field.path == path) {
+                Some(field) => {
+                    field.occurrences += 1;
+                    if field.sample_value.is_null() {
+                        field.sample_value = value;
+                    }
+                }
+                None => fields.push(UnmappedField {
+                    path,
+                    sample_value: value,
+                    occurrences: 1,
+                }),
+            }
+        }
+    }
+
+    Ok(fields)
+}
+
 /// Récupère un échantillon d'objets depuis un chemin JSON
 pub fn get_sample(
     json_path: &str,
@@ -220,7 +663,7 @@ pub fn get_sample(
     limit: Option<u32>,
 ) -> Result<Vec<JsonValue>, String> {
     // Lecture du fichier JSON
-    let json_data = read_json_file(json_path)?;
+    let json_data = read_json_file_cached(json_path)?;
 
     // Extraction des objets à la racine spécifiée
     let mut objects = extract_root_objects(&json_data, json_root)?;
@@ -233,8 +676,126 @@ pub fn get_sample(
     Ok(objects)
 }
 
+/// Récupère un échantillon d'objets en les envoyant progressivement via un événement,
+/// au fur et à mesure du parsing, sans attendre la lecture complète du fichier
+pub fn get_sample_progressive(
+    json_path: &str,
+    json_root: &str,
+    limit: Option<u32>,
+    window: Window,
+) -> Result<(), String> {
+    let json_path = json_path.to_string();
+    let json_root = json_root.to_string();
+    let limit = limit.unwrap_or(10) as usize;
+
+    // Streaming pour un tableau à la racine du fichier, ou désigné par des clés simples
+    let streamable = json_root.is_empty() && starts_with_array(&json_path)?;
+    let nested_keys = streamable_root_keys(&json_root);
+
+    std::thread::spawn(move || {
+        let mut sent = 0;
+        let emit = |value: JsonValue| {
+            if sent >= limit {
+                return false;
+            }
+            let _ = window.emit("json-sample-object", &value);
+            sent += 1;
+            sent < limit
+        };
+
+        let result = if streamable {
+            stream_top_level_array(&json_path, emit).map(|_| ())
+        } else if let Some(keys) = nested_keys {
+            stream_nested_array(&json_path, &keys, emit).map(|_| ())
+        } else {
+            // Repli sur la lecture complète pour les racines imbriquées
+            get_sample(&json_path, &json_root, Some(limit as u32)).map(|objects| {
+                for value in objects {
+                    let _ = window.emit("json-sample-object", &value);
+                }
+            })
+        };
+
+        if let Err(e) = result {
+            let _ = window.emit("json-sample-error", &e);
+        }
+
+        // Envoyer un événement de fin d'échantillonnage
+        let _ = window.emit("json-sample-complete", ());
+    });
+
+    Ok(())
+}
+
+/// Message d'une erreur serde_json sans le suffixe de position (" at line X column Y")
+fn parse_error_message(error: &serde_json::Error) -> String {
+    let full = error.to_string();
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+    full.strip_suffix(&suffix).unwrap_or(&full).to_string()
+}
+
+/// Formate une erreur de parsing JSON en indiquant la ligne et la colonne
+pub fn format_parse_error(error: &serde_json::Error) -> String {
+    format!(
+        "Erreur lors du parsing JSON (ligne {}, colonne {}): {}",
+        error.line(),
+        error.column(),
+        parse_error_message(error)
+    )
+}
+
+/// Vérifie qu'un fichier JSON est bien formé, sans construire sa représentation en mémoire
+pub fn validate_json_file(json_path: &str) -> Result<(), JsonParseError> {
+    let file = File::open(json_path).map_err(|e| JsonParseError {
+        line: 0,
+        column: 0,
+        message: format!("Erreur lors de l'ouverture du fichier {}: {}", json_path, e),
+    })?;
+
+    serde_json::from_reader::<_, serde::de::IgnoredAny>(std::io::BufReader::new(file))
+        .map(|_| ())
+        .map_err(|e| JsonParseError {
+            line: e.line(),
+            column: e.column(),
+            message: parse_error_message(&e),
+        })
+}
+
+/// Taille des blocs lus entre deux rapports d'avancement de `read_file_with_progress`
+const READ_PROGRESS_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Lit un fichier par blocs en rapportant les octets lus et la taille totale après chaque bloc
+pub fn read_file_with_progress<F>(file_path: &str, mut on_progress: F) -> Result<Vec<u8>, String>
+where
+    F: FnMut(u64, u64),
+{
+    let mut file = File::open(file_path)
+        .map_err(|e| format!("Erreur lors de l'ouverture du fichier {}: {}", file_path, e))?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    let mut bytes = Vec::with_capacity(total as usize);
+    let mut chunk = vec![0u8; READ_PROGRESS_CHUNK_BYTES];
+    loop {
+        let n = file
+            .read(&mut chunk)
+            .map_err(|e| format!("Erreur lors de la lecture du fichier {}: {}", file_path, e))?;
+        if n == 0 {
+            break;
+        }
+        bytes.extend_from_slice(&chunk[..n]);
+        on_progress(bytes.len() as u64, total);
+    }
+
+    Ok(bytes)
+}
+
 /// Lit un fichier JSON et retourne sa représentation en mémoire
-fn read_json_file(file_path: &str) -> Result<JsonValue, String> {
+pub fn read_json_file(file_path: &str) -> Result<JsonValue, String> {
+    // Source distante: téléchargement avec les options par défaut
+    if is_url(file_path) {
+        return fetch_json(file_path, &HttpOptions::default());
+    }
+
     let path = Path::new(file_path);
 
     // Ouvrir le fichier
@@ -247,7 +808,7 @@ fn read_json_file(file_path: &str) -> Result<JsonValue, String> {
         .map_err(|e| format!("Erreur lors de la lecture du fichier {}: {}", file_path, e))?;
 
     // Parser le JSON
-    serde_json::from_str(&content).map_err(|e| format!("Erreur lors du parsing JSON: {}", e))
+    serde_json::from_str(&content).map_err(|e| format_parse_error(&e))
 }
 
 /// Extrait les chemins possibles à partir d'une valeur JSON
@@ -276,7 +837,10 @@ fn extract_paths(prefix: &str, value: &JsonValue, paths: &mut Vec<String>, depth
             }
         }
         JsonValue::Array(arr) => {
-            // Ajoute le chemin actuel avec notation tableau
+            // Ajoute le chemin du tableau complet (sans []) puis la notation tableau
+            if !prefix.is_empty() && !prefix.ends_with("[]") {
+                paths.push(prefix.to_string());
+            }
             if !prefix.is_empty() {
                 paths.push(format!("{}[]", prefix));
             }
@@ -346,3 +910,155 @@ fn get_value_by_path<'a>(obj: &'a JsonValue, path: &str) -> Option<&'a JsonValue
 
     Some(current)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::temp_json;
+    use serde_json::json;
+
+    #[test]
+    fn cancellation_stops_path_emission() {
+        let lines = "{\"a\": 1}\n{\"b\": 2}\n{\"c\": 3}\n";
+        let cancelled = AtomicBool::new(false);
+        let mut emitted = Vec::new();
+
+        let result = scan_ndjson_paths(lines.as_bytes(), &cancelled, |path_info| {
+            emitted.push(path_info.path);
+            cancelled.store(true, Ordering::Relaxed);
+        });
+
+        assert_eq!(result.unwrap_err(), "Analyse annulée");
+        assert_eq!(emitted, ["a"]);
+    }
+
+    #[test]
+    fn cancelled_document_scan_stops_and_releases_the_document() {
+        let document = Arc::new(json!({"a": {"b": 1, "c": [{"d": 2}]}, "e": "f", "g": null}));
+        let released = Arc::downgrade(&document);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut emitted = Vec::new();
+
+        scan_document_paths(document, &cancelled, |path_info| {
+            emitted.push(path_info.path);
+            cancelled.store(true, Ordering::Relaxed);
+        });
+
+        assert_eq!(emitted, ["a"]);
+        assert!(released.upgrade().is_none());
+    }
+
+    #[test]
+    fn document_scan_emits_every_path() {
+        let document = Arc::new(json!({"a": {"b": 1}, "c": [{"d": 2}]}));
+        let mut emitted = Vec::new();
+
+        scan_document_paths(document, &Arc::new(AtomicBool::new(false)), |path_info| {
+            emitted.push(path_info.path)
+        });
+
+        assert_eq!(emitted, ["a", "a.b", "c", "c[]", "c[].d"]);
+    }
+
+    #[test]
+    fn permuted_keys_serialize_identically() {
+        let first: JsonValue =
+            serde_json::from_str(r#"{"b": 1, "a": {"y": [{"q": 1, "p": 2}], "x": null}}"#).unwrap();
+        let second: JsonValue =
+            serde_json::from_str(r#"{"a": {"x": null, "y": [{"p": 2, "q": 1}]}, "b": 1}"#).unwrap();
+
+        assert_eq!(canonical_json(&first), canonical_json(&second));
+        assert_eq!(canonical_json(&first), r#"{"a":{"x":null,"y":[{"p":2,"q":1}]},"b":1}"#);
+    }
+
+    #[test]
+    fn canonical_keys_are_escaped() {
+        let value: JsonValue = serde_json::from_str(r#"{"a\"b": "c\nd"}"#).unwrap();
+        assert_eq!(canonical_json(&value), r#"{"a\"b":"c\nd"}"#);
+    }
+
+    #[test]
+    fn non_finite_numbers_are_replaced_outside_strings() {
+        let text = r#"[NaN, -Infinity, {"a": Infinity, "b": "NaN \" Infinity", "NaNa": 1}]"#;
+
+        let (replaced, count) = replace_non_finite_numbers(text);
+
+        assert_eq!(count, 3);
+        assert_eq!(replaced, r#"[null, null, {"a": null, "b": "NaN \" Infinity", "NaNa": 1}]"#);
+    }
+
+    #[test]
+    fn ndjson_paths_are_unioned_across_lines() {
+        let lines = [
+            r#"{"id": 1, "user": {"name": "a"}}"#,
+            "",
+            r#"{"id": 2, "user": {"name": null}}"#,
+            r#"{"id": 3, "extra": true}"#,
+            r#"{"id": 4, "user": {"name": "d"}}"#,
+        ]
+        .join("\n");
+        let mut emitted = Vec::new();
+
+        let stats = scan_ndjson_paths(lines.as_bytes(), &AtomicBool::new(false), |info| {
+            emitted.push(info.path)
+        })
+        .unwrap();
+
+        let unique: HashSet<&String> = emitted.iter().collect();
+        assert_eq!(unique.len(), emitted.len());
+        assert_eq!(emitted.len(), stats.len());
+        let stat = |path: &str| stats.iter().find(|s| s.path == path).unwrap();
+
+        assert!(stat("id").in_all_lines);
+        let name = stat("user.name");
+        assert_eq!((name.present_lines, name.null_lines, name.missing_lines), (2, 1, 1));
+        assert_eq!((name.null_ratio, name.missing_ratio), (0.5, 0.25));
+        let extra = stat("extra");
+        assert_eq!((extra.present_lines, extra.missing_lines), (1, 3));
+        assert!(!extra.in_all_lines);
+    }
+
+    #[test]
+    fn invalid_ndjson_line_is_reported_with_its_number() {
+        let lines = "{\"id\": 1}\n\n{\"id\": }\n";
+
+        let error = scan_ndjson_paths(lines.as_bytes(), &AtomicBool::new(false), |_| {});
+
+        assert!(error.unwrap_err().starts_with("Ligne 3:"));
+    }
+
+    #[test]
+    fn unmapped_fields_are_listed_with_a_sample_value() {
+        let json_path = temp_json(
+            r#"{"data": [
+                {"id": 1, "name": "a", "note": null, "user": {"city": "x", "zip": "1"}},
+                {"id": 2, "name": "b", "note": "vu", "tags": ["t"], "extra": true},
+                {"id": 3, "name": "c", "extra": false}
+            ]}"#,
+        );
+        let mapping: HashMap<String, String> = [("id", "id"), ("name", "name"), ("user.city", "c")]
+            .iter()
+            .map(|(path, column)| (path.to_string(), column.to_string()))
+            .collect();
+
+        let fields = unmapped_fields(&json_path, "data[]", &mapping, None).unwrap();
+
+        let found: Vec<_> = fields
+            .iter()
+            .map(|f| (f.path.as_str(), f.sample_value.clone(), f.occurrences))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("note", json!("vu"), 2),
+                ("user.zip", json!("1"), 1),
+                ("extra", json!(true), 2),
+                ("tags", json!(["t"]), 1),
+            ]
+        );
+
+        let sampled = unmapped_fields(&json_path, "data[]", &mapping, Some(1)).unwrap();
+        let paths: Vec<&str> = sampled.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["note", "user.zip"]);
+    }
+}
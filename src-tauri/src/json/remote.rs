@@ -0,0 +1,69 @@
+use crate::json::format_parse_error;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Délai d'attente par défaut d'une requête HTTP, en secondes
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Options de lecture d'une source JSON distante
+#[derive(Debug, Clone, Default)]
+pub struct HttpOptions {
+    pub timeout_secs: Option<u64>,
+    /// En-têtes ajoutés à la requête (ex: `Authorization: Bearer ...`)
+    pub headers: Option<HashMap<String, String>>,
+}
+
+/// Indique si la source JSON est une URL `http://` ou `https://` plutôt qu'un fichier
+pub fn is_url(source: &str) -> bool {
+    let lower = source.trim_start().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Télécharge et parse un document JSON distant; le corps de la réponse est lu directement
+/// par le parser, sans copie intermédiaire.
+/// La requête s'exécute dans un thread dédié: le client HTTP bloquant ne peut pas être
+/// utilisé depuis le runtime asynchrone des commandes Tauri.
+pub fn fetch_json(url: &str, options: &HttpOptions) -> Result<JsonValue, String> {
+    let url = url.trim().to_string();
+    let options = options.clone();
+
+    std::thread::spawn(move || fetch_json_blocking(&url, &options))
+        .join()
+        .map_err(|_| "Erreur interne lors de la requête HTTP".to_string())?
+}
+
+fn fetch_json_blocking(url: &str, options: &HttpOptions) -> Result<JsonValue, String> {
+    let timeout = Duration::from_secs(options.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Erreur lors de l'initialisation du client HTTP: {}", e))?;
+
+    let mut request = client.get(url);
+    if let Some(ref headers) = options.headers {
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+    }
+
+    let response = request.send().map_err(|e| {
+        if e.is_timeout() {
+            format!(
+                "Délai d'attente dépassé ({} s) pour {}",
+                timeout.as_secs(),
+                url
+            )
+        } else {
+            format!("Erreur lors de la requête HTTP vers {}: {}", url, e)
+        }
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Réponse HTTP {} pour {}", status, url));
+    }
+
+    serde_json::from_reader(response).map_err(|e| format_parse_error(&e))
+}
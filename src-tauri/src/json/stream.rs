@@ -0,0 +1,243 @@
+use crate::json::format_parse_error;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{Deserializer, Value as JsonValue};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+/// Adaptateur de lecture qui transforme un tableau JSON de premier niveau en flux de
+/// valeurs séparées par des espaces, lisible par `StreamDeserializer`.
+/// Le `[` d'ouverture, les virgules de premier niveau et le `]` final sont remplacés par
+/// des espaces, sans toucher au contenu des chaînes ni des valeurs imbriquées.
+struct TopLevelArrayReader<R: Read> {
+    inner: R,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    finished: bool,
+}
+
+impl<R: Read> TopLevelArrayReader<R> {
+    fn new(inner: R) -> Self {
+        TopLevelArrayReader {
+            inner,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            finished: false,
+        }
+    }
+}
+
+impl<R: Read> Read for TopLevelArrayReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.finished {
+            return Ok(0);
+        }
+
+        let n = self.inner.read(buf)?;
+
+        for (i, byte) in buf[..n].iter_mut().enumerate() {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if *byte == b'\\' {
+                    self.escaped = true;
+                } else if *byte == b'"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            match *byte {
+                b'"' => self.in_string = true,
+                b'[' | b'{' => {
+                    self.depth += 1;
+                    // Crochet ouvrant du tableau racine
+                    if self.depth == 1 && *byte == b'[' {
+                        *byte = b' ';
+                    }
+                }
+                b']' | b'}' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    // Fin du tableau racine: on ignore tout ce qui suit
+                    if self.depth == 0 && *byte == b']' {
+                        *byte = b' ';
+                        self.finished = true;
+                        return Ok(i + 1);
+                    }
+                }
+                b',' if self.depth == 1 => *byte = b' ',
+                _ => {}
+            }
+        }
+
+        Ok(n)
+    }
+}
+
+/// Vérifie que le premier caractère significatif du fichier ouvre un tableau
+pub fn starts_with_array(file_path: &str) -> Result<bool, String> {
+    let file = File::open(file_path)
+        .map_err(|e| format!("Erreur lors de l'ouverture du fichier {}: {}", file_path, e))?;
+
+    for byte in BufReader::new(file).bytes() {
+        let byte =
+            byte.map_err(|e| format!("Erreur lors de la lecture du fichier {}: {}", file_path, e))?;
+        if !byte.is_ascii_whitespace() {
+            return Ok(byte == b'[');
+        }
+    }
+
+    Ok(false)
+}
+
+/// Parcourt les éléments d'un tableau JSON de premier niveau au fur et à mesure du parsing,
+/// sans charger le fichier entier en mémoire.
+/// Le callback retourne `false` pour interrompre la lecture.
+pub fn stream_top_level_array<F>(file_path: &str, mut on_element: F) -> Result<usize, String>
+where
+    F: FnMut(JsonValue) -> bool,
+{
+    let file = File::open(file_path)
+        .map_err(|e| format!("Erreur lors de l'ouverture du fichier {}: {}", file_path, e))?;
+
+    let reader = TopLevelArrayReader::new(BufReader::new(file));
+    let stream = Deserializer::from_reader(reader).into_iter::<JsonValue>();
+
+    let mut count = 0;
+    for value in stream {
+        let value = value.map_err(|e| format_parse_error(&e))?;
+        count += 1;
+
+        if !on_element(value) {
+            break;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Clés menant au tableau d'un chemin racine lisible en streaming: clés d'objets simples
+/// terminées par un unique tableau (ex: `response.data.records[]` → `response`, `data`,
+/// `records`). `None` pour les chemins qui traversent plusieurs tableaux (`a[].b[]`).
+pub fn streamable_root_keys(json_root: &str) -> Option<Vec<String>> {
+    let segments: Vec<&str> = json_root.split('.').collect();
+    let (last, parents) = segments.split_last()?;
+    let array_key = last.strip_suffix("[]")?;
+
+    let mut keys: Vec<String> = Vec::with_capacity(segments.len());
+    for key in parents.iter().chain(std::iter::once(&array_key)) {
+        if key.is_empty() || key.contains('[') {
+            return None;
+        }
+        keys.push(key.to_string());
+    }
+
+    Some(keys)
+}
+
+/// État partagé du parcours d'un tableau imbriqué
+struct NestedStream<F> {
+    on_element: F,
+    count: usize,
+    found: bool,
+    stopped: bool,
+}
+
+/// Valeur à la position `keys` du document: objet traversé clé par clé (les autres valeurs
+/// sont parcourues sans être construites), puis tableau dont les éléments sont transmis
+struct NestedPath<'s, F> {
+    keys: &'s [String],
+    stream: &'s mut NestedStream<F>,
+}
+
+impl<'de, F: FnMut(JsonValue) -> bool> DeserializeSeed<'de> for NestedPath<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        if self.keys.is_empty() {
+            deserializer.deserialize_seq(self)
+        } else {
+            deserializer.deserialize_map(self)
+        }
+    }
+}
+
+impl<'de, F: FnMut(JsonValue) -> bool> Visitor<'de> for NestedPath<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.keys.first() {
+            Some(key) => write!(formatter, "un objet contenant la clé {}", key),
+            None => write!(formatter, "un tableau"),
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.keys[0] && !self.stream.found {
+                map.next_value_seed(NestedPath {
+                    keys: &self.keys[1..],
+                    stream: &mut *self.stream,
+                })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        self.stream.found = true;
+        while let Some(value) = seq.next_element::<JsonValue>()? {
+            self.stream.count += 1;
+            if !(self.stream.on_element)(value) {
+                // Arrêt demandé: la lecture est interrompue par une erreur ignorée ensuite
+                self.stream.stopped = true;
+                return Err(de::Error::custom("lecture interrompue"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parcourt les éléments d'un tableau imbriqué (désigné par `keys`, voir
+/// `streamable_root_keys`) en une seule passe de parsing: les objets englobants sont traversés
+/// sans être construits, seuls les éléments du tableau le sont, un par un.
+/// Le callback retourne `false` pour interrompre la lecture.
+pub fn stream_nested_array<F>(
+    file_path: &str,
+    keys: &[String],
+    on_element: F,
+) -> Result<usize, String>
+where
+    F: FnMut(JsonValue) -> bool,
+{
+    let file = File::open(file_path)
+        .map_err(|e| format!("Erreur lors de l'ouverture du fichier {}: {}", file_path, e))?;
+
+    let mut stream = NestedStream {
+        on_element,
+        count: 0,
+        found: false,
+        stopped: false,
+    };
+    let mut deserializer = Deserializer::from_reader(BufReader::new(file));
+    let result = NestedPath {
+        keys,
+        stream: &mut stream,
+    }
+    .deserialize(&mut deserializer);
+
+    match result {
+        Err(_) if stream.stopped => Ok(stream.count),
+        Err(e) => Err(format_parse_error(&e)),
+        Ok(()) if !stream.found => Err(format!(
+            "Chemin racine '{}[]' introuvable dans {}",
+            keys.join("."),
+            file_path
+        )),
+        Ok(()) => Ok(stream.count),
+    }
+}
@@ -0,0 +1,51 @@
+use crate::commands::Transform;
+use serde_json::Value as JsonValue;
+
+impl Transform {
+    /// Nom de l'étape, utilisé dans les messages d'erreur
+    pub fn name(&self) -> &'static str {
+        match self {
+            Transform::Trim => "trim",
+            Transform::Upper => "upper",
+            Transform::Lower => "lower",
+            Transform::Truncate(_) => "truncate",
+            Transform::NullIfEmpty => "null_if_empty",
+        }
+    }
+
+    /// Applique l'étape à une valeur; null traverse toutes les étapes sans modification
+    pub fn apply(&self, value: JsonValue) -> Result<JsonValue, String> {
+        let text = match value {
+            JsonValue::Null => return Ok(JsonValue::Null),
+            JsonValue::String(s) => s,
+            other => return Err(format!("chaîne attendue, valeur reçue: {}", other)),
+        };
+
+        let result = match self {
+            Transform::Trim => JsonValue::String(text.trim().to_string()),
+            Transform::Upper => JsonValue::String(text.to_uppercase()),
+            Transform::Lower => JsonValue::String(text.to_lowercase()),
+            Transform::Truncate(length) => JsonValue::String(text.chars().take(*length).collect()),
+            Transform::NullIfEmpty => {
+                if text.is_empty() {
+                    JsonValue::Null
+                } else {
+                    JsonValue::String(text)
+                }
+            }
+        };
+
+        Ok(result)
+    }
+}
+
+/// Applique les étapes de gauche à droite; l'erreur indique l'étape (position et nom) en échec
+pub fn apply_transforms(value: JsonValue, steps: &[Transform]) -> Result<JsonValue, String> {
+    steps
+        .iter()
+        .enumerate()
+        .try_fold(value, |current, (position, step)| {
+            step.apply(current)
+                .map_err(|e| format!("étape {} ({}): {}", position + 1, step.name(), e))
+        })
+}
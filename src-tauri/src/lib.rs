@@ -3,8 +3,12 @@ mod db;
 mod json;
 
 use commands::{
-    db_analyze_table, db_get_tables, import_json_to_sqlite, json_analyze_structure, json_analyze_structure_progressive,
-    json_get_sample, update_sqlite_from_json,
+    cancel_analysis, check_conflicts, create_table_from_json, db_analyze_table, db_get_tables,
+    db_table_counts, estimate_import, export_schema, export_table_to_ndjson, get_import_history,
+    get_provenance, import_csv_to_sqlite, import_json_files_to_sqlite, import_json_to_sqlite,
+    json_analyze_structure, json_analyze_structure_progressive, json_clear_cache, json_get_sample,
+    json_get_sample_progressive, json_probe_path, json_validate, json_value_histogram,
+    propose_import, report_unmapped_fields, update_sqlite_from_json,
 };
 
 pub fn run() {
@@ -13,12 +17,30 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
             db_get_tables,
+            db_table_counts,
             db_analyze_table,
             json_analyze_structure,
             json_analyze_structure_progressive,
+            cancel_analysis,
+            json_validate,
             json_get_sample,
+            json_get_sample_progressive,
+            json_probe_path,
+            json_value_histogram,
+            report_unmapped_fields,
+            json_clear_cache,
             import_json_to_sqlite,
+            import_json_files_to_sqlite,
+            check_conflicts,
+            estimate_import,
+            import_csv_to_sqlite,
+            create_table_from_json,
+            propose_import,
             update_sqlite_from_json,
+            export_table_to_ndjson,
+            export_schema,
+            get_import_history,
+            get_provenance,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -6,6 +6,7 @@ import { UnlistenFn } from '@tauri-apps/api/event';
 interface JsonPathInfo {
   path: string;
   data_type: string;
+  inferred_type?: string | null;
   sample: string;
 }
 
@@ -48,6 +49,9 @@ const JsonPathExplorer: React.FC<JsonPathExplorerProps> = ({
   useEffect(() => {
     let unlisten: Promise<UnlistenFn> | null = null;
     let unlisten2: Promise<UnlistenFn> | null = null;
+    // Identifiant de l'analyse en cours, pour l'arrêter si le fichier change ou au démontage
+    let analysisId: string | null = null;
+    let disposed = false;
     
     if (!jsonFilePath) return;
     
@@ -118,9 +122,12 @@ const JsonPathExplorer: React.FC<JsonPathExplorerProps> = ({
         await setupListener();
         
         // Puis lancer l'analyse progressive
-        await invoke('json_analyze_structure_progressive', { 
+        analysisId = await invoke<string>('json_analyze_structure_progressive', { 
           jsonPath: jsonFilePath 
         });
+        if (disposed) {
+          invoke('cancel_analysis', { analysisId });
+        }
       } catch (err) {
         setError(`Erreur d'analyse du fichier JSON: ${err instanceof Error ? err.message : String(err)}`);
         setIsLoading(false);
@@ -138,6 +145,10 @@ const JsonPathExplorer: React.FC<JsonPathExplorerProps> = ({
     
     // Nettoyage lors du démontage du composant
     return () => {
+      disposed = true;
+      if (analysisId) {
+        invoke('cancel_analysis', { analysisId });
+      }
       if (unlisten) {
         unlisten.then(fn => fn());
       }
@@ -258,7 +269,7 @@ const JsonPathExplorer: React.FC<JsonPathExplorerProps> = ({
                 }`}
                 style={{ paddingLeft: getIndentation(pathInfo.path) + 8 }}
                 onClick={() => onPathSelect(pathInfo.path)}
-                title={`Type: ${pathInfo.data_type}, Exemple: ${pathInfo.sample}`}
+                title={`Type: ${pathInfo.data_type}${pathInfo.inferred_type && pathInfo.inferred_type !== 'string' ? ` (${pathInfo.inferred_type})` : ''}, Exemple: ${pathInfo.sample}`}
               >
                 <div className="flex items-center">
                   <span className={`mr-2 ${
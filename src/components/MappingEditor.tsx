@@ -18,6 +18,7 @@ interface MappingEditorProps {
 interface JsonPathInfo {
   path: string;
   data_type: string;
+  inferred_type?: string | null;
   sample: string;
 }
 
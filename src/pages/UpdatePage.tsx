@@ -23,11 +23,22 @@ interface TableInfo {
   unique_constraints: string[][];
 }
 
+type ImportPhase =
+  | 'preparing'
+  | 'analyzing_schema'
+  | 'inserting'
+  | 'exporting'
+  | 'committing'
+  | 'done'
+  | 'cancelled'
+  | 'failed';
+
 interface ImportProgress {
   total: number;
   processed: number;
   succeeded: number;
   failed: number;
+  phase?: ImportPhase;
   status: string;
 }
 